@@ -0,0 +1,193 @@
+//! Command line access to the games maker and the club database, for scripting
+//! and testing without the app window.
+
+use lib::commands::clubs::list_clubs;
+use lib::commands::games_maker::{generate_round, random_seed, PairingHistory};
+use lib::commands::import::import_players;
+use lib::database::run_migrations;
+use lib::models::{GamesOptions, ImportOptions, InGamePlayer};
+use lib::services::backup::backup_to;
+use lib::utils::config::DATABASE_PATH_ENV;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::SqlitePool;
+use std::collections::HashSet;
+use std::path::Path;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: courtctl [--db PATH] <command>
+
+Commands:
+  clubs list                                  List the clubs and their member counts
+  players import --club ID FILE [--dry-run]   Add the players in a CSV file to a club
+  round --players FILE --courts N [--seed N] [--options FILE]
+                                              Make a round of games and print it as JSON
+  backup PATH                                 Save a copy of the database to PATH
+
+The database is the one given by --db, or by BADMINTON_DB_PATH when --db is left out.";
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let mut args = Args(std::env::args().skip(1).collect());
+
+    if args.0.is_empty() || args.flag("--help") || args.flag("-h") {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    match run(args).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("courtctl: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(mut args: Args) -> Result<(), String> {
+    let db_path = args.option("--db")?;
+    let words: Vec<String> = args.0.iter().take(2).cloned().collect();
+    let command: Vec<&str> = words.iter().map(String::as_str).collect();
+
+    match command.as_slice() {
+        ["clubs", "list"] => {
+            args.words(2)?;
+            let db = open_database(db_path).await?;
+
+            for club in list_clubs(&db).await.map_err(|e| e.to_string())? {
+                println!("{}\t{}\t{} members", club.id, club.name, club.member_count);
+            }
+        }
+        ["players", "import"] => {
+            let club_id = parse_number(&args.required("--club")?, "--club")?;
+            let dry_run = args.flag("--dry-run");
+            let file = args.words(3)?.remove(2);
+            let csv_text = std::fs::read_to_string(&file).map_err(|e| format!("Failed to read {}: {}", file, e))?;
+            let db = open_database(db_path).await?;
+
+            let options = ImportOptions { dry_run, ..ImportOptions::default() };
+            let report = import_players(&db, None, club_id, &csv_text, options).await.map_err(|e| e.to_string())?;
+
+            println!(
+                "{} {}, {} {}, {} rejected",
+                if dry_run { "Would create" } else { "Created" },
+                report.created.len(),
+                if dry_run { "would update" } else { "updated" },
+                report.updated.len(),
+                report.rejected.len()
+            );
+            for row in &report.rejected {
+                println!("  line {}: {}", row.line, row.message);
+            }
+        }
+        ["round", ..] => {
+            let players_file = args.required("--players")?;
+            let courts = parse_number(&args.required("--courts")?, "--courts")?;
+            let seed = args.option("--seed")?.map(|seed| parse_number(&seed, "--seed")).transpose()?;
+            let options_file = args.option("--options")?;
+            args.words(1)?;
+
+            let players: Vec<InGamePlayer> = read_json(&players_file)?;
+            let options: GamesOptions = match options_file {
+                Some(file) => read_json(&file)?,
+                None => GamesOptions::default(),
+            };
+
+            let round = generate_round(
+                players,
+                courts,
+                &HashSet::new(),
+                &PairingHistory::default(),
+                &options,
+                seed.unwrap_or_else(random_seed),
+            );
+            println!("{}", serde_json::to_string_pretty(&round).map_err(|e| e.to_string())?);
+        }
+        ["backup", ..] => {
+            let path = args.words(2)?.remove(1);
+            let db = open_database(db_path).await?;
+
+            backup_to(&db, Path::new(&path)).await.map_err(|e| e.to_string())?;
+            println!("Backed up to {}", path);
+        }
+        _ => return Err(format!("Unknown command '{}', see courtctl --help", args.0.join(" "))),
+    }
+
+    Ok(())
+}
+
+/// Opens an existing database and brings its schema up to date, the same as
+/// the app does on start up.
+async fn open_database(db_path: Option<String>) -> Result<SqlitePool, String> {
+    let path = db_path
+        .or_else(|| std::env::var(DATABASE_PATH_ENV).ok().filter(|path| !path.is_empty()))
+        .ok_or_else(|| format!("No database given, pass --db PATH or set {}", DATABASE_PATH_ENV))?;
+
+    if !Path::new(&path).exists() {
+        return Err(format!("There is no database at {}", path));
+    }
+
+    let db = SqlitePoolOptions::new()
+        .connect_with(SqliteConnectOptions::new().filename(&path))
+        .await
+        .map_err(|e| format!("Failed to open {}: {}", path, e))?;
+
+    run_migrations(&db).await.map_err(|e| format!("Failed to update {}: {}", path, e))?;
+
+    Ok(db)
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(file: &str) -> Result<T, String> {
+    let text = std::fs::read_to_string(file).map_err(|e| format!("Failed to read {}: {}", file, e))?;
+    serde_json::from_str(&text).map_err(|e| format!("Failed to read {}: {}", file, e))
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str, option: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("{} must be a whole number, not '{}'", option, value))
+}
+
+/// The arguments still to be used. Options are taken out by name as they are
+/// read, leaving the command words behind.
+struct Args(Vec<String>);
+
+impl Args {
+    fn option(&mut self, name: &str) -> Result<Option<String>, String> {
+        let Some(index) = self.0.iter().position(|arg| arg == name) else {
+            return Ok(None);
+        };
+
+        if index + 1 >= self.0.len() {
+            return Err(format!("{} needs a value", name));
+        }
+
+        let value = self.0.remove(index + 1);
+        self.0.remove(index);
+        Ok(Some(value))
+    }
+
+    fn required(&mut self, name: &str) -> Result<String, String> {
+        self.option(name)?.ok_or_else(|| format!("{} is required", name))
+    }
+
+    fn flag(&mut self, name: &str) -> bool {
+        let index = self.0.iter().position(|arg| arg == name);
+        if let Some(index) = index {
+            self.0.remove(index);
+        }
+        index.is_some()
+    }
+
+    /// The command words, once every option has been taken, checking there
+    /// are exactly `count` of them and nothing unrecognised is left.
+    fn words(self, count: usize) -> Result<Vec<String>, String> {
+        if let Some(unknown) = self.0.iter().find(|arg| arg.starts_with("--")) {
+            return Err(format!("Unknown option {}", unknown));
+        }
+
+        if self.0.len() != count {
+            return Err(format!("Expected {} words in '{}', see courtctl --help", count, self.0.join(" ")));
+        }
+
+        Ok(self.0)
+    }
+}
@@ -0,0 +1,17 @@
+use crate::models::{AppState, AuditEntry, AuditLogFilters, ClubRole};
+use crate::services::audit::audit_log;
+use crate::services::auth::require_club_role;
+use crate::utils::errors::AppError;
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_audit_log(
+    club_id: i64,
+    filters: Option<AuditLogFilters>,
+    token: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<AuditEntry>, AppError> {
+    require_club_role(&state.db, &state.logins, token.as_deref(), club_id, ClubRole::Viewer).await?;
+
+    audit_log(&state.db, club_id, &filters.unwrap_or_default()).await
+}
@@ -0,0 +1,74 @@
+use crate::models::{AppState, ClubRole, LoginResponse, User};
+use crate::services::auth::{log_in, register, require_club_role};
+use crate::utils::errors::AppError;
+use sqlx::Row;
+use tauri::State;
+
+#[tauri::command]
+pub async fn register_user(username: String, password: String, state: State<'_, AppState>) -> Result<User, AppError> {
+    register(&state.db, &username, &password).await
+}
+
+#[tauri::command]
+pub async fn login(username: String, password: String, state: State<'_, AppState>) -> Result<LoginResponse, AppError> {
+    log_in(&state.db, &state.logins, &username, &password).await
+}
+
+#[tauri::command]
+pub async fn logout(token: String, state: State<'_, AppState>) -> Result<(), AppError> {
+    state.logins.end(&token);
+
+    Ok(())
+}
+
+/// Gives an account a role in the club, or takes its role away when `role` is
+/// missing. Only the club's owners can do this.
+#[tauri::command]
+pub async fn set_club_role(
+    club_id: i64,
+    username: String,
+    role: Option<ClubRole>,
+    token: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    require_club_role(&state.db, &state.logins, token.as_deref(), club_id, ClubRole::Owner).await?;
+
+    let club = sqlx::query("SELECT id FROM clubs WHERE id = ?")
+        .bind(club_id)
+        .fetch_optional(&state.db)
+        .await?;
+
+    if club.is_none() {
+        return Err(AppError::not_found("Club not found"));
+    }
+
+    let user_id: i64 = sqlx::query("SELECT id FROM users WHERE username = ?")
+        .bind(username.trim())
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("There is no account called {}", username.trim())))?
+        .get("id");
+
+    match role {
+        Some(role) => {
+            sqlx::query(
+                "INSERT INTO club_roles (user_id, club_id, role) VALUES (?, ?, ?)
+                 ON CONFLICT (user_id, club_id) DO UPDATE SET role = excluded.role"
+            )
+            .bind(user_id)
+            .bind(club_id)
+            .bind(role.as_str())
+            .execute(&state.db)
+            .await?;
+        }
+        None => {
+            sqlx::query("DELETE FROM club_roles WHERE user_id = ? AND club_id = ?")
+                .bind(user_id)
+                .bind(club_id)
+                .execute(&state.db)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
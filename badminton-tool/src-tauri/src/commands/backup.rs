@@ -0,0 +1,33 @@
+use crate::models::AppState;
+use crate::services::auth::require_admin;
+use crate::services::backup::{backup_to, restore_from};
+use crate::utils::errors::AppError;
+use std::path::Path;
+use tauri::State;
+
+/// Saves a copy of the whole database to `path`. The file mustn't exist yet.
+#[tauri::command]
+pub async fn backup_database(path: String, state: State<'_, AppState>) -> Result<(), AppError> {
+    if path.trim().is_empty() {
+        return Err(AppError::validation("path", "Choose where to save the backup"));
+    }
+
+    backup_to(&state.db, Path::new(&path)).await
+}
+
+/// Replaces all data with the backup at `path`, which can come from this or an
+/// older release of the app. Only an admin can do this, and everyone has to log
+/// in again afterwards against the accounts in the backup.
+#[tauri::command]
+pub async fn restore_database(path: String, token: Option<String>, state: State<'_, AppState>) -> Result<(), AppError> {
+    require_admin(&state.db, &state.logins, token.as_deref()).await?;
+
+    if path.trim().is_empty() {
+        return Err(AppError::validation("path", "Choose a backup to restore"));
+    }
+
+    restore_from(&state.db, Path::new(&path)).await?;
+    state.logins.clear();
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use crate::models::{AppState, BoardSnapshot};
+use crate::services::board::{start_server, stop_server};
+use crate::utils::errors::AppError;
+use tauri::State;
+
+/// Starts serving the court board and self check-in to phones on the hall
+/// network, on a bind address such as `0.0.0.0:8080`. Returns the address it
+/// listens on.
+#[tauri::command]
+pub async fn start_board_server(bind_address: String, state: State<'_, AppState>) -> Result<String, AppError> {
+    let address = start_server(state.db.clone(), state.board.clone(), &bind_address).await?;
+
+    Ok(address.to_string())
+}
+
+#[tauri::command]
+pub async fn stop_board_server(state: State<'_, AppState>) -> Result<(), AppError> {
+    stop_server(&state.board).await;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_board_server_address(state: State<'_, AppState>) -> Result<Option<String>, AppError> {
+    Ok(state.board.server_address().map(|address| address.to_string()))
+}
+
+/// What the phones currently see.
+#[tauri::command]
+pub async fn get_board(state: State<'_, AppState>) -> Result<BoardSnapshot, AppError> {
+    Ok(state.board.snapshot())
+}
+
+/// Keeps the board's timer in step with the one on screen. Call it whenever
+/// the timer is started, paused or changed.
+#[tauri::command]
+pub async fn set_board_timer(
+    remaining_seconds: u32,
+    running: bool,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    state.board.set_timer(remaining_seconds, running);
+
+    Ok(())
+}
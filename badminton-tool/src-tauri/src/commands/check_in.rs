@@ -0,0 +1,52 @@
+use crate::models::{AppState, CheckInLink, ClubRole};
+use crate::services::auth::require_club_role;
+use crate::services::board::reachable_address;
+use crate::services::check_in::{running_session_club, set_pin};
+use crate::utils::errors::AppError;
+use qrcode::render::svg;
+use qrcode::QrCode;
+use sqlx::Row;
+use tauri::State;
+
+/// Sets the PIN a player checks themselves in with. A missing PIN removes it,
+/// leaving check-in to the organisers.
+#[tauri::command]
+pub async fn set_player_pin(
+    player_id: i64,
+    pin: Option<String>,
+    token: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let club_id: i64 = sqlx::query("SELECT club_id FROM players WHERE id = ?")
+        .bind(player_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::not_found("Player not found"))?
+        .get("club_id");
+
+    // The PIN works at every club the player belongs to, so it's up to their home club
+    require_club_role(&state.db, &state.logins, token.as_deref(), club_id, ClubRole::Organiser).await?;
+
+    set_pin(&state.db, player_id, pin.as_deref()).await
+}
+
+/// The address of the session's check-in page on the court board server, and a
+/// QR code of it for players to scan. The server has to be running.
+#[tauri::command]
+pub async fn get_check_in_link(session_id: i64, state: State<'_, AppState>) -> Result<CheckInLink, AppError> {
+    running_session_club(&state.db, session_id).await?;
+
+    let address = state.board.server_address().ok_or_else(|| AppError::Conflict {
+        field: None,
+        message: "Start the court board server so phones can reach the check-in page".to_string(),
+    })?;
+
+    let url = format!("http://{}/check-in?session={}", reachable_address(address), session_id);
+    let qr_svg = QrCode::new(url.as_bytes())
+        .map_err(|e| AppError::validation("session_id", format!("Couldn't make a QR code: {}", e)))?
+        .render::<svg::Color>()
+        .min_dimensions(240, 240)
+        .build();
+
+    Ok(CheckInLink { url, qr_svg })
+}
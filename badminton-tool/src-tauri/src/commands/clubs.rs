@@ -1,22 +1,29 @@
 use crate::models::AppState;
 use crate::models::Club;
-use crate::models::CreateClubRequest;
-use sqlx::Row;
+use crate::models::{skill_level_for_rating, AuditAction, AuditChange, AuditEntity, ClubRole, CreateClubRequest, User};
+use crate::services::audit::{club_snapshot, player_snapshot, record};
+use crate::services::auth::{logged_in_user, require_club_role};
+use crate::utils::errors::AppError;
+use sqlx::{Row, SqlitePool};
 use tauri::State;
 
 #[tauri::command]
-pub async fn get_clubs(state: State<'_, AppState>) -> Result<Vec<Club>, String> {
+pub async fn get_clubs(state: State<'_, AppState>) -> Result<Vec<Club>, AppError> {
+    list_clubs(&state.db).await
+}
+
+/// Every club with its count of active members, ordered by name.
+pub async fn list_clubs(db: &SqlitePool) -> Result<Vec<Club>, AppError> {
     let rows = sqlx::query(
-        "SELECT c.id, c.name, c.description, 
-         COALESCE(COUNT(cm.user_id), 0) as member_count
-         FROM clubs c 
-         LEFT JOIN club_members cm ON c.id = cm.club_id 
-         GROUP BY c.id, c.name, c.description
+        "SELECT c.id, c.name, c.description, c.auto_skill_level,
+         COUNT(cm.player_id) as member_count
+         FROM clubs c
+         LEFT JOIN club_memberships cm ON c.id = cm.club_id AND cm.status = 'Active'
+         GROUP BY c.id, c.name, c.description, c.auto_skill_level
          ORDER BY c.name",
     )
-    .fetch_all(&state.db)
-    .await
-    .map_err(|e| format!("Database error: {}", e))?;
+    .fetch_all(db)
+    .await?;
 
     let clubs = rows
         .into_iter()
@@ -24,6 +31,7 @@ pub async fn get_clubs(state: State<'_, AppState>) -> Result<Vec<Club>, String>
             id: row.get("id"),
             name: row.get("name"),
             member_count: row.get::<i64, _>("member_count") as i32,
+            auto_skill_level: row.get("auto_skill_level"),
         })
         .collect();
 
@@ -31,63 +39,286 @@ pub async fn get_clubs(state: State<'_, AppState>) -> Result<Vec<Club>, String>
 }
 
 #[tauri::command]
-pub async fn get_club_by_id(club_id: i64, state: State<'_, AppState>) -> Result<Club, String> {
-    let row = sqlx::query("SELECT id, name FROM clubs WHERE id = ?")
-        .bind(club_id)
-        .fetch_optional(&state.db)
-        .await
-        .map_err(|e| format!("Database error: {}", e))?;
+pub async fn get_club_by_id(club_id: i64, state: State<'_, AppState>) -> Result<Club, AppError> {
+    let row = sqlx::query(
+        "SELECT id, name, auto_skill_level,
+         (SELECT COUNT(*) FROM club_memberships WHERE club_id = clubs.id AND status = 'Active') as member_count
+         FROM clubs WHERE id = ?"
+    )
+    .bind(club_id)
+    .fetch_optional(&state.db)
+    .await?;
 
     match row {
         Some(row) => Ok(Club {
             id: row.get("id"),
             name: row.get("name"),
-            member_count: 0,
+            member_count: row.get::<i64, _>("member_count") as i32,
+            auto_skill_level: row.get("auto_skill_level"),
         }),
-        None => Err("Club not found".into()),
+        None => Err(AppError::not_found("Club not found")),
     }
 }
 
 #[tauri::command]
 pub async fn create_club(
     request: CreateClubRequest,
+    token: Option<String>,
     state: State<'_, AppState>,
-) -> Result<Club, String> {
-    println!("Creating club: {:?}", request);
-    let result = sqlx::query("INSERT INTO clubs (name) VALUES (?) RETURNING id")
+) -> Result<Club, AppError> {
+    // Whoever is logged in owns the new club
+    let creator = logged_in_user(&state.db, &state.logins, token.as_deref()).await?;
+
+    add_club(&state.db, creator.as_ref(), request).await
+}
+
+/// Creates a club, making `creator` its owner when someone is logged in.
+pub async fn add_club(db: &SqlitePool, creator: Option<&User>, request: CreateClubRequest) -> Result<Club, AppError> {
+    if request.name.trim().is_empty() {
+        return Err(AppError::validation("name", "Club name cannot be empty"));
+    }
+
+    // Start a transaction
+    let mut tx = db.begin().await?;
+
+    let result = sqlx::query("INSERT INTO clubs (name, created_by) VALUES (?, ?) RETURNING id")
         .bind(&request.name)
-        .fetch_one(&state.db)
-        .await
-        .map_err(|e| format!("Failed to create club: {}", e))?;
+        .bind(creator.map(|user| user.id))
+        .fetch_one(&mut *tx)
+        .await?;
 
     let club_id: i64 = result.get("id");
 
+    if let Some(creator) = creator {
+        sqlx::query("INSERT INTO club_roles (user_id, club_id, role) VALUES (?, ?, ?)")
+            .bind(creator.id)
+            .bind(club_id)
+            .bind(ClubRole::Owner.as_str())
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    let after = club_snapshot(&mut tx, club_id).await?;
+    record(
+        &mut tx,
+        creator,
+        AuditChange {
+            action: AuditAction::Create,
+            entity: AuditEntity::Club,
+            entity_id: club_id,
+            club_id,
+            before: None,
+            after,
+        },
+    )
+    .await?;
+
+    // Commit the transaction
+    tx.commit().await?;
+
     Ok(Club {
         id: club_id,
         name: request.name,
-        member_count: 1,
+        member_count: 0,
+        auto_skill_level: false,
     })
 }
 
 #[tauri::command]
-pub async fn delete_club(club_id: i64, state: State<'_, AppState>) -> Result<(), String> {
-    // Delete club members first (foreign key constraint)
-    sqlx::query("DELETE FROM club_members WHERE club_id = ?")
+pub async fn delete_club(club_id: i64, token: Option<String>, state: State<'_, AppState>) -> Result<(), AppError> {
+    require_club_role(&state.db, &state.logins, token.as_deref(), club_id, ClubRole::Organiser).await?;
+    let actor = logged_in_user(&state.db, &state.logins, token.as_deref()).await?;
+
+    remove_club(&state.db, actor.as_ref(), club_id).await
+}
+
+/// Deletes a club with its memberships and the players who only belonged to it.
+pub async fn remove_club(db: &SqlitePool, actor: Option<&User>, club_id: i64) -> Result<(), AppError> {
+    // Start a transaction
+    let mut tx = db.begin().await?;
+
+    let before = club_snapshot(&mut tx, club_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Club not found"))?;
+
+    let home_players: Vec<i64> = sqlx::query_scalar("SELECT id FROM players WHERE club_id = ? ORDER BY id")
         .bind(club_id)
-        .execute(&state.db)
-        .await
-        .map_err(|e| format!("Failed to delete club members: {}", e))?;
+        .fetch_all(&mut *tx)
+        .await?;
+
+    let mut player_befores = Vec::with_capacity(home_players.len());
+    for player_id in home_players {
+        player_befores.push((player_id, player_snapshot(&mut tx, player_id).await?));
+    }
+
+    // Players whose home club this is but who belong elsewhere move to their
+    // longest-standing other club, rather than being deleted along with it
+    sqlx::query(
+        "UPDATE players SET club_id = (
+             SELECT cm.club_id FROM club_memberships cm
+             WHERE cm.player_id = players.id AND cm.club_id != ? AND cm.status = 'Active'
+             ORDER BY cm.joined_at, cm.club_id LIMIT 1
+         )
+         WHERE club_id = ? AND EXISTS (
+             SELECT 1 FROM club_memberships cm
+             WHERE cm.player_id = players.id AND cm.club_id != ? AND cm.status = 'Active'
+         )"
+    )
+    .bind(club_id)
+    .bind(club_id)
+    .bind(club_id)
+    .execute(&mut *tx)
+    .await?;
+
+    // Log the players who moved and those about to go with the club
+    for (player_id, player_before) in player_befores {
+        let home_club_id: i64 = sqlx::query_scalar("SELECT club_id FROM players WHERE id = ?")
+            .bind(player_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        let change = if home_club_id == club_id {
+            AuditChange {
+                action: AuditAction::Delete,
+                entity: AuditEntity::Player,
+                entity_id: player_id,
+                club_id,
+                before: player_before,
+                after: None,
+            }
+        } else {
+            AuditChange {
+                action: AuditAction::Update,
+                entity: AuditEntity::Player,
+                entity_id: player_id,
+                club_id: home_club_id,
+                before: player_before,
+                after: player_snapshot(&mut tx, player_id).await?,
+            }
+        };
+        record(&mut tx, actor, change).await?;
+    }
 
-    // Delete the club
+    // Delete the club, its memberships and the players who only belonged to it
     let result = sqlx::query("DELETE FROM clubs WHERE id = ?")
         .bind(club_id)
-        .execute(&state.db)
-        .await
-        .map_err(|e| format!("Failed to delete club: {}", e))?;
+        .execute(&mut *tx)
+        .await?;
 
     if result.rows_affected() == 0 {
-        return Err("Club not found".to_string());
+        return Err(AppError::not_found("Club not found"));
     }
 
+    record(
+        &mut tx,
+        actor,
+        AuditChange {
+            action: AuditAction::Delete,
+            entity: AuditEntity::Club,
+            entity_id: club_id,
+            club_id,
+            before: Some(before),
+            after: None,
+        },
+    )
+    .await?;
+
+    // Commit the transaction
+    tx.commit().await?;
+
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Turns on or off letting ratings drive the skill levels of a club's members.
+/// Turning it on brings every rated member's skill level into line straight away.
+#[tauri::command]
+pub async fn set_auto_skill_level(
+    club_id: i64,
+    enabled: bool,
+    token: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    require_club_role(&state.db, &state.logins, token.as_deref(), club_id, ClubRole::Organiser).await?;
+    let actor = logged_in_user(&state.db, &state.logins, token.as_deref()).await?;
+
+    change_auto_skill_level(&state.db, actor.as_ref(), club_id, enabled).await
+}
+
+/// Sets the club's auto skill level, logging the club and every member whose
+/// skill level it changes.
+pub async fn change_auto_skill_level(db: &SqlitePool, actor: Option<&User>, club_id: i64, enabled: bool) -> Result<(), AppError> {
+    // Start a transaction
+    let mut tx = db.begin().await?;
+
+    let before = club_snapshot(&mut tx, club_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Club not found"))?;
+
+    sqlx::query("UPDATE clubs SET auto_skill_level = ? WHERE id = ?")
+        .bind(enabled)
+        .bind(club_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let after = club_snapshot(&mut tx, club_id).await?;
+    record(
+        &mut tx,
+        actor,
+        AuditChange {
+            action: AuditAction::Update,
+            entity: AuditEntity::Club,
+            entity_id: club_id,
+            club_id,
+            before: Some(before),
+            after,
+        },
+    )
+    .await?;
+
+    if enabled {
+        let rows = sqlx::query(
+            "SELECT p.id, p.skill_level, p.rating FROM players p
+             JOIN club_memberships cm ON cm.player_id = p.id
+             WHERE cm.club_id = ? AND cm.status = 'Active' AND p.rating IS NOT NULL"
+        )
+        .bind(club_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for row in rows {
+            let player_id: i64 = row.get("id");
+            let skill_level = skill_level_for_rating(row.get("rating"));
+            if skill_level == row.get::<i32, _>("skill_level") {
+                continue;
+            }
+
+            let before = player_snapshot(&mut tx, player_id).await?;
+
+            sqlx::query("UPDATE players SET skill_level = ? WHERE id = ?")
+                .bind(skill_level)
+                .bind(player_id)
+                .execute(&mut *tx)
+                .await?;
+
+            let after = player_snapshot(&mut tx, player_id).await?;
+            record(
+                &mut tx,
+                actor,
+                AuditChange {
+                    action: AuditAction::Update,
+                    entity: AuditEntity::Player,
+                    entity_id: player_id,
+                    club_id,
+                    before,
+                    after,
+                },
+            )
+            .await?;
+        }
+    }
+
+    // Commit the transaction
+    tx.commit().await?;
+
+    Ok(())
+}
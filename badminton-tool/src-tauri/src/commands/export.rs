@@ -0,0 +1,666 @@
+use crate::commands::results::results_from_rows;
+use crate::models::{
+    AppState, AuditAction, AuditChange, AuditEntity, Club, ClubCsvExport, ClubRole, ClubExport, ExportedAttendee, ExportedClub, ExportedGame, ExportedGamePlayer,
+    ExportedMembership, ExportedPlayer, ExportedRatingChange, ExportedResult, ExportedRound, ExportedSession, Gender,
+    MembershipRole, MembershipStatus, Team, User, CLUB_EXPORT_VERSION,
+};
+use crate::services::audit::{club_snapshot, player_snapshot, record};
+use crate::services::auth::{has_organisers, logged_in_user};
+use crate::utils::errors::AppError;
+use sqlx::{Row, SqlitePool};
+use std::collections::{HashMap, HashSet};
+use tauri::State;
+
+/// Joins a table `x` with a `game_id` column to the club the game was played at.
+const GAMES_OF_CLUB: &str = "JOIN round_games rg ON rg.id = x.game_id
+     JOIN rounds r ON r.id = rg.round_id
+     JOIN sessions s ON s.id = r.session_id
+     WHERE s.club_id = ?";
+
+/// The club's players, sessions, rounds and results as a JSON document that
+/// `import_club_json` can read back.
+#[tauri::command]
+pub async fn export_club_json(club_id: i64, state: State<'_, AppState>) -> Result<String, AppError> {
+    let export = build_club_export(&state.db, club_id).await?;
+    serde_json::to_string_pretty(&export).map_err(|e| AppError::Io(format!("Failed to write JSON: {}", e)))
+}
+
+/// The club's players, sessions and games as one CSV each.
+#[tauri::command]
+pub async fn export_club_csv(club_id: i64, state: State<'_, AppState>) -> Result<ClubCsvExport, AppError> {
+    let export = build_club_export(&state.db, club_id).await?;
+    club_export_to_csv(&export)
+}
+
+/// Creates a club from a document made by `export_club_json`. Players whose
+/// email is already known are linked to rather than created again. Once there
+/// are organisers this needs a login, and whoever imports the club owns it.
+#[tauri::command]
+pub async fn import_club_json(json_text: String, token: Option<String>, state: State<'_, AppState>) -> Result<Club, AppError> {
+    let importer = logged_in_user(&state.db, &state.logins, token.as_deref()).await?;
+    if importer.is_none() && has_organisers(&state.db).await? {
+        return Err(AppError::Unauthorized("Log in to do that".to_string()));
+    }
+
+    let export = parse_club_export(&json_text)?;
+    import_club_export(&state.db, &export, importer.as_ref()).await
+}
+
+pub async fn build_club_export(db: &SqlitePool, club_id: i64) -> Result<ClubExport, AppError> {
+    let club = sqlx::query("SELECT name, description, auto_skill_level, CURRENT_TIMESTAMP as exported_at FROM clubs WHERE id = ?")
+        .bind(club_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| AppError::not_found("Club not found"))?;
+
+    // Members past and present, and anyone else who came to the club's sessions
+    // or is named in one of its rounds
+    let player_rows = sqlx::query(&format!(
+        "SELECT p.id, p.first_name, p.last_name, p.email, p.gender, p.skill_level, p.rating, p.rating_deviation,
+         cm.role, cm.status, cm.joined_at
+         FROM players p
+         LEFT JOIN club_memberships cm ON cm.player_id = p.id AND cm.club_id = ?
+         WHERE cm.club_id IS NOT NULL OR p.id IN (
+             SELECT sp.player_id FROM session_players sp
+             JOIN sessions s ON s.id = sp.session_id
+             WHERE s.club_id = ?
+             UNION
+             SELECT x.player_id FROM round_game_players x
+             {}
+             UNION
+             SELECT rso.player_id FROM round_sit_outs rso
+             JOIN rounds r ON r.id = rso.round_id
+             JOIN sessions s ON s.id = r.session_id
+             WHERE s.club_id = ?
+         )
+         ORDER BY p.email",
+        GAMES_OF_CLUB
+    ))
+    .bind(club_id)
+    .bind(club_id)
+    .bind(club_id)
+    .bind(club_id)
+    .fetch_all(db)
+    .await?;
+
+    let mut numbers: HashMap<i64, i64> = HashMap::new();
+    let players: Vec<ExportedPlayer> = player_rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let number = i as i64 + 1;
+            numbers.insert(row.get("id"), number);
+
+            let role: Option<String> = row.get("role");
+            ExportedPlayer {
+                number,
+                first_name: row.get("first_name"),
+                last_name: row.get("last_name"),
+                email: row.get("email"),
+                gender: Gender::from_db(row.get("gender")),
+                skill_level: row.get("skill_level"),
+                rating: row.get("rating"),
+                rating_deviation: row.get("rating_deviation"),
+                membership: role.map(|role| ExportedMembership {
+                    role: MembershipRole::from_db(&role),
+                    status: MembershipStatus::from_db(row.get("status")),
+                    joined_at: row.get("joined_at"),
+                }),
+            }
+        })
+        .collect();
+    let number = |player_id: i64| {
+        numbers
+            .get(&player_id)
+            .copied()
+            .ok_or_else(|| AppError::Database(format!("Player {} is missing from the export", player_id)))
+    };
+
+    let session_rows = sqlx::query(
+        "SELECT id, court_count, started_at, ended_at FROM sessions WHERE club_id = ? ORDER BY started_at, id"
+    )
+    .bind(club_id)
+    .fetch_all(db)
+    .await?;
+
+    let attendee_rows = sqlx::query(
+        "SELECT sp.session_id, sp.player_id, sp.sit_out_count, sp.games_played, sp.last_sat_out_round,
+         sp.checked_in_at, sp.checked_out_at
+         FROM session_players sp
+         JOIN sessions s ON s.id = sp.session_id
+         WHERE s.club_id = ?"
+    )
+    .bind(club_id)
+    .fetch_all(db)
+    .await?;
+
+    let round_rows = sqlx::query(
+        "SELECT r.id, r.session_id, r.round_number, r.seed, r.created_at FROM rounds r
+         JOIN sessions s ON s.id = r.session_id
+         WHERE s.club_id = ?
+         ORDER BY r.round_number"
+    )
+    .bind(club_id)
+    .fetch_all(db)
+    .await?;
+
+    let sit_out_rows = sqlx::query(
+        "SELECT rso.round_id, rso.player_id FROM round_sit_outs rso
+         JOIN rounds r ON r.id = rso.round_id
+         JOIN sessions s ON s.id = r.session_id
+         WHERE s.club_id = ?"
+    )
+    .bind(club_id)
+    .fetch_all(db)
+    .await?;
+
+    let game_rows = sqlx::query(
+        "SELECT rg.id, rg.round_id, rg.court, rg.started_at, rg.finished_at FROM round_games rg
+         JOIN rounds r ON r.id = rg.round_id
+         JOIN sessions s ON s.id = r.session_id
+         WHERE s.club_id = ?
+         ORDER BY rg.court, rg.id"
+    )
+    .bind(club_id)
+    .fetch_all(db)
+    .await?;
+
+    let game_player_rows = sqlx::query(&format!(
+        "SELECT x.game_id, x.player_id, x.position, x.team FROM round_game_players x
+         {}
+         ORDER BY x.position",
+        GAMES_OF_CLUB
+    ))
+    .bind(club_id)
+    .fetch_all(db)
+    .await?;
+
+    let result_rows = sqlx::query(&format!(
+        "SELECT x.game_id, gr.winner, gr.recorded_at, x.team_a_score, x.team_b_score FROM game_result_sets x
+         JOIN game_results gr ON gr.game_id = x.game_id
+         {}
+         ORDER BY x.game_id, x.set_number",
+        GAMES_OF_CLUB
+    ))
+    .bind(club_id)
+    .fetch_all(db)
+    .await?;
+
+    let rating_rows = sqlx::query(&format!(
+        "SELECT x.game_id, x.player_id, x.rating_before, x.deviation_before, x.rating_after, x.deviation_after
+         FROM rating_history x
+         {}
+         ORDER BY x.id",
+        GAMES_OF_CLUB
+    ))
+    .bind(club_id)
+    .fetch_all(db)
+    .await?;
+
+    let mut results = results_from_rows(&result_rows);
+
+    let mut rating_changes: HashMap<i64, Vec<ExportedRatingChange>> = HashMap::new();
+    for row in &rating_rows {
+        rating_changes.entry(row.get("game_id")).or_default().push(ExportedRatingChange {
+            player: number(row.get("player_id"))?,
+            rating_before: row.get("rating_before"),
+            deviation_before: row.get("deviation_before"),
+            rating_after: row.get("rating_after"),
+            deviation_after: row.get("deviation_after"),
+        });
+    }
+
+    let mut game_players: HashMap<i64, Vec<ExportedGamePlayer>> = HashMap::new();
+    for row in &game_player_rows {
+        game_players.entry(row.get("game_id")).or_default().push(ExportedGamePlayer {
+            player: number(row.get("player_id"))?,
+            position: row.get("position"),
+            team: Team::from_db(row.get("team")),
+        });
+    }
+
+    let mut games: HashMap<i64, Vec<ExportedGame>> = HashMap::new();
+    for row in &game_rows {
+        let game_id: i64 = row.get("id");
+        games.entry(row.get("round_id")).or_default().push(ExportedGame {
+            court: row.get("court"),
+            started_at: row.get("started_at"),
+            finished_at: row.get("finished_at"),
+            players: game_players.remove(&game_id).unwrap_or_default(),
+            result: results.remove(&game_id).map(|result| ExportedResult {
+                winner: result.winner,
+                recorded_at: result.recorded_at,
+                sets: result.sets,
+                rating_changes: rating_changes.remove(&game_id).unwrap_or_default(),
+            }),
+        });
+    }
+
+    let mut sitting_out: HashMap<i64, Vec<i64>> = HashMap::new();
+    for row in &sit_out_rows {
+        sitting_out.entry(row.get("round_id")).or_default().push(number(row.get("player_id"))?);
+    }
+
+    let mut rounds: HashMap<i64, Vec<ExportedRound>> = HashMap::new();
+    for row in &round_rows {
+        let round_id: i64 = row.get("id");
+        let mut round_sitting_out = sitting_out.remove(&round_id).unwrap_or_default();
+        round_sitting_out.sort();
+
+        rounds.entry(row.get("session_id")).or_default().push(ExportedRound {
+            round_number: row.get("round_number"),
+            seed: row.get("seed"),
+            created_at: row.get("created_at"),
+            games: games.remove(&round_id).unwrap_or_default(),
+            sitting_out: round_sitting_out,
+        });
+    }
+
+    let mut attendees: HashMap<i64, Vec<ExportedAttendee>> = HashMap::new();
+    for row in &attendee_rows {
+        attendees.entry(row.get("session_id")).or_default().push(ExportedAttendee {
+            player: number(row.get("player_id"))?,
+            sit_out_count: row.get("sit_out_count"),
+            games_played: row.get("games_played"),
+            last_sat_out_round: row.get("last_sat_out_round"),
+            checked_in_at: row.get("checked_in_at"),
+            checked_out_at: row.get("checked_out_at"),
+        });
+    }
+
+    let sessions = session_rows
+        .iter()
+        .map(|row| {
+            let session_id: i64 = row.get("id");
+            let mut session_attendees = attendees.remove(&session_id).unwrap_or_default();
+            session_attendees.sort_by_key(|attendee| attendee.player);
+
+            ExportedSession {
+                court_count: row.get("court_count"),
+                started_at: row.get("started_at"),
+                ended_at: row.get("ended_at"),
+                attendees: session_attendees,
+                rounds: rounds.remove(&session_id).unwrap_or_default(),
+            }
+        })
+        .collect();
+
+    Ok(ClubExport {
+        format_version: CLUB_EXPORT_VERSION,
+        exported_at: club.get("exported_at"),
+        club: ExportedClub {
+            name: club.get("name"),
+            description: club.get("description"),
+            auto_skill_level: club.get("auto_skill_level"),
+        },
+        players,
+        sessions,
+    })
+}
+
+/// Reads a club export, refusing versions this app doesn't know before
+/// looking at the rest of the document.
+pub fn parse_club_export(json_text: &str) -> Result<ClubExport, AppError> {
+    let invalid = |e: serde_json::Error| AppError::validation("json_text", format!("Not a club export: {}", e));
+
+    let document: serde_json::Value = serde_json::from_str(json_text).map_err(invalid)?;
+    let version = document.get("format_version").and_then(|v| v.as_u64()).unwrap_or(0);
+
+    if version == 0 || version > CLUB_EXPORT_VERSION as u64 {
+        return Err(AppError::validation(
+            "format_version",
+            format!(
+                "Export format version {} isn't supported, this app reads versions 1 to {}",
+                version, CLUB_EXPORT_VERSION
+            ),
+        ));
+    }
+
+    serde_json::from_value(document).map_err(invalid)
+}
+
+pub async fn import_club_export(db: &SqlitePool, export: &ClubExport, importer: Option<&User>) -> Result<Club, AppError> {
+    // Start a transaction
+    let mut tx = db.begin().await?;
+
+    let club_id: i64 =
+        sqlx::query("INSERT INTO clubs (name, description, auto_skill_level, created_by) VALUES (?, ?, ?, ?) RETURNING id")
+            .bind(&export.club.name)
+            .bind(&export.club.description)
+            .bind(export.club.auto_skill_level)
+            .bind(importer.map(|user| user.id))
+            .fetch_one(&mut *tx)
+            .await?
+            .get("id");
+
+    if let Some(importer) = importer {
+        sqlx::query("INSERT INTO club_roles (user_id, club_id, role) VALUES (?, ?, ?)")
+            .bind(importer.id)
+            .bind(club_id)
+            .bind(ClubRole::Owner.as_str())
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    let after = club_snapshot(&mut tx, club_id).await?;
+    record(
+        &mut tx,
+        importer,
+        AuditChange {
+            action: AuditAction::Create,
+            entity: AuditEntity::Club,
+            entity_id: club_id,
+            club_id,
+            before: None,
+            after,
+        },
+    )
+    .await?;
+
+    let mut player_ids: HashMap<i64, i64> = HashMap::new();
+    let mut matched: HashSet<i64> = HashSet::new();
+    for player in &export.players {
+        let existing = sqlx::query("SELECT id FROM players WHERE lower(email) = lower(?)")
+            .bind(&player.email)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        let player_id: i64 = match existing {
+            Some(row) => {
+                matched.insert(player.number);
+                row.get("id")
+            }
+            None => {
+                let player_id: i64 = sqlx::query(
+                    "INSERT INTO players (first_name, last_name, email, gender, club_id, skill_level, sit_out_count, rating, rating_deviation)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?) RETURNING id"
+                )
+                .bind(&player.first_name)
+                .bind(&player.last_name)
+                .bind(&player.email)
+                .bind(player.gender.as_str())
+                .bind(club_id)
+                .bind(player.skill_level)
+                .bind(0)
+                .bind(player.rating)
+                .bind(player.rating_deviation)
+                .fetch_one(&mut *tx)
+                .await?
+                .get("id");
+
+                let after = player_snapshot(&mut tx, player_id).await?;
+                record(
+                    &mut tx,
+                    importer,
+                    AuditChange {
+                        action: AuditAction::Create,
+                        entity: AuditEntity::Player,
+                        entity_id: player_id,
+                        club_id,
+                        before: None,
+                        after,
+                    },
+                )
+                .await?;
+
+                player_id
+            }
+        };
+
+        if let Some(membership) = &player.membership {
+            sqlx::query("INSERT INTO club_memberships (player_id, club_id, role, status, joined_at) VALUES (?, ?, ?, ?, ?)")
+                .bind(player_id)
+                .bind(club_id)
+                .bind(membership.role.as_str())
+                .bind(membership.status.as_str())
+                .bind(&membership.joined_at)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        if player_ids.insert(player.number, player_id).is_some() {
+            return Err(AppError::validation("players", format!("Player number {} is used twice", player.number)));
+        }
+    }
+
+    let player_id = |number: i64| {
+        player_ids
+            .get(&number)
+            .copied()
+            .ok_or_else(|| AppError::validation("players", format!("Player number {} isn't in the export", number)))
+    };
+
+    for session in &export.sessions {
+        let session_id: i64 = sqlx::query(
+            "INSERT INTO sessions (club_id, court_count, started_at, ended_at) VALUES (?, ?, ?, ?) RETURNING id"
+        )
+        .bind(club_id)
+        .bind(session.court_count)
+        .bind(&session.started_at)
+        .bind(&session.ended_at)
+        .fetch_one(&mut *tx)
+        .await?
+        .get("id");
+
+        for attendee in &session.attendees {
+            sqlx::query(
+                "INSERT INTO session_players (session_id, player_id, sit_out_count, games_played, last_sat_out_round, checked_in_at, checked_out_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?)"
+            )
+            .bind(session_id)
+            .bind(player_id(attendee.player)?)
+            .bind(attendee.sit_out_count)
+            .bind(attendee.games_played)
+            .bind(attendee.last_sat_out_round)
+            .bind(&attendee.checked_in_at)
+            .bind(&attendee.checked_out_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for round in &session.rounds {
+            let round_id: i64 = sqlx::query(
+                "INSERT INTO rounds (session_id, round_number, seed, created_at) VALUES (?, ?, ?, ?) RETURNING id"
+            )
+            .bind(session_id)
+            .bind(round.round_number)
+            .bind(round.seed)
+            .bind(&round.created_at)
+            .fetch_one(&mut *tx)
+            .await?
+            .get("id");
+
+            for &number in &round.sitting_out {
+                sqlx::query("INSERT INTO round_sit_outs (round_id, player_id) VALUES (?, ?)")
+                    .bind(round_id)
+                    .bind(player_id(number)?)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+
+            for game in &round.games {
+                let game_id: i64 = sqlx::query(
+                    "INSERT INTO round_games (round_id, court, started_at, finished_at) VALUES (?, ?, ?, ?) RETURNING id"
+                )
+                .bind(round_id)
+                .bind(game.court)
+                .bind(&game.started_at)
+                .bind(&game.finished_at)
+                .fetch_one(&mut *tx)
+                .await?
+                .get("id");
+
+                for game_player in &game.players {
+                    sqlx::query("INSERT INTO round_game_players (game_id, player_id, position, team) VALUES (?, ?, ?, ?)")
+                        .bind(game_id)
+                        .bind(player_id(game_player.player)?)
+                        .bind(game_player.position)
+                        .bind(game_player.team.as_str())
+                        .execute(&mut *tx)
+                        .await?;
+                }
+
+                let Some(result) = &game.result else {
+                    continue;
+                };
+
+                sqlx::query("INSERT INTO game_results (game_id, winner, recorded_at) VALUES (?, ?, ?)")
+                    .bind(game_id)
+                    .bind(result.winner.as_str())
+                    .bind(&result.recorded_at)
+                    .execute(&mut *tx)
+                    .await?;
+
+                for (i, set) in result.sets.iter().enumerate() {
+                    sqlx::query(
+                        "INSERT INTO game_result_sets (game_id, set_number, team_a_score, team_b_score) VALUES (?, ?, ?, ?)"
+                    )
+                    .bind(game_id)
+                    .bind(i as i32 + 1)
+                    .bind(set.team_a)
+                    .bind(set.team_b)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+
+                for change in &result.rating_changes {
+                    // Players already here keep their own ratings, which this history didn't lead to
+                    if matched.contains(&change.player) {
+                        continue;
+                    }
+
+                    sqlx::query(
+                        "INSERT INTO rating_history (player_id, game_id, rating_before, deviation_before, rating_after, deviation_after, recorded_at)
+                         VALUES (?, ?, ?, ?, ?, ?, ?)"
+                    )
+                    .bind(player_id(change.player)?)
+                    .bind(game_id)
+                    .bind(change.rating_before)
+                    .bind(change.deviation_before)
+                    .bind(change.rating_after)
+                    .bind(change.deviation_after)
+                    .bind(&result.recorded_at)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+            }
+        }
+    }
+
+    // Commit the transaction
+    tx.commit().await?;
+
+    let member_count = export
+        .players
+        .iter()
+        .filter(|player| player.membership.as_ref().is_some_and(|m| m.status == MembershipStatus::Active))
+        .count();
+
+    Ok(Club {
+        id: club_id,
+        name: export.club.name.clone(),
+        member_count: member_count as i32,
+        auto_skill_level: export.club.auto_skill_level,
+    })
+}
+
+pub fn club_export_to_csv(export: &ClubExport) -> Result<ClubCsvExport, AppError> {
+    let write_error = |e: csv::Error| AppError::Io(format!("Failed to write CSV: {}", e));
+    let names: HashMap<i64, String> = export
+        .players
+        .iter()
+        .map(|player| (player.number, format!("{} {}", player.first_name, player.last_name)))
+        .collect();
+
+    // The first five columns match what the member import looks for
+    let mut players = csv::Writer::from_writer(Vec::new());
+    players
+        .write_record(["First name", "Last name", "Email", "Gender", "Skill level", "Rating", "Role", "Status", "Joined"])
+        .map_err(write_error)?;
+    for player in &export.players {
+        let membership = player.membership.as_ref();
+        players
+            .write_record([
+                player.first_name.clone(),
+                player.last_name.clone(),
+                player.email.clone(),
+                player.gender.as_str().to_string(),
+                player.skill_level.to_string(),
+                player.rating.map(|rating| format!("{:.0}", rating)).unwrap_or_default(),
+                membership.map(|m| m.role.as_str().to_string()).unwrap_or_default(),
+                membership.map(|m| m.status.as_str().to_string()).unwrap_or_default(),
+                membership.map(|m| m.joined_at.clone()).unwrap_or_default(),
+            ])
+            .map_err(write_error)?;
+    }
+
+    let mut sessions = csv::Writer::from_writer(Vec::new());
+    sessions
+        .write_record(["Started", "Ended", "Courts", "Attendees", "Rounds", "Games"])
+        .map_err(write_error)?;
+
+    let mut games = csv::Writer::from_writer(Vec::new());
+    games
+        .write_record(["Session started", "Round", "Court", "Team A", "Team B", "Score", "Winner", "Started", "Finished"])
+        .map_err(write_error)?;
+
+    for session in &export.sessions {
+        let game_count: usize = session.rounds.iter().map(|round| round.games.len()).sum();
+        sessions
+            .write_record([
+                session.started_at.clone(),
+                session.ended_at.clone().unwrap_or_default(),
+                session.court_count.to_string(),
+                session.attendees.len().to_string(),
+                session.rounds.len().to_string(),
+                game_count.to_string(),
+            ])
+            .map_err(write_error)?;
+
+        for round in &session.rounds {
+            for game in &round.games {
+                let team = |team: Team| {
+                    game.players
+                        .iter()
+                        .filter(|p| p.team == team)
+                        .map(|p| names.get(&p.player).cloned().unwrap_or_default())
+                        .collect::<Vec<_>>()
+                        .join(" & ")
+                };
+                let score = game.result.as_ref().map(|result| {
+                    result
+                        .sets
+                        .iter()
+                        .map(|set| format!("{}-{}", set.team_a, set.team_b))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                });
+
+                games
+                    .write_record([
+                        session.started_at.clone(),
+                        round.round_number.to_string(),
+                        game.court.to_string(),
+                        team(Team::A),
+                        team(Team::B),
+                        score.unwrap_or_default(),
+                        game.result.as_ref().map(|r| r.winner.as_str().to_string()).unwrap_or_default(),
+                        game.started_at.clone().unwrap_or_default(),
+                        game.finished_at.clone().unwrap_or_default(),
+                    ])
+                    .map_err(write_error)?;
+            }
+        }
+    }
+
+    let finish = |writer: csv::Writer<Vec<u8>>| -> Result<String, AppError> {
+        let bytes = writer.into_inner().map_err(|e| AppError::Io(format!("Failed to write CSV: {}", e)))?;
+        String::from_utf8(bytes).map_err(|e| AppError::Io(format!("Failed to write CSV: {}", e)))
+    };
+
+    Ok(ClubCsvExport {
+        players: finish(players)?,
+        sessions: finish(sessions)?,
+        games: finish(games)?,
+    })
+}
@@ -1,76 +1,312 @@
-use crate::models::{Game, GamesRound, InGamePlayer};
-use std::collections::HashSet;
+use crate::models::{AppState, CourtFormat, Game, GamesOptions, GamesRound, Gender, InGamePlayer, MatchFormat};
+use crate::utils::errors::AppError;
+use std::collections::{HashMap, HashSet};
 use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::State;
 
+/// Draws a round from the players picked in the lobby and shows it on the court board.
 #[tauri::command]
 pub async fn make_games(
-    mut players: Vec<InGamePlayer>,
+    players: Vec<InGamePlayer>,
     num_courts: usize,
     previous_sit_out: Option<Vec<InGamePlayer>>,
-) -> GamesRound {
-    println!(
-        "make_games called with {} players and {} courts",
-        players.len(),
-        num_courts
-    );
+    options: Option<GamesOptions>,
+    seed: Option<u64>,
+    state: State<'_, AppState>,
+) -> Result<GamesRound, AppError> {
+    let round = draw_games(players, num_courts, previous_sit_out, options, seed);
+    state.board.show_games(round.clone());
+
+    Ok(round)
+}
 
+/// The draw behind `make_games`, which leaves the court board alone.
+pub fn draw_games(
+    players: Vec<InGamePlayer>,
+    num_courts: usize,
+    previous_sit_out: Option<Vec<InGamePlayer>>,
+    options: Option<GamesOptions>,
+    seed: Option<u64>,
+) -> GamesRound {
     let previous_sit_out_ids: HashSet<i64> = previous_sit_out
         .unwrap_or_default()
         .into_iter()
         .map(|p| p.id)
         .collect();
 
-    let total_needed = num_courts * 4;
+    // Without a session there is no pairing history to avoid
+    generate_round(
+        players,
+        num_courts,
+        &previous_sit_out_ids,
+        &PairingHistory::default(),
+        &options.unwrap_or_default(),
+        seed.unwrap_or_else(random_seed),
+    )
+}
+
+/// A fresh seed for when the caller doesn't supply one. It is kept within 53
+/// bits so it survives the round trip through a JavaScript number.
+pub fn random_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+        & ((1 << 53) - 1)
+}
+
+/// Builds one round of games from the given players. Shared by the stateless
+/// `make_games` command and the session based round generator.
+///
+/// All tie-breaking is driven by `seed`, so the same inputs and seed always
+/// produce the same round.
+pub fn generate_round(
+    mut players: Vec<InGamePlayer>,
+    num_courts: usize,
+    previous_sit_out_ids: &HashSet<i64>,
+    history: &PairingHistory,
+    options: &GamesOptions,
+    seed: u64,
+) -> GamesRound {
+    let court_formats: Vec<CourtFormat> = (0..num_courts).map(|court| options.court_format(court)).collect();
+    let total_needed: usize = court_formats.iter().map(|format| format.players()).sum();
     let mut games = Vec::new();
-    let mut sitting_out = Vec::new();
+    let mut warnings = Vec::new();
 
     if players.len() < total_needed {
-        sitting_out = players;
+        let mut sitting_out = players;
         for p in &mut sitting_out {
             p.sit_out_count += 1;
         }
-        return GamesRound { games, sitting_out };
+        return GamesRound {
+            games,
+            sitting_out,
+            warnings,
+            seed,
+        };
     }
 
-    let num_to_sit_out = players.len() - total_needed;
-    sitting_out = select_players_to_sit_out(&mut players, num_to_sit_out, &previous_sit_out_ids);
+    let men = players.iter().filter(|p| p.gender == Gender::Male).count();
+    let plans = plan_courts(options.format, men, players.len() - men, &court_formats, &mut warnings);
+
+    let mut sitting_out = if options.format == MatchFormat::Any {
+        let num_to_sit_out = players.len() - total_needed;
+        select_players_to_sit_out(&mut players, num_to_sit_out, previous_sit_out_ids, seed)
+    } else {
+        // Sit-outs are chosen within each gender so the courts can be filled as planned
+        let men_needed: usize = plans.iter().map(|plan| plan.men).sum();
+        let women_needed: usize = plans.iter().map(|plan| plan.women).sum();
+        let (mut men, mut women): (Vec<_>, Vec<_>) =
+            players.into_iter().partition(|p| p.gender == Gender::Male);
+
+        let men_to_sit_out = men.len() - men_needed;
+        let women_to_sit_out = women.len() - women_needed;
+        let mut sitting_out = select_players_to_sit_out(&mut men, men_to_sit_out, previous_sit_out_ids, seed);
+        sitting_out.extend(select_players_to_sit_out(&mut women, women_to_sit_out, previous_sit_out_ids, seed));
+
+        players = men;
+        players.extend(women);
+        sitting_out
+    };
 
     for p in &mut sitting_out {
         p.sit_out_count += 1;
     }
 
     // Prioritize previous sit-outs in assignment order
-    let teams = create_balanced_teams(players, num_courts, &previous_sit_out_ids);
+    let teams = create_balanced_teams(players, &plans, previous_sit_out_ids, history, options, seed);
+
+    for (plan, mut team) in plans.iter().zip(teams) {
+        let team_b = team.split_off(team.len() / 2);
+        games.push(Game::new(None, plan.court + 1, team, team_b));
+    }
+
+    GamesRound {
+        games,
+        sitting_out,
+        warnings,
+        seed,
+    }
+}
+
+/// How many of `max_courts` can be filled by `num_players`, taking singles
+/// courts into account. Courts are filled in order, so a later court is only
+/// used once every earlier court has enough players.
+pub fn courts_in_use(num_players: usize, max_courts: usize, options: &GamesOptions) -> usize {
+    let mut capacity = 0;
+
+    for court in 0..max_courts {
+        capacity += options.court_format(court).players();
+        if capacity > num_players {
+            return court;
+        }
+    }
+
+    max_courts
+}
+
+/// One court to fill this round. Under a gendered match format `men` and
+/// `women` fix its make-up, otherwise only `size` matters.
+#[derive(Clone, Copy)]
+struct CourtPlan {
+    court: usize,
+    size: usize,
+    men: usize,
+    women: usize,
+}
+
+impl CourtPlan {
+    fn new(court: usize, men: usize, women: usize) -> Self {
+        CourtPlan {
+            court,
+            size: men + women,
+            men,
+            women,
+        }
+    }
+}
+
+/// Decides the make-up of each court for the requested format. When the
+/// format can't be met on every court it falls back as follows, adding a
+/// warning explaining why:
+/// - `StrictMixed` leaves the doubles courts it can't fill with mixed pairs empty.
+/// - `PreferMixed` fills the remaining doubles courts from whoever is left,
+///   majority gender first.
+/// - `Level` gives each doubles court to whichever gender has more players
+///   waiting, then puts any leftover players on mixed courts.
+///
+/// Under any gendered format singles are played between two men or two
+/// women where possible.
+fn plan_courts(
+    format: MatchFormat,
+    men: usize,
+    women: usize,
+    court_formats: &[CourtFormat],
+    warnings: &mut Vec<String>,
+) -> Vec<CourtPlan> {
+    if format == MatchFormat::Any {
+        return court_formats
+            .iter()
+            .enumerate()
+            .map(|(court, court_format)| CourtPlan {
+                court,
+                size: court_format.players(),
+                men: 0,
+                women: 0,
+            })
+            .collect();
+    }
+
+    let (doubles, singles): (Vec<usize>, Vec<usize>) =
+        (0..court_formats.len()).partition(|&court| court_formats[court] == CourtFormat::Doubles);
+
+    let mut plans: Vec<Option<CourtPlan>> = vec![None; court_formats.len()];
+    let mut unfilled_doubles = Vec::new();
+    let (mut men_left, mut women_left) = (men, women);
+
+    match format {
+        MatchFormat::Any => {}
+        MatchFormat::PreferMixed | MatchFormat::StrictMixed => {
+            let mixed_courts = doubles.len().min(men / 2).min(women / 2);
+
+            for &court in &doubles[..mixed_courts] {
+                plans[court] = Some(CourtPlan::new(court, 2, 2));
+            }
+            men_left -= mixed_courts * 2;
+            women_left -= mixed_courts * 2;
+
+            if mixed_courts < doubles.len() {
+                let short = if men / 2 < women / 2 { "men" } else { "women" };
+
+                if format == MatchFormat::StrictMixed {
+                    warnings.push(format!(
+                        "Not enough {} for mixed doubles on every court, so only {} of {} doubles courts are in use",
+                        short,
+                        mixed_courts,
+                        doubles.len()
+                    ));
+                } else {
+                    warnings.push(format!(
+                        "Not enough {} for mixed doubles on every court, so {} court(s) are not mixed",
+                        short,
+                        doubles.len() - mixed_courts
+                    ));
+                    unfilled_doubles.extend_from_slice(&doubles[mixed_courts..]);
+                }
+            }
+        }
+        MatchFormat::Level => {
+            for &court in &doubles {
+                if men_left >= 4 && (women_left < 4 || men_left >= women_left) {
+                    plans[court] = Some(CourtPlan::new(court, 4, 0));
+                    men_left -= 4;
+                } else if women_left >= 4 {
+                    plans[court] = Some(CourtPlan::new(court, 0, 4));
+                    women_left -= 4;
+                } else {
+                    unfilled_doubles.push(court);
+                }
+            }
+
+            if !unfilled_doubles.is_empty() {
+                warnings.push(format!(
+                    "Not enough players for single-gender courts, so {} court(s) are mixed",
+                    unfilled_doubles.len()
+                ));
+            }
+        }
+    }
+
+    for &court in &singles {
+        if men_left >= 2 && (women_left < 2 || men_left >= women_left) {
+            plans[court] = Some(CourtPlan::new(court, 2, 0));
+            men_left -= 2;
+        } else if women_left >= 2 {
+            plans[court] = Some(CourtPlan::new(court, 0, 2));
+            women_left -= 2;
+        } else {
+            warnings.push(format!("Court {} singles is between a man and a woman", court + 1));
+            plans[court] = Some(CourtPlan::new(court, 1, 1));
+            men_left -= 1;
+            women_left -= 1;
+        }
+    }
+
+    for &court in &unfilled_doubles {
+        let mut plan = CourtPlan::new(court, 0, 0);
 
-    for (court_idx, team) in teams.into_iter().enumerate() {
-        games.push(Game {
-            court: court_idx + 1,
-            players: team,
-        });
+        for _ in 0..CourtFormat::Doubles.players() {
+            if men_left > 0 && men_left >= women_left {
+                plan.men += 1;
+                men_left -= 1;
+            } else if women_left > 0 {
+                plan.women += 1;
+                women_left -= 1;
+            }
+        }
+
+        plan.size = plan.men + plan.women;
+        plans[court] = Some(plan);
     }
 
-    GamesRound { games, sitting_out }
+    plans.into_iter().flatten().collect()
 }
 
 fn select_players_to_sit_out(
     players: &mut Vec<InGamePlayer>,
     num_to_sit_out: usize,
     previous_sit_out_ids: &HashSet<i64>,
+    seed: u64,
 ) -> Vec<InGamePlayer> {
     if num_to_sit_out == 0 || players.is_empty() {
         return Vec::new();
     }
 
-    let seed = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_nanos() as u64)
-        .unwrap_or(0);
-
     // Rank all players by:
     // 1) not in previous sit-out round
     // 2) lowest sit_out_count (fair distribution over time)
     // 3) randomized tie-breaker
-    let mut pool: Vec<InGamePlayer> = players.drain(..).collect();
+    let mut pool: Vec<InGamePlayer> = std::mem::take(players);
     pool.sort_by(|a, b| {
         let a_prev = previous_sit_out_ids.contains(&a.id) as u8;
         let b_prev = previous_sit_out_ids.contains(&b.id) as u8;
@@ -101,18 +337,16 @@ fn tie_breaker(id: i64, seed: u64) -> u64 {
 
 fn create_balanced_teams(
     players: Vec<InGamePlayer>,
-    num_courts: usize,
+    plans: &[CourtPlan],
     previous_sit_out_ids: &HashSet<i64>,
+    history: &PairingHistory,
+    options: &GamesOptions,
+    seed: u64,
 ) -> Vec<Vec<InGamePlayer>> {
-    if num_courts == 0 {
+    if plans.is_empty() {
         return Vec::new();
     }
 
-    let seed = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_nanos() as u64)
-        .unwrap_or(0);
-
     // 1) Players who sat out last round are assigned first
     // 2) Within each group, higher skill first
     // 3) Random tie-breaker for same skill
@@ -121,55 +355,150 @@ fn create_balanced_teams(
         .partition(|p| previous_sit_out_ids.contains(&p.id));
 
     priority.sort_by(|a, b| {
-        b.skill_level
-            .cmp(&a.skill_level)
+        b.balance_level()
+            .cmp(&a.balance_level())
             .then_with(|| tie_breaker(a.id, seed).cmp(&tie_breaker(b.id, seed)))
     });
 
     regular.sort_by(|a, b| {
-        b.skill_level
-            .cmp(&a.skill_level)
+        b.balance_level()
+            .cmp(&a.balance_level())
             .then_with(|| tie_breaker(a.id, seed).cmp(&tie_breaker(b.id, seed)))
     });
 
     let mut draft_order = priority;
     draft_order.extend(regular);
 
-    let mut teams: Vec<Vec<InGamePlayer>> = vec![Vec::new(); num_courts];
+    let mut teams: Vec<Vec<InGamePlayer>> = vec![Vec::new(); plans.len()];
+
+    if options.format == MatchFormat::Any {
+        let capacity: Vec<usize> = plans.iter().map(|plan| plan.size).collect();
+        snake_draft(draft_order, &mut teams, &capacity);
+    } else {
+        let (men, women): (Vec<_>, Vec<_>) = draft_order.into_iter().partition(|p| p.gender == Gender::Male);
+        let men_capacity: Vec<usize> = plans.iter().map(|plan| plan.men).collect();
+        let women_capacity: Vec<usize> = plans.iter().map(|plan| plan.women).collect();
+
+        snake_draft(men, &mut teams, &men_capacity);
+        snake_draft(women, &mut teams, &women_capacity);
+    }
+
+    optimize_teams_stability(&mut teams, history, options);
+    teams
+}
+
+/// Deals players out across the courts in snake order (1, 2, 3, 3, 2, 1, ...),
+/// skipping any court that already holds its `capacity`.
+fn snake_draft(draft_order: Vec<InGamePlayer>, teams: &mut [Vec<InGamePlayer>], capacity: &[usize]) {
     let mut current_team = 0usize;
     let mut direction = 1i32;
+    let mut placed = vec![0usize; teams.len()];
 
     for player in draft_order {
+        // Capacity always matches the players being drafted, so a free court is
+        // found within one full sweep in each direction.
+        for _ in 0..teams.len() * 2 {
+            if placed[current_team] < capacity[current_team] {
+                break;
+            }
+            advance_snake(&mut current_team, &mut direction, teams.len());
+        }
+
         teams[current_team].push(player);
+        placed[current_team] += 1;
+        advance_snake(&mut current_team, &mut direction, teams.len());
+    }
+}
 
-        if direction == 1 {
-            if current_team == num_courts - 1 {
-                direction = -1;
-            } else {
-                current_team += 1;
-            }
-        } else if current_team == 0 {
-            direction = 1;
+fn advance_snake(current_team: &mut usize, direction: &mut i32, num_courts: usize) {
+    if *direction == 1 {
+        if *current_team == num_courts - 1 {
+            *direction = -1;
         } else {
-            current_team -= 1;
+            *current_team += 1;
         }
+    } else if *current_team == 0 {
+        *direction = 1;
+    } else {
+        *current_team -= 1;
     }
+}
 
-    optimize_teams_stability(&mut teams);
-    teams
+/// How many times each pair of players has partnered or opposed each other
+/// in earlier rounds. Pairs are keyed with the lower id first.
+#[derive(Default)]
+pub struct PairingHistory {
+    partners: HashMap<(i64, i64), u32>,
+    opponents: HashMap<(i64, i64), u32>,
 }
 
-fn optimize_teams_stability(teams: &mut [Vec<InGamePlayer>]) {
-    for _ in 0..10 {
+impl PairingHistory {
+    pub fn from_games<'a>(games: impl IntoIterator<Item = &'a Game>) -> Self {
+        let mut history = PairingHistory::default();
+        for game in games {
+            history.record_game(game);
+        }
+        history
+    }
+
+    pub fn record_game(&mut self, game: &Game) {
+        for team in [&game.team_a, &game.team_b] {
+            for (i, a) in team.iter().enumerate() {
+                for b in &team[i + 1..] {
+                    *self.partners.entry(pair_key(a.id, b.id)).or_insert(0) += 1;
+                }
+            }
+        }
+
+        for a in &game.team_a {
+            for b in &game.team_b {
+                *self.opponents.entry(pair_key(a.id, b.id)).or_insert(0) += 1;
+            }
+        }
+    }
+
+    pub fn partner_count(&self, a: i64, b: i64) -> u32 {
+        self.partners.get(&pair_key(a, b)).copied().unwrap_or(0)
+    }
+
+    pub fn opponent_count(&self, a: i64, b: i64) -> u32 {
+        self.opponents.get(&pair_key(a, b)).copied().unwrap_or(0)
+    }
+}
+
+fn pair_key(a: i64, b: i64) -> (i64, i64) {
+    if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+// The three ways four players on a court can split into two pairs.
+const DOUBLES_SPLITS: [[usize; 4]; 3] = [[0, 1, 2, 3], [0, 2, 1, 3], [0, 3, 1, 2]];
+
+const MIN_IMPROVEMENT: f64 = 0.01;
+
+// Large enough that a mixed pair is always chosen over a better skill match when one is possible.
+const UNMIXED_PAIR_PENALTY: f64 = 1000.0;
+
+fn optimize_teams_stability(
+    teams: &mut [Vec<InGamePlayer>],
+    history: &PairingHistory,
+    options: &GamesOptions,
+) {
+    let mean_skill = calculate_team_average(&teams.concat());
+
+    for _ in 0..50 {
         let mut improved = false;
 
         for i in 0..teams.len() {
             for j in (i + 1)..teams.len() {
-                if let Some((idx_a, idx_b)) = find_best_swap(&teams[i], &teams[j]) {
-                    let player_a = teams[i].remove(idx_a);
-                    let player_b = teams[j].remove(idx_b);
-                    teams[i].push(player_b);
-                    teams[j].push(player_a);
+                let (left, right) = teams.split_at_mut(j);
+                let (team_a, team_b) = (&mut left[i], &mut right[0]);
+
+                if let Some((idx_a, idx_b)) = find_best_swap(team_a, team_b, mean_skill, history, options) {
+                    std::mem::swap(&mut team_a[idx_a], &mut team_b[idx_b]);
                     improved = true;
                 }
             }
@@ -179,24 +508,42 @@ fn optimize_teams_stability(teams: &mut [Vec<InGamePlayer>]) {
             break;
         }
     }
+
+    // Order each court so the first half partners against the second half
+    for team in teams.iter_mut() {
+        let (_, order) = best_split(team, history, options);
+        let arranged: Vec<InGamePlayer> = order.iter().map(|&idx| team[idx].clone()).collect();
+        *team = arranged;
+    }
 }
 
-fn find_best_swap(team_a: &[InGamePlayer], team_b: &[InGamePlayer]) -> Option<(usize, usize)> {
-    let team_a_avg = calculate_team_average(team_a);
-    let team_b_avg = calculate_team_average(team_b);
-    let current_variance = (team_a_avg - team_b_avg).abs();
+fn find_best_swap(
+    team_a: &mut [InGamePlayer],
+    team_b: &mut [InGamePlayer],
+    mean_skill: f64,
+    history: &PairingHistory,
+    options: &GamesOptions,
+) -> Option<(usize, usize)> {
+    let current_cost = court_cost(team_a, mean_skill, history, options)
+        + court_cost(team_b, mean_skill, history, options);
 
     let mut best_swap = None;
-    let mut best_improvement = 0.0;
+    let mut best_improvement = MIN_IMPROVEMENT;
+
+    for i in 0..team_a.len() {
+        for j in 0..team_b.len() {
+            // Gendered formats only swap like for like so each court keeps its planned make-up
+            if options.format != MatchFormat::Any && team_a[i].gender != team_b[j].gender {
+                continue;
+            }
 
-    for (i, player_a) in team_a.iter().enumerate() {
-        for (j, player_b) in team_b.iter().enumerate() {
-            let new_team_a_avg = calculate_new_average(team_a, player_a.skill_level, player_b.skill_level);
-            let new_team_b_avg = calculate_new_average(team_b, player_b.skill_level, player_a.skill_level);
-            let new_variance = (new_team_a_avg - new_team_b_avg).abs();
-            let improvement = current_variance - new_variance;
+            std::mem::swap(&mut team_a[i], &mut team_b[j]);
+            let new_cost = court_cost(team_a, mean_skill, history, options)
+                + court_cost(team_b, mean_skill, history, options);
+            std::mem::swap(&mut team_a[i], &mut team_b[j]);
 
-            if improvement > best_improvement && improvement > 0.1 {
+            let improvement = current_cost - new_cost;
+            if improvement > best_improvement {
                 best_improvement = improvement;
                 best_swap = Some((i, j));
             }
@@ -206,15 +553,84 @@ fn find_best_swap(team_a: &[InGamePlayer], team_b: &[InGamePlayer]) -> Option<(u
     best_swap
 }
 
-fn calculate_new_average(team: &[InGamePlayer], remove_skill: i32, add_skill: i32) -> f64 {
-    let total: i32 = team.iter().map(|p| p.skill_level).sum::<i32>() - remove_skill + add_skill;
-    total as f64 / team.len() as f64
+/// Cost of one court: how far its average sits from the session average,
+/// plus the cost of the best way to split it into sides. Singles courts only
+/// need their two players matched, so they may sit at any level.
+fn court_cost(team: &[InGamePlayer], mean_skill: f64, history: &PairingHistory, options: &GamesOptions) -> f64 {
+    let court_imbalance = if team.len() > CourtFormat::Singles.players() {
+        (calculate_team_average(team) - mean_skill).abs()
+    } else {
+        0.0
+    };
+    let (split_cost, _) = best_split(team, history, options);
+
+    options.skill_weight * court_imbalance + split_cost
+}
+
+/// Finds the cheapest way to split a court into two sides, returned as an
+/// ordering where the first half plays the second half.
+fn best_split(team: &[InGamePlayer], history: &PairingHistory, options: &GamesOptions) -> (f64, Vec<usize>) {
+    let candidates: Vec<Vec<usize>> = if team.len() == 4 {
+        DOUBLES_SPLITS.iter().map(|split| split.to_vec()).collect()
+    } else {
+        vec![(0..team.len()).collect()]
+    };
+
+    candidates
+        .into_iter()
+        .map(|order| (split_cost(team, &order, history, options), order))
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .unwrap_or((0.0, Vec::new()))
+}
+
+fn split_cost(team: &[InGamePlayer], order: &[usize], history: &PairingHistory, options: &GamesOptions) -> f64 {
+    let (side_a, side_b) = order.split_at(order.len() / 2);
+    let side_a: Vec<&InGamePlayer> = side_a.iter().map(|&idx| &team[idx]).collect();
+    let side_b: Vec<&InGamePlayer> = side_b.iter().map(|&idx| &team[idx]).collect();
+
+    let side_average = |side: &[&InGamePlayer]| {
+        if side.is_empty() {
+            return 0.0;
+        }
+        side.iter().map(|p| p.balance_level()).sum::<i32>() as f64 / side.len() as f64
+    };
+    let skill_gap = (side_average(&side_a) - side_average(&side_b)).abs();
+
+    // Repeats are squared so meeting a third time costs far more than a second
+    let mut partner_repeats = 0;
+    for side in [&side_a, &side_b] {
+        for (i, a) in side.iter().enumerate() {
+            for b in &side[i + 1..] {
+                partner_repeats += history.partner_count(a.id, b.id).pow(2);
+            }
+        }
+    }
+
+    let mut opponent_repeats = 0;
+    for a in &side_a {
+        for b in &side_b {
+            opponent_repeats += history.opponent_count(a.id, b.id).pow(2);
+        }
+    }
+
+    let mut unmixed_pairs = 0;
+    if matches!(options.format, MatchFormat::PreferMixed | MatchFormat::StrictMixed) {
+        unmixed_pairs = [&side_a, &side_b]
+            .iter()
+            .filter(|side| side.len() == 2 && side[0].gender == side[1].gender)
+            .count();
+    }
+
+    options.skill_weight * skill_gap
+        + options.partner_repeat_weight * partner_repeats as f64
+        + options.opponent_repeat_weight * opponent_repeats as f64
+        + UNMIXED_PAIR_PENALTY * unmixed_pairs as f64
 }
 
 fn calculate_team_average(team: &[InGamePlayer]) -> f64 {
     if team.is_empty() {
         return 0.0;
     }
-    let total: i32 = team.iter().map(|p| p.skill_level).sum();
+    let total: i32 = team.iter().map(|p| p.balance_level()).sum();
     total as f64 / team.len() as f64
 }
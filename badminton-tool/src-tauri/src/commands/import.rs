@@ -0,0 +1,291 @@
+use crate::models::{
+    AppState, AuditAction, AuditChange, AuditEntity, ClubRole, ColumnMapping, CreatePlayerRequest, Gender, ImportOptions,
+    ImportReport, ImportRow, RejectedRow, User,
+};
+use crate::services::audit::{player_snapshot, record};
+use crate::services::auth::{logged_in_user, require_club_role};
+use crate::utils::errors::AppError;
+use sqlx::{Row, SqlitePool};
+use std::collections::HashMap;
+use tauri::State;
+
+/// Headers each column is recognised by when the mapping doesn't name one,
+/// compared ignoring case, spaces and underscores.
+const FIRST_NAME_HEADERS: &[&str] = &["firstname", "first", "forename", "givenname"];
+const LAST_NAME_HEADERS: &[&str] = &["lastname", "last", "surname", "familyname"];
+const EMAIL_HEADERS: &[&str] = &["email", "e-mail", "emailaddress"];
+const GENDER_HEADERS: &[&str] = &["gender", "sex"];
+const SKILL_LEVEL_HEADERS: &[&str] = &["skilllevel", "skill", "level"];
+
+/// Adds the players in a CSV to a club. Rows whose email matches one of the
+/// club's members update that member; rows that fail validation, or whose email
+/// belongs to a player outside the club, are left out. With `dry_run` set, only
+/// the report of what would happen is returned.
+#[tauri::command]
+pub async fn import_players_csv(
+    club_id: i64,
+    csv_text: String,
+    options: Option<ImportOptions>,
+    token: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<ImportReport, AppError> {
+    require_club_role(&state.db, &state.logins, token.as_deref(), club_id, ClubRole::Organiser).await?;
+    let actor = logged_in_user(&state.db, &state.logins, token.as_deref()).await?;
+
+    import_players(&state.db, actor.as_ref(), club_id, &csv_text, options.unwrap_or_default()).await
+}
+
+/// The import behind `import_players_csv`, for callers that hold the pool directly.
+pub async fn import_players(
+    db: &SqlitePool,
+    actor: Option<&User>,
+    club_id: i64,
+    csv_text: &str,
+    options: ImportOptions,
+) -> Result<ImportReport, AppError> {
+    let (rows, mut rejected) = parse_players_csv(csv_text, &options.columns, club_id)?;
+
+    // Start a transaction
+    let mut tx = db.begin().await?;
+
+    let club = sqlx::query("SELECT id FROM clubs WHERE id = ?")
+        .bind(club_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+    if club.is_none() {
+        return Err(AppError::not_found("Club not found"));
+    }
+
+    let mut created = Vec::new();
+    let mut updated = Vec::new();
+
+    for mut row in rows {
+        let existing = sqlx::query(
+            "SELECT p.id, EXISTS (
+                 SELECT 1 FROM club_memberships cm WHERE cm.player_id = p.id AND cm.club_id = ? AND cm.status = 'Active'
+             ) as member
+             FROM players p WHERE lower(p.email) = lower(?)"
+        )
+        .bind(club_id)
+        .bind(&row.email)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        // Only the club's own members are updated, players elsewhere are up to their clubs
+        match existing {
+            Some(player) if player.get("member") => {
+                row.player_id = Some(player.get("id"));
+                updated.push(row);
+            }
+            Some(_) => rejected.push(RejectedRow {
+                line: row.line,
+                field: Some("email".to_string()),
+                message: format!("{} belongs to a player who isn't a member of this club", row.email),
+            }),
+            None => created.push(row),
+        }
+    }
+    rejected.sort_by_key(|row| row.line);
+
+    if !options.dry_run {
+        for row in &created {
+            let player_id: i64 = sqlx::query(
+                "INSERT INTO players (first_name, last_name, email, gender, club_id, skill_level, sit_out_count) VALUES (?, ?, ?, ?, ?, ?, ?) RETURNING id"
+            )
+            .bind(&row.first_name)
+            .bind(&row.last_name)
+            .bind(&row.email)
+            .bind(row.gender.as_str())
+            .bind(club_id)
+            .bind(row.skill_level)
+            .bind(0)
+            .fetch_one(&mut *tx)
+            .await?
+            .get("id");
+
+            sqlx::query("INSERT INTO club_memberships (player_id, club_id) VALUES (?, ?)")
+                .bind(player_id)
+                .bind(club_id)
+                .execute(&mut *tx)
+                .await?;
+
+            let after = player_snapshot(&mut tx, player_id).await?;
+            record(
+                &mut tx,
+                actor,
+                AuditChange {
+                    action: AuditAction::Create,
+                    entity: AuditEntity::Player,
+                    entity_id: player_id,
+                    club_id,
+                    before: None,
+                    after,
+                },
+            )
+            .await?;
+        }
+
+        for row in &updated {
+            // Set when the email matched above
+            let player_id = row.player_id.unwrap_or_default();
+            let before = player_snapshot(&mut tx, player_id).await?;
+
+            sqlx::query("UPDATE players SET first_name = ?, last_name = ?, gender = ?, skill_level = ? WHERE id = ?")
+                .bind(&row.first_name)
+                .bind(&row.last_name)
+                .bind(row.gender.as_str())
+                .bind(row.skill_level)
+                .bind(player_id)
+                .execute(&mut *tx)
+                .await?;
+
+            // Logged against the importing club, the snapshot still names their home club
+            let after = player_snapshot(&mut tx, player_id).await?;
+            record(
+                &mut tx,
+                actor,
+                AuditChange {
+                    action: AuditAction::Update,
+                    entity: AuditEntity::Player,
+                    entity_id: player_id,
+                    club_id,
+                    before,
+                    after,
+                },
+            )
+            .await?;
+        }
+
+        // Commit the transaction
+        tx.commit().await?;
+    }
+
+    Ok(ImportReport {
+        dry_run: options.dry_run,
+        created,
+        updated,
+        rejected,
+    })
+}
+
+/// Reads players from a CSV with a header row, splitting the rows into those
+/// that pass validation and those that don't. An email used on an earlier row
+/// rejects the later one.
+pub fn parse_players_csv(
+    csv_text: &str,
+    columns: &ColumnMapping,
+    club_id: i64,
+) -> Result<(Vec<ImportRow>, Vec<RejectedRow>), AppError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(csv_text.as_bytes());
+
+    let headers = reader
+        .headers()
+        .map_err(|e| AppError::validation("csv_text", format!("Failed to read CSV: {}", e)))?
+        .clone();
+
+    let first_name = find_column(&headers, &columns.first_name, FIRST_NAME_HEADERS, "first_name", "first name")?;
+    let last_name = find_column(&headers, &columns.last_name, LAST_NAME_HEADERS, "last_name", "last name")?;
+    let email = find_column(&headers, &columns.email, EMAIL_HEADERS, "email", "email")?;
+    let gender = find_column(&headers, &columns.gender, GENDER_HEADERS, "gender", "gender")?;
+    let skill_level = find_column(&headers, &columns.skill_level, SKILL_LEVEL_HEADERS, "skill_level", "skill level")?;
+
+    let mut rows = Vec::new();
+    let mut rejected = Vec::new();
+    let mut email_lines: HashMap<String, u64> = HashMap::new();
+
+    for record in reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                rejected.push(RejectedRow {
+                    line: e.position().map_or(0, |p| p.line()),
+                    field: None,
+                    message: format!("Failed to read row: {}", e),
+                });
+                continue;
+            }
+        };
+
+        let line = record.position().map_or(0, |p| p.line());
+        if record.iter().all(|value| value.is_empty()) {
+            continue;
+        }
+
+        let reject = |field: &str, message: String| RejectedRow { line, field: Some(field.to_string()), message };
+        let value = |index: usize| record.get(index).unwrap_or("").to_string();
+
+        let Some(parsed_gender) = parse_gender(&value(gender)) else {
+            rejected.push(reject("gender", format!("Gender '{}' must be Male or Female", value(gender))));
+            continue;
+        };
+
+        let Ok(parsed_skill_level) = value(skill_level).parse::<i32>() else {
+            rejected.push(reject("skill_level", format!("Skill level '{}' must be a whole number", value(skill_level))));
+            continue;
+        };
+
+        let request = CreatePlayerRequest {
+            first_name: value(first_name),
+            last_name: value(last_name),
+            email: value(email),
+            gender: parsed_gender,
+            club_id,
+            skill_level: parsed_skill_level,
+        };
+
+        if let Err(e) = request.validate() {
+            rejected.push(RejectedRow { line, field: e.field().map(str::to_string), message: e.to_string() });
+            continue;
+        }
+
+        if let Some(first_line) = email_lines.get(&request.email.to_lowercase()) {
+            rejected.push(reject("email", format!("Email {} is also on line {}", request.email, first_line)));
+            continue;
+        }
+        email_lines.insert(request.email.to_lowercase(), line);
+
+        rows.push(ImportRow {
+            line,
+            player_id: None,
+            first_name: request.first_name,
+            last_name: request.last_name,
+            email: request.email,
+            gender: request.gender,
+            skill_level: request.skill_level,
+        });
+    }
+
+    Ok((rows, rejected))
+}
+
+fn find_column(
+    headers: &csv::StringRecord,
+    mapped: &Option<String>,
+    known_headers: &[&str],
+    field: &str,
+    description: &str,
+) -> Result<usize, AppError> {
+    let normalise = |header: &str| header.to_lowercase().replace([' ', '_'], "");
+
+    let index = match mapped {
+        Some(mapped) => headers.iter().position(|header| normalise(header) == normalise(mapped)),
+        None => headers.iter().position(|header| known_headers.contains(&normalise(header).as_str())),
+    };
+
+    index.ok_or_else(|| match mapped {
+        Some(mapped) => AppError::validation(field, format!("The CSV has no '{}' column", mapped)),
+        None => AppError::validation(field, format!("Couldn't find the {} column, choose it in the column mapping", description)),
+    })
+}
+
+fn parse_gender(value: &str) -> Option<Gender> {
+    match value.to_lowercase().as_str() {
+        "male" | "m" => Some(Gender::Male),
+        "female" | "f" => Some(Gender::Female),
+        _ => None,
+    }
+}
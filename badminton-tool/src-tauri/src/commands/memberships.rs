@@ -0,0 +1,174 @@
+use crate::models::{AppState, ClubMembership, ClubRole, MembershipRole, MembershipStatus};
+use crate::services::auth::require_club_role;
+use crate::utils::errors::AppError;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqliteConnection, SqlitePool};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_player_memberships(
+    player_id: i64,
+    state: State<'_, AppState>,
+) -> Result<Vec<ClubMembership>, AppError> {
+    player_memberships(&state.db, player_id).await
+}
+
+/// Every club a player has belonged to, including memberships that lapsed.
+pub async fn player_memberships(db: &SqlitePool, player_id: i64) -> Result<Vec<ClubMembership>, AppError> {
+    let rows = sqlx::query(&format!("{} WHERE cm.player_id = ? ORDER BY cm.joined_at, c.name", MEMBERSHIP_SELECT))
+        .bind(player_id)
+        .fetch_all(db)
+        .await?;
+
+    Ok(rows.iter().map(membership_from_row).collect())
+}
+
+/// Adds a player to another club, or brings back a lapsed membership.
+/// The role is only changed when one is given.
+#[tauri::command]
+pub async fn join_club(
+    player_id: i64,
+    club_id: i64,
+    role: Option<MembershipRole>,
+    state: State<'_, AppState>,
+) -> Result<ClubMembership, AppError> {
+    add_membership(&state.db, player_id, club_id, role).await
+}
+
+pub async fn add_membership(
+    db: &SqlitePool,
+    player_id: i64,
+    club_id: i64,
+    role: Option<MembershipRole>,
+) -> Result<ClubMembership, AppError> {
+    // Start a transaction
+    let mut tx = db.begin().await?;
+
+    let player = sqlx::query("SELECT id FROM players WHERE id = ?")
+        .bind(player_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+    if player.is_none() {
+        return Err(AppError::not_found("Player not found"));
+    }
+
+    let club = sqlx::query("SELECT id FROM clubs WHERE id = ?")
+        .bind(club_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+    if club.is_none() {
+        return Err(AppError::not_found("Club not found"));
+    }
+
+    sqlx::query(
+        "INSERT INTO club_memberships (player_id, club_id, role) VALUES (?, ?, ?)
+         ON CONFLICT (player_id, club_id) DO UPDATE SET status = 'Active', role = COALESCE(?, role)"
+    )
+    .bind(player_id)
+    .bind(club_id)
+    .bind(role.unwrap_or_default().as_str())
+    .bind(role.map(|r| r.as_str()))
+    .execute(&mut *tx)
+    .await?;
+
+    let membership = fetch_membership(&mut tx, player_id, club_id).await?;
+
+    // Commit the transaction
+    tx.commit().await?;
+
+    Ok(membership)
+}
+
+/// Makes a membership inactive. Leaving the home club moves the player's home
+/// to their longest-standing other club; a player can't leave their only club.
+#[tauri::command]
+pub async fn leave_club(
+    player_id: i64,
+    club_id: i64,
+    token: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<ClubMembership, AppError> {
+    require_club_role(&state.db, &state.logins, token.as_deref(), club_id, ClubRole::Organiser).await?;
+
+    end_membership(&state.db, player_id, club_id).await
+}
+
+pub async fn end_membership(db: &SqlitePool, player_id: i64, club_id: i64) -> Result<ClubMembership, AppError> {
+    // Start a transaction
+    let mut tx = db.begin().await?;
+
+    let result = sqlx::query(
+        "UPDATE club_memberships SET status = 'Inactive' WHERE player_id = ? AND club_id = ? AND status = 'Active'"
+    )
+    .bind(player_id)
+    .bind(club_id)
+    .execute(&mut *tx)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found("Player is not an active member of this club"));
+    }
+
+    let home_club_id: i64 = sqlx::query("SELECT club_id FROM players WHERE id = ?")
+        .bind(player_id)
+        .fetch_one(&mut *tx)
+        .await?
+        .get("club_id");
+
+    if home_club_id == club_id {
+        let next_home = sqlx::query(
+            "SELECT club_id FROM club_memberships WHERE player_id = ? AND status = 'Active'
+             ORDER BY joined_at, club_id LIMIT 1"
+        )
+        .bind(player_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(next_home) = next_home else {
+            return Err(AppError::Conflict {
+                field: None,
+                message: "A player must belong to at least one club, delete the player instead".to_string(),
+            });
+        };
+
+        sqlx::query("UPDATE players SET club_id = ? WHERE id = ?")
+            .bind(next_home.get::<i64, _>("club_id"))
+            .bind(player_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    let membership = fetch_membership(&mut tx, player_id, club_id).await?;
+
+    // Commit the transaction
+    tx.commit().await?;
+
+    Ok(membership)
+}
+
+const MEMBERSHIP_SELECT: &str = "SELECT cm.player_id, cm.club_id, c.name as club_name, cm.role, cm.joined_at, cm.status
+     FROM club_memberships cm
+     JOIN clubs c ON c.id = cm.club_id";
+
+fn membership_from_row(row: &SqliteRow) -> ClubMembership {
+    ClubMembership {
+        player_id: row.get("player_id"),
+        club_id: row.get("club_id"),
+        club_name: row.get("club_name"),
+        role: MembershipRole::from_db(row.get("role")),
+        joined_at: row.get("joined_at"),
+        status: MembershipStatus::from_db(row.get("status")),
+    }
+}
+
+async fn fetch_membership(conn: &mut SqliteConnection, player_id: i64, club_id: i64) -> Result<ClubMembership, AppError> {
+    let row = sqlx::query(&format!("{} WHERE cm.player_id = ? AND cm.club_id = ?", MEMBERSHIP_SELECT))
+        .bind(player_id)
+        .bind(club_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    Ok(membership_from_row(&row))
+}
@@ -1,3 +1,15 @@
 pub mod clubs;
 pub mod player;
-pub mod games_maker;
\ No newline at end of file
+pub mod games_maker;
+pub mod sessions;
+pub mod results;
+pub mod stats;
+pub mod reports;
+pub mod memberships;
+pub mod backup;
+pub mod import;
+pub mod export;
+pub mod board;
+pub mod check_in;
+pub mod auth;
+pub mod audit;
\ No newline at end of file
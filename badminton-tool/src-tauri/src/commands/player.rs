@@ -1,16 +1,22 @@
-use crate::models::{AppState, Player, CreatePlayerRequest, GetPlayer, Gender};
-use sqlx::Row;
+use crate::models::{AppState, Player, CreatePlayerRequest, GetPlayer, Gender, ClubRole, AuditAction, AuditChange, AuditEntity, User};
+use crate::services::audit::{player_snapshot, record};
+use crate::services::auth::{logged_in_user, require_club_role};
+use crate::utils::errors::AppError;
+use sqlx::{Row, SqlitePool};
 use tauri::State;
 
 #[tauri::command]
-pub async fn get_players_by_club(club_id: i64, state: State<'_, AppState>) -> Result<Vec<GetPlayer>, String> {
+pub async fn get_players_by_club(club_id: i64, state: State<'_, AppState>) -> Result<Vec<GetPlayer>, AppError> {
     let rows = sqlx::query(
-        "SELECT id, first_name, last_name, gender, club_id, skill_level, sit_out_count FROM players WHERE club_id = ? ORDER BY first_name, last_name"
+        "SELECT p.id, p.first_name, p.last_name, p.gender, p.club_id, p.skill_level, p.sit_out_count, p.rating
+         FROM players p
+         JOIN club_memberships cm ON cm.player_id = p.id
+         WHERE cm.club_id = ? AND cm.status = 'Active'
+         ORDER BY p.first_name, p.last_name"
     )
     .bind(club_id)
     .fetch_all(&state.db)
-    .await
-    .map_err(|e| format!("Database error: {}", e))?;
+    .await?;
 
     let players = rows
         .into_iter()
@@ -29,6 +35,7 @@ pub async fn get_players_by_club(club_id: i64, state: State<'_, AppState>) -> Re
                 club_id: row.get("club_id"),
                 skill_level: row.get("skill_level"),
                 sit_out_count: row.get("sit_out_count"),
+                rating: row.get("rating"),
                 gender,
                 
             }
@@ -41,17 +48,25 @@ pub async fn get_players_by_club(club_id: i64, state: State<'_, AppState>) -> Re
 #[tauri::command]
 pub async fn create_player(
     request: CreatePlayerRequest,
+    token: Option<String>,
     state: State<'_, AppState>,
-) -> Result<Player, String> {
-    println!("Creating player: {:?}", request);
-    
+) -> Result<Player, AppError> {
+    let actor = logged_in_user(&state.db, &state.logins, token.as_deref()).await?;
+
+    add_player(&state.db, actor.as_ref(), request).await
+}
+
+/// Creates a player with a membership of their home club, logging who did it.
+pub async fn add_player(db: &SqlitePool, actor: Option<&User>, request: CreatePlayerRequest) -> Result<Player, AppError> {
+    request.validate()?;
+
     let gender_str = match request.gender {
         Gender::Male => "Male",
         Gender::Female => "Female",
     };
 
     // Start a transaction
-    let mut tx = state.db.begin().await.map_err(|e| format!("Failed to start transaction: {}", e))?;
+    let mut tx = db.begin().await?;
 
     // Insert the player
     let result = sqlx::query(
@@ -65,20 +80,34 @@ pub async fn create_player(
     .bind(request.skill_level)
     .bind(0)
     .fetch_one(&mut *tx)
-    .await
-    .map_err(|e| format!("Failed to create player: {}", e))?;
+    .await?;
 
     let player_id: i64 = result.get("id");
 
-    // Increment the club's member count
-    sqlx::query("UPDATE clubs SET member_count = member_count + 1 WHERE id = ?")
+    // The player's home club is their first membership
+    sqlx::query("INSERT INTO club_memberships (player_id, club_id) VALUES (?, ?)")
+        .bind(player_id)
         .bind(request.club_id)
         .execute(&mut *tx)
-        .await
-        .map_err(|e| format!("Failed to update club member count: {}", e))?;
+        .await?;
+
+    let after = player_snapshot(&mut tx, player_id).await?;
+    record(
+        &mut tx,
+        actor,
+        AuditChange {
+            action: AuditAction::Create,
+            entity: AuditEntity::Player,
+            entity_id: player_id,
+            club_id: request.club_id,
+            before: None,
+            after,
+        },
+    )
+    .await?;
 
     // Commit the transaction
-    tx.commit().await.map_err(|e| format!("Failed to commit transaction: {}", e))?;
+    tx.commit().await?;
 
     Ok(Player {
         id: player_id,
@@ -96,28 +125,57 @@ pub async fn create_player(
 pub async fn update_player(
     player_id: i64,
     request: CreatePlayerRequest,
+    token: Option<String>,
     state: State<'_, AppState>,
-) -> Result<Player, String> {
+) -> Result<Player, AppError> {
+    let club_id: i64 = sqlx::query("SELECT club_id FROM players WHERE id = ?")
+        .bind(player_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::not_found("Player not found"))?
+        .get("club_id");
+
+    // Moving a player to another home club is up to both clubs
+    require_club_role(&state.db, &state.logins, token.as_deref(), club_id, ClubRole::Organiser).await?;
+    if request.club_id != club_id {
+        require_club_role(&state.db, &state.logins, token.as_deref(), request.club_id, ClubRole::Organiser).await?;
+    }
+    let actor = logged_in_user(&state.db, &state.logins, token.as_deref()).await?;
+
+    edit_player(&state.db, actor.as_ref(), player_id, request).await
+}
+
+/// Saves a player's details, moving their membership along if their home club
+/// changes, and logs the change.
+pub async fn edit_player(
+    db: &SqlitePool,
+    actor: Option<&User>,
+    player_id: i64,
+    request: CreatePlayerRequest,
+) -> Result<Player, AppError> {
+    request.validate()?;
+
     let gender_str = match request.gender {
         Gender::Male => "Male",
         Gender::Female => "Female",
     };
 
     // Start a transaction
-    let mut tx = state.db.begin().await.map_err(|e| format!("Failed to start transaction: {}", e))?;
+    let mut tx = db.begin().await?;
 
     // Get the current player to check if club_id is changing
     let current_player = sqlx::query("SELECT club_id FROM players WHERE id = ?")
         .bind(player_id)
-        .fetch_one(&mut *tx)
-        .await
-        .map_err(|e| format!("Player not found: {}", e))?;
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::not_found("Player not found"))?;
 
     let current_club_id: i64 = current_player.get("club_id");
+    let before = player_snapshot(&mut tx, player_id).await?;
 
     // Update the player
     let result = sqlx::query(
-        "UPDATE players SET first_name = ?, last_name = ?, email = ?, gender = ?, club_id = ? WHERE id = ?"
+        "UPDATE players SET first_name = ?, last_name = ?, email = ?, gender = ?, club_id = ?, skill_level = ? WHERE id = ?"
     )
     .bind(&request.first_name)
     .bind(&request.last_name)
@@ -127,32 +185,47 @@ pub async fn update_player(
     .bind(request.skill_level)
     .bind(player_id)
     .execute(&mut *tx)
-    .await
-    .map_err(|e| format!("Failed to update player: {}", e))?;
+    .await?;
 
     if result.rows_affected() == 0 {
-        return Err("Player not found".to_string());
+        return Err(AppError::not_found("Player not found"));
     }
 
-    // If club_id changed, update member counts
+    // Moving home club joins the new club and leaves the old one
     if current_club_id != request.club_id {
-        // Decrement old club's member count
-        sqlx::query("UPDATE clubs SET member_count = member_count - 1 WHERE id = ?")
-            .bind(current_club_id)
-            .execute(&mut *tx)
-            .await
-            .map_err(|e| format!("Failed to update old club member count: {}", e))?;
+        sqlx::query(
+            "INSERT INTO club_memberships (player_id, club_id) VALUES (?, ?)
+             ON CONFLICT (player_id, club_id) DO UPDATE SET status = 'Active'"
+        )
+        .bind(player_id)
+        .bind(request.club_id)
+        .execute(&mut *tx)
+        .await?;
 
-        // Increment new club's member count
-        sqlx::query("UPDATE clubs SET member_count = member_count + 1 WHERE id = ?")
-            .bind(request.club_id)
+        sqlx::query("UPDATE club_memberships SET status = 'Inactive' WHERE player_id = ? AND club_id = ?")
+            .bind(player_id)
+            .bind(current_club_id)
             .execute(&mut *tx)
-            .await
-            .map_err(|e| format!("Failed to update new club member count: {}", e))?;
+            .await?;
     }
 
+    let after = player_snapshot(&mut tx, player_id).await?;
+    record(
+        &mut tx,
+        actor,
+        AuditChange {
+            action: AuditAction::Update,
+            entity: AuditEntity::Player,
+            entity_id: player_id,
+            club_id: request.club_id,
+            before,
+            after,
+        },
+    )
+    .await?;
+
     // Commit the transaction
-    tx.commit().await.map_err(|e| format!("Failed to commit transaction: {}", e))?;
+    tx.commit().await?;
 
     Ok(Player {
         id: player_id,
@@ -167,39 +240,60 @@ pub async fn update_player(
 }
 
 #[tauri::command]
-pub async fn delete_player(player_id: i64, state: State<'_, AppState>) -> Result<(), String> {
+pub async fn delete_player(player_id: i64, token: Option<String>, state: State<'_, AppState>) -> Result<(), AppError> {
+    let club_id: i64 = sqlx::query("SELECT club_id FROM players WHERE id = ?")
+        .bind(player_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::not_found("Player not found"))?
+        .get("club_id");
+
+    // Deleting a player takes them out of every club, so it's up to their home club
+    require_club_role(&state.db, &state.logins, token.as_deref(), club_id, ClubRole::Organiser).await?;
+    let actor = logged_in_user(&state.db, &state.logins, token.as_deref()).await?;
+
+    remove_player(&state.db, actor.as_ref(), player_id).await
+}
+
+/// Deletes a player and their memberships, logging it against their home club.
+pub async fn remove_player(db: &SqlitePool, actor: Option<&User>, player_id: i64) -> Result<(), AppError> {
     // Start a transaction
-    let mut tx = state.db.begin().await.map_err(|e| format!("Failed to start transaction: {}", e))?;
+    let mut tx = db.begin().await?;
 
-    // Get the player's club_id before deleting
-    let player = sqlx::query("SELECT club_id FROM players WHERE id = ?")
+    let club_id: i64 = sqlx::query("SELECT club_id FROM players WHERE id = ?")
         .bind(player_id)
-        .fetch_one(&mut *tx)
-        .await
-        .map_err(|e| format!("Player not found: {}", e))?;
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::not_found("Player not found"))?
+        .get("club_id");
+    let before = player_snapshot(&mut tx, player_id).await?;
 
-    let club_id: i64 = player.get("club_id");
-
-    // Delete the player
+    // Delete the player, along with their club memberships
     let result = sqlx::query("DELETE FROM players WHERE id = ?")
         .bind(player_id)
         .execute(&mut *tx)
-        .await
-        .map_err(|e| format!("Failed to delete player: {}", e))?;
+        .await?;
 
     if result.rows_affected() == 0 {
-        return Err("Player not found".to_string());
+        return Err(AppError::not_found("Player not found"));
     }
 
-    // Decrement the club's member count
-    sqlx::query("UPDATE clubs SET member_count = member_count - 1 WHERE id = ?")
-        .bind(club_id)
-        .execute(&mut *tx)
-        .await
-        .map_err(|e| format!("Failed to update club member count: {}", e))?;
+    record(
+        &mut tx,
+        actor,
+        AuditChange {
+            action: AuditAction::Delete,
+            entity: AuditEntity::Player,
+            entity_id: player_id,
+            club_id,
+            before,
+            after: None,
+        },
+    )
+    .await?;
 
     // Commit the transaction
-    tx.commit().await.map_err(|e| format!("Failed to commit transaction: {}", e))?;
+    tx.commit().await?;
 
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,223 @@
+use crate::commands::stats::{validate_date, SESSION_DATE_RANGE};
+use crate::models::{AppState, ClubReport, SessionReport};
+use crate::utils::errors::AppError;
+use sqlx::{Row, SqlitePool};
+use tauri::State;
+
+/// Players a court holds when the report works out the available player-minutes.
+const PLAYERS_PER_COURT: f64 = 4.0;
+
+/// Attendance and activity of a club over sessions between `from` and `to`
+/// (inclusive, `YYYY-MM-DD`). Either end can be left open.
+#[tauri::command]
+pub async fn get_club_report(
+    club_id: i64,
+    from: Option<String>,
+    to: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<ClubReport, AppError> {
+    build_club_report(&state.db, club_id, from, to).await
+}
+
+/// The club report as CSV: the headline figures, then one row per session.
+#[tauri::command]
+pub async fn export_club_report_csv(
+    club_id: i64,
+    from: Option<String>,
+    to: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    let report = build_club_report(&state.db, club_id, from, to).await?;
+    club_report_to_csv(&report)
+}
+
+pub async fn build_club_report(
+    db: &SqlitePool,
+    club_id: i64,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<ClubReport, AppError> {
+    validate_date("from", &from)?;
+    validate_date("to", &to)?;
+
+    let club = sqlx::query("SELECT id FROM clubs WHERE id = ?")
+        .bind(club_id)
+        .fetch_optional(db)
+        .await?;
+
+    if club.is_none() {
+        return Err(AppError::not_found("Club not found"));
+    }
+
+    // Sessions and games still running are counted up to now
+    let session_rows = sqlx::query(&format!(
+        "SELECT s.id, s.started_at, s.ended_at, s.court_count,
+         (SELECT COUNT(*) FROM session_players sp WHERE sp.session_id = s.id) as attendees,
+         (SELECT COUNT(*) FROM round_games rg JOIN rounds r ON r.id = rg.round_id WHERE r.session_id = s.id) as games,
+         (julianday(COALESCE(s.ended_at, CURRENT_TIMESTAMP)) - julianday(s.started_at)) * 1440 as session_minutes,
+         (
+             SELECT COALESCE(SUM(
+                 (julianday(COALESCE(rg.finished_at, s.ended_at, CURRENT_TIMESTAMP)) - julianday(COALESCE(rg.started_at, r.created_at))) * 1440
+                 * (SELECT COUNT(*) FROM round_game_players rgp WHERE rgp.game_id = rg.id)
+             ), 0.0)
+             FROM round_games rg JOIN rounds r ON r.id = rg.round_id
+             WHERE r.session_id = s.id
+         ) as player_minutes_played
+         FROM sessions s
+         WHERE s.club_id = ? AND {}
+         ORDER BY s.started_at",
+        SESSION_DATE_RANGE
+    ))
+    .bind(club_id)
+    .bind(&from)
+    .bind(&from)
+    .bind(&to)
+    .bind(&to)
+    .fetch_all(db)
+    .await?;
+
+    let sessions: Vec<SessionReport> = session_rows
+        .iter()
+        .map(|row| {
+            let court_count: i32 = row.get("court_count");
+            let session_minutes: f64 = row.get("session_minutes");
+
+            SessionReport {
+                session_id: row.get("id"),
+                started_at: row.get("started_at"),
+                ended_at: row.get("ended_at"),
+                court_count,
+                attendees: row.get("attendees"),
+                games: row.get("games"),
+                player_minutes_played: row.get("player_minutes_played"),
+                player_minutes_available: session_minutes * court_count as f64 * PLAYERS_PER_COURT,
+            }
+        })
+        .collect();
+
+    let unique_attendees: i64 = sqlx::query(&format!(
+        "SELECT COUNT(DISTINCT sp.player_id) as unique_attendees FROM session_players sp
+         JOIN sessions s ON s.id = sp.session_id
+         WHERE s.club_id = ? AND {}",
+        SESSION_DATE_RANGE
+    ))
+    .bind(club_id)
+    .bind(&from)
+    .bind(&from)
+    .bind(&to)
+    .bind(&to)
+    .fetch_one(db)
+    .await?
+    .get("unique_attendees");
+
+    // A member is new if their first session at the club is inside the report
+    let new_members: i64 = sqlx::query(&format!(
+        "SELECT COUNT(*) as new_members FROM (
+             SELECT sp.player_id, MIN(s.started_at) as started_at FROM session_players sp
+             JOIN sessions s ON s.id = sp.session_id
+             WHERE s.club_id = ?
+             GROUP BY sp.player_id
+         ) s
+         WHERE {}",
+        SESSION_DATE_RANGE
+    ))
+    .bind(club_id)
+    .bind(&from)
+    .bind(&from)
+    .bind(&to)
+    .bind(&to)
+    .fetch_one(db)
+    .await?
+    .get("new_members");
+
+    let sessions_held = sessions.len() as i32;
+    let total_attendance: i32 = sessions.iter().map(|s| s.attendees).sum();
+    let player_minutes_played: f64 = sessions.iter().map(|s| s.player_minutes_played).sum();
+    let player_minutes_available: f64 = sessions.iter().map(|s| s.player_minutes_available).sum();
+
+    Ok(ClubReport {
+        club_id,
+        from,
+        to,
+        sessions_held,
+        unique_attendees: unique_attendees as i32,
+        average_attendance: if sessions_held > 0 { total_attendance as f64 / sessions_held as f64 } else { 0.0 },
+        player_minutes_played,
+        player_minutes_available,
+        court_utilisation: (player_minutes_available > 0.0).then(|| player_minutes_played / player_minutes_available),
+        new_members: new_members as i32,
+        returning_members: (unique_attendees - new_members) as i32,
+        sessions,
+    })
+}
+
+pub fn club_report_to_csv(report: &ClubReport) -> Result<String, AppError> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let write_error = |e: csv::Error| AppError::Io(format!("Failed to write CSV: {}", e));
+
+    let summary = [
+        ("Club", report.club_id.to_string()),
+        ("From", report.from.clone().unwrap_or_default()),
+        ("To", report.to.clone().unwrap_or_default()),
+        ("Sessions held", report.sessions_held.to_string()),
+        ("Unique attendees", report.unique_attendees.to_string()),
+        ("Average attendance", format!("{:.1}", report.average_attendance)),
+        ("New members", report.new_members.to_string()),
+        ("Returning members", report.returning_members.to_string()),
+        ("Player minutes played", format!("{:.0}", report.player_minutes_played)),
+        ("Player minutes available", format!("{:.0}", report.player_minutes_available)),
+        ("Court utilisation", report.court_utilisation.map(percentage).unwrap_or_default()),
+    ];
+
+    for (label, value) in &summary {
+        writer.write_record([*label, value.as_str()]).map_err(write_error)?;
+    }
+
+    // A blank line between the two sections, which an empty record can't give
+    let mut bytes = writer.into_inner().map_err(|e| AppError::Io(format!("Failed to write CSV: {}", e)))?;
+    bytes.push(b'\n');
+    let mut writer = csv::Writer::from_writer(bytes);
+
+    writer
+        .write_record([
+            "Session",
+            "Started",
+            "Ended",
+            "Courts",
+            "Attendees",
+            "Games",
+            "Player minutes played",
+            "Player minutes available",
+            "Court utilisation",
+        ])
+        .map_err(write_error)?;
+
+    for session in &report.sessions {
+        let utilisation = if session.player_minutes_available > 0.0 {
+            percentage(session.player_minutes_played / session.player_minutes_available)
+        } else {
+            String::new()
+        };
+
+        writer
+            .write_record([
+                session.session_id.to_string(),
+                session.started_at.clone(),
+                session.ended_at.clone().unwrap_or_default(),
+                session.court_count.to_string(),
+                session.attendees.to_string(),
+                session.games.to_string(),
+                format!("{:.0}", session.player_minutes_played),
+                format!("{:.0}", session.player_minutes_available),
+                utilisation,
+            ])
+            .map_err(write_error)?;
+    }
+
+    let bytes = writer.into_inner().map_err(|e| AppError::Io(format!("Failed to write CSV: {}", e)))?;
+    String::from_utf8(bytes).map_err(|e| AppError::Io(format!("Failed to write CSV: {}", e)))
+}
+
+fn percentage(share: f64) -> String {
+    format!("{:.1}%", share * 100.0)
+}
@@ -0,0 +1,189 @@
+use crate::models::{match_winner, AppState, GameResult, SetScore, Team};
+use crate::services::rating;
+use crate::utils::errors::AppError;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqliteConnection, SqlitePool};
+use std::collections::HashMap;
+use tauri::State;
+
+/// Records the score of a game. A one-set game is just the two scores; for best
+/// of three they are the first set and `further_sets` holds the rest. A game
+/// with a result has finished, and recording it again is refused; use
+/// `correct_result` to change a score entered wrongly. The players' ratings are
+/// updated from the result.
+#[tauri::command]
+pub async fn record_result(
+    game_id: i64,
+    team_a_score: i32,
+    team_b_score: i32,
+    further_sets: Option<Vec<SetScore>>,
+    state: State<'_, AppState>,
+) -> Result<GameResult, AppError> {
+    add_result(&state.db, game_id, all_sets(team_a_score, team_b_score, further_sets)).await
+}
+
+/// Replaces the result of a game that already has one, taking the scores the
+/// same way as `record_result`. Every rating the old score fed into is worked
+/// out again.
+#[tauri::command]
+pub async fn correct_result(
+    game_id: i64,
+    team_a_score: i32,
+    team_b_score: i32,
+    further_sets: Option<Vec<SetScore>>,
+    state: State<'_, AppState>,
+) -> Result<GameResult, AppError> {
+    replace_result(&state.db, game_id, all_sets(team_a_score, team_b_score, further_sets)).await
+}
+
+fn all_sets(team_a_score: i32, team_b_score: i32, further_sets: Option<Vec<SetScore>>) -> Vec<SetScore> {
+    let mut sets = vec![SetScore {
+        team_a: team_a_score,
+        team_b: team_b_score,
+    }];
+    sets.extend(further_sets.unwrap_or_default());
+    sets
+}
+
+/// Saves the result of a game that has none yet.
+pub async fn add_result(db: &SqlitePool, game_id: i64, sets: Vec<SetScore>) -> Result<GameResult, AppError> {
+    let winner = match_winner(&sets)?;
+
+    // Start a transaction
+    let mut tx = db.begin().await?;
+
+    if has_result(&mut tx, game_id).await? {
+        return Err(AppError::Conflict {
+            field: None,
+            message: "Game already has a result".into(),
+        });
+    }
+
+    let result = save_result(&mut tx, game_id, sets, winner).await?;
+    rating::apply_result(&mut tx, game_id).await?;
+
+    // Commit the transaction
+    tx.commit().await?;
+
+    Ok(result)
+}
+
+/// Swaps the result of a game for a corrected one.
+pub async fn replace_result(db: &SqlitePool, game_id: i64, sets: Vec<SetScore>) -> Result<GameResult, AppError> {
+    let winner = match_winner(&sets)?;
+
+    // Start a transaction
+    let mut tx = db.begin().await?;
+
+    if !has_result(&mut tx, game_id).await? {
+        return Err(AppError::not_found("Game has no result to correct"));
+    }
+
+    sqlx::query("DELETE FROM game_result_sets WHERE game_id = ?")
+        .bind(game_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let result = save_result(&mut tx, game_id, sets, winner).await?;
+
+    // A corrected score changes every later rating of these players and of those they went on to play
+    rating::replay_ratings(&mut tx, game_id).await?;
+
+    // Commit the transaction
+    tx.commit().await?;
+
+    Ok(result)
+}
+
+/// Whether a game drawn in some round has a result. Games that were never
+/// drawn are refused.
+async fn has_result(conn: &mut SqliteConnection, game_id: i64) -> Result<bool, AppError> {
+    let row = sqlx::query(
+        "SELECT gr.game_id FROM round_games rg LEFT JOIN game_results gr ON gr.game_id = rg.id WHERE rg.id = ?"
+    )
+    .bind(game_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| AppError::not_found("Game not found"))?;
+
+    Ok(row.get::<Option<i64>, _>("game_id").is_some())
+}
+
+/// Stores a result with its set scores and marks the game as finished.
+async fn save_result(
+    conn: &mut SqliteConnection,
+    game_id: i64,
+    sets: Vec<SetScore>,
+    winner: Team,
+) -> Result<GameResult, AppError> {
+    // Update a replaced result in place, as deleting it would take its rating history with it
+    let row = sqlx::query(
+        "INSERT INTO game_results (game_id, winner) VALUES (?, ?)
+         ON CONFLICT (game_id) DO UPDATE SET winner = excluded.winner, recorded_at = CURRENT_TIMESTAMP
+         RETURNING recorded_at"
+    )
+    .bind(game_id)
+    .bind(winner.as_str())
+    .fetch_one(&mut *conn)
+    .await?;
+
+    for (index, set) in sets.iter().enumerate() {
+        sqlx::query("INSERT INTO game_result_sets (game_id, set_number, team_a_score, team_b_score) VALUES (?, ?, ?, ?)")
+            .bind(game_id)
+            .bind(index as i64 + 1)
+            .bind(set.team_a)
+            .bind(set.team_b)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    sqlx::query("UPDATE round_games SET finished_at = COALESCE(finished_at, CURRENT_TIMESTAMP) WHERE id = ?")
+        .bind(game_id)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(GameResult {
+        game_id,
+        sets,
+        winner,
+        recorded_at: row.get("recorded_at"),
+    })
+}
+
+/// Loads the recorded results of every game in a session, keyed by game id.
+pub async fn fetch_session_results(conn: &mut SqliteConnection, session_id: i64) -> Result<HashMap<i64, GameResult>, AppError> {
+    let rows = sqlx::query(
+        "SELECT gr.game_id, gr.winner, gr.recorded_at, grs.team_a_score, grs.team_b_score
+         FROM game_results gr
+         JOIN game_result_sets grs ON grs.game_id = gr.game_id
+         JOIN round_games rg ON rg.id = gr.game_id
+         JOIN rounds r ON r.id = rg.round_id
+         WHERE r.session_id = ?
+         ORDER BY gr.game_id, grs.set_number"
+    )
+    .bind(session_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(results_from_rows(&rows))
+}
+
+/// Groups result rows, one per set in set order, into results keyed by game id.
+pub fn results_from_rows(rows: &[SqliteRow]) -> HashMap<i64, GameResult> {
+    let mut results: HashMap<i64, GameResult> = HashMap::new();
+    for row in rows {
+        let game_id: i64 = row.get("game_id");
+        let result = results.entry(game_id).or_insert_with(|| GameResult {
+            game_id,
+            sets: Vec::new(),
+            winner: Team::from_db(row.get("winner")),
+            recorded_at: row.get("recorded_at"),
+        });
+        result.sets.push(SetScore {
+            team_a: row.get("team_a_score"),
+            team_b: row.get("team_b_score"),
+        });
+    }
+
+    results
+}
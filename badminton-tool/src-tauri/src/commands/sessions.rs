@@ -0,0 +1,879 @@
+use crate::commands::games_maker::{courts_in_use, generate_round, random_seed, PairingHistory};
+use crate::commands::results::fetch_session_results;
+use crate::models::{
+    AppState, Game, GamesOptions, GamesRound, Gender, InGamePlayer, Session, SessionHistory,
+    SessionPlayer, SessionRound,
+};
+use crate::services::backup::auto_backup;
+use crate::utils::errors::AppError;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqliteConnection, SqlitePool};
+use std::collections::{HashMap, HashSet};
+use tauri::State;
+
+#[tauri::command]
+pub async fn start_session(
+    club_id: i64,
+    court_count: i32,
+    player_ids: Vec<i64>,
+    state: State<'_, AppState>,
+) -> Result<Session, AppError> {
+    create_session(&state.db, club_id, court_count, &player_ids).await
+}
+
+/// Starts a new session for a club with the given players checked in, ending
+/// any session the club left running.
+pub async fn create_session(db: &SqlitePool, club_id: i64, court_count: i32, player_ids: &[i64]) -> Result<Session, AppError> {
+    if court_count < 1 {
+        return Err(AppError::validation("court_count", "Court count must be at least 1"));
+    }
+
+    // Start a transaction
+    let mut tx = db.begin().await?;
+
+    let club = sqlx::query("SELECT id FROM clubs WHERE id = ?")
+        .bind(club_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+    if club.is_none() {
+        return Err(AppError::not_found("Club not found"));
+    }
+
+    // Only one session per club can be running, so close any that were left open
+    sqlx::query("UPDATE sessions SET ended_at = CURRENT_TIMESTAMP WHERE club_id = ? AND ended_at IS NULL")
+        .bind(club_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let row = sqlx::query(
+        "INSERT INTO sessions (club_id, court_count) VALUES (?, ?) RETURNING id, club_id, court_count, started_at, ended_at"
+    )
+    .bind(club_id)
+    .bind(court_count)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let session = session_from_row(&row);
+
+    for player_id in player_ids.iter().collect::<HashSet<_>>() {
+        let membership = sqlx::query("SELECT 1 FROM club_memberships WHERE player_id = ? AND club_id = ? AND status = 'Active'")
+            .bind(player_id)
+            .bind(club_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        if membership.is_none() {
+            return Err(AppError::validation(
+                "player_ids",
+                format!("Player {} is not an active member of this club", player_id),
+            ));
+        }
+
+        sqlx::query("INSERT INTO session_players (session_id, player_id, checked_in_at) VALUES (?, ?, CURRENT_TIMESTAMP)")
+            .bind(session.id)
+            .bind(player_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    // Sit-out fairness starts afresh with each session
+    sqlx::query("UPDATE players SET sit_out_count = 0 WHERE id IN (SELECT player_id FROM session_players WHERE session_id = ?)")
+        .bind(session.id)
+        .execute(&mut *tx)
+        .await?;
+
+    // Commit the transaction
+    tx.commit().await?;
+
+    Ok(session)
+}
+
+#[tauri::command]
+pub async fn get_active_session(
+    club_id: i64,
+    state: State<'_, AppState>,
+) -> Result<Option<Session>, AppError> {
+    let row = sqlx::query(
+        "SELECT id, club_id, court_count, started_at, ended_at FROM sessions WHERE club_id = ? AND ended_at IS NULL ORDER BY id DESC LIMIT 1"
+    )
+    .bind(club_id)
+    .fetch_optional(&state.db)
+    .await?;
+
+    Ok(row.as_ref().map(session_from_row))
+}
+
+#[tauri::command]
+pub async fn end_session(session_id: i64, state: State<'_, AppState>) -> Result<(), AppError> {
+    finish_session(&state.db, session_id).await?;
+
+    state.board.clear_session(session_id);
+
+    // The session has ended either way, so a failed backup is only logged
+    if let Some(settings) = &state.auto_backup {
+        if let Err(e) = auto_backup(&state.db, settings, session_id).await {
+            log::error!("Automatic backup after session {} failed: {}", session_id, e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Marks a session and any games still on court as finished.
+pub async fn finish_session(db: &SqlitePool, session_id: i64) -> Result<(), AppError> {
+    // Start a transaction
+    let mut tx = db.begin().await?;
+
+    let result = sqlx::query("UPDATE sessions SET ended_at = CURRENT_TIMESTAMP WHERE id = ? AND ended_at IS NULL")
+        .bind(session_id)
+        .execute(&mut *tx)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found("Session not found or already ended"));
+    }
+
+    sqlx::query(
+        "UPDATE round_games SET finished_at = CURRENT_TIMESTAMP
+         WHERE finished_at IS NULL AND round_id IN (SELECT id FROM rounds WHERE session_id = ?)"
+    )
+    .bind(session_id)
+    .execute(&mut *tx)
+    .await?;
+
+    // Commit the transaction
+    tx.commit().await?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn generate_next_round(
+    session_id: i64,
+    options: Option<GamesOptions>,
+    seed: Option<u64>,
+    state: State<'_, AppState>,
+) -> Result<SessionRound, AppError> {
+    let saved = next_round(&state.db, session_id, options, seed).await?;
+
+    state.board.show_round(session_id, GamesRound::from(&saved));
+
+    Ok(saved)
+}
+
+/// Generates and stores the next round of a session from the players checked
+/// in, finishing whatever was still on court.
+pub async fn next_round(
+    db: &SqlitePool,
+    session_id: i64,
+    options: Option<GamesOptions>,
+    seed: Option<u64>,
+) -> Result<SessionRound, AppError> {
+    // Start a transaction, taking the write lock up front so the round is drawn
+    // from the same state it's saved into
+    let mut tx = db.begin_with("BEGIN IMMEDIATE").await?;
+
+    let session = fetch_session(&mut tx, session_id).await?;
+    ensure_running(&session)?;
+
+    let players = fetch_session_players(&mut tx, session_id).await?;
+    let previous_sit_out_ids = fetch_previous_sit_out_ids(&mut tx, session_id).await?;
+
+    let previous_rounds = fetch_session_rounds(&mut tx, session_id).await?;
+    let history = PairingHistory::from_games(previous_rounds.iter().flat_map(|round| &round.games));
+
+    let options = options.unwrap_or_default();
+    let num_courts = courts_in_use(players.len(), session.court_count as usize, &options);
+
+    if num_courts == 0 {
+        return Err(AppError::Conflict {
+            field: None,
+            message: "Not enough players to fill a court".to_string(),
+        });
+    }
+
+    let seed = seed.unwrap_or_else(random_seed);
+    let round = generate_round(players, num_courts, &previous_sit_out_ids, &history, &options, seed);
+
+    // A new round replaces whatever is still on court
+    sqlx::query(
+        "UPDATE round_games SET finished_at = CURRENT_TIMESTAMP
+         WHERE finished_at IS NULL AND round_id IN (SELECT id FROM rounds WHERE session_id = ?)"
+    )
+    .bind(session_id)
+    .execute(&mut *tx)
+    .await?;
+
+    let saved = save_round(&mut tx, session_id, round).await?;
+
+    // Commit the transaction
+    tx.commit().await?;
+
+    Ok(saved)
+}
+
+/// Fills a single court that has come free while the other courts keep playing.
+/// The game on that court is marked finished and the next one is drawn from the
+/// players not on another court, with the usual balancing. Whoever has waited
+/// through the most games since they last played goes first, on top of their
+/// sit-out count, so the rotation stays as fair as a full round. The game is
+/// added to the latest round; nobody is counted as sitting out, since the
+/// players left waiting are still in line for the next court to free up.
+#[tauri::command]
+pub async fn next_game_for_court(
+    session_id: i64,
+    court: i32,
+    options: Option<GamesOptions>,
+    seed: Option<u64>,
+    state: State<'_, AppState>,
+) -> Result<SessionRound, AppError> {
+    let saved = refill_court(&state.db, session_id, court, options, seed).await?;
+
+    state.board.show_courts(session_id, GamesRound::from(&saved));
+
+    Ok(saved)
+}
+
+/// Draws and stores the next game for one court. The returned round holds just
+/// that game and the seed that drew it, with everyone still waiting as its
+/// sitting out.
+pub async fn refill_court(
+    db: &SqlitePool,
+    session_id: i64,
+    court: i32,
+    options: Option<GamesOptions>,
+    seed: Option<u64>,
+) -> Result<SessionRound, AppError> {
+    // Start a transaction, taking the write lock up front so two courts freeing
+    // up at once can't both draw the same waiting players
+    let mut tx = db.begin_with("BEGIN IMMEDIATE").await?;
+
+    let session = fetch_session(&mut tx, session_id).await?;
+    ensure_running(&session)?;
+
+    if court < 1 || court > session.court_count {
+        return Err(AppError::validation("court", format!("Court {} is not part of this session", court)));
+    }
+
+    let busy_ids: HashSet<i64> = sqlx::query(
+        "SELECT rgp.player_id FROM round_game_players rgp
+         JOIN round_games rg ON rg.id = rgp.game_id
+         JOIN rounds r ON r.id = rg.round_id
+         WHERE r.session_id = ? AND rg.court != ? AND rg.finished_at IS NULL"
+    )
+    .bind(session_id)
+    .bind(court)
+    .fetch_all(&mut *tx)
+    .await?
+    .into_iter()
+    .map(|row| row.get("player_id"))
+    .collect();
+
+    let mut waiting: Vec<InGamePlayer> = fetch_session_players(&mut tx, session_id)
+        .await?
+        .into_iter()
+        .filter(|p| !busy_ids.contains(&p.id))
+        .collect();
+
+    let options = options.unwrap_or_default();
+    let court_format = options.court_format(court as usize - 1);
+
+    if waiting.len() < court_format.players() {
+        return Err(AppError::Conflict {
+            field: None,
+            message: format!("Not enough waiting players for court {}", court),
+        });
+    }
+
+    // Rank by sit-outs plus games waited, then put the real counts back
+    let games_waited = fetch_games_waited(&mut tx, session_id).await?;
+    let sit_out_counts: HashMap<i64, i32> = waiting.iter().map(|p| (p.id, p.sit_out_count)).collect();
+    for player in &mut waiting {
+        player.sit_out_count += games_waited.get(&player.id).copied().unwrap_or(0);
+    }
+
+    let previous_rounds = fetch_session_rounds(&mut tx, session_id).await?;
+    let history = PairingHistory::from_games(previous_rounds.iter().flat_map(|round| &round.games));
+
+    let court_options = GamesOptions {
+        court_formats: vec![court_format],
+        ..options
+    };
+
+    let seed = seed.unwrap_or_else(random_seed);
+    let mut round = generate_round(waiting, 1, &HashSet::new(), &history, &court_options, seed);
+    let players = round
+        .games
+        .iter_mut()
+        .flat_map(|g| g.team_a.iter_mut().chain(g.team_b.iter_mut()))
+        .chain(round.sitting_out.iter_mut());
+    for player in players {
+        player.sit_out_count = sit_out_counts[&player.id];
+    }
+
+    sqlx::query(
+        "UPDATE round_games SET finished_at = CURRENT_TIMESTAMP
+         WHERE court = ? AND finished_at IS NULL AND round_id IN (SELECT id FROM rounds WHERE session_id = ?)"
+    )
+    .bind(court)
+    .bind(session_id)
+    .execute(&mut *tx)
+    .await?;
+
+    let latest = sqlx::query("SELECT id, round_number FROM rounds WHERE session_id = ? ORDER BY round_number DESC LIMIT 1")
+        .bind(session_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+    let (round_id, round_number) = match latest {
+        Some(row) => (row.get("id"), row.get("round_number")),
+        None => insert_round(&mut tx, session_id, seed).await?,
+    };
+
+    // The games maker numbers the one court it filled as court 1
+    let mut games = Vec::with_capacity(round.games.len());
+    for mut game in round.games {
+        game.court = court as usize;
+        games.push(save_game(&mut tx, session_id, round_id, game).await?);
+    }
+
+    // Commit the transaction
+    tx.commit().await?;
+
+    Ok(SessionRound {
+        id: round_id,
+        round_number,
+        seed: Some(seed),
+        games,
+        sitting_out: round.sitting_out,
+        warnings: round.warnings,
+    })
+}
+
+#[tauri::command]
+pub async fn get_session_history(
+    session_id: i64,
+    state: State<'_, AppState>,
+) -> Result<SessionHistory, AppError> {
+    session_history(&state.db, session_id).await
+}
+
+pub async fn session_history(db: &SqlitePool, session_id: i64) -> Result<SessionHistory, AppError> {
+    let mut conn = db.acquire().await?;
+    let session = fetch_session(&mut conn, session_id).await?;
+    let rounds = fetch_session_rounds(&mut conn, session_id).await?;
+
+    Ok(SessionHistory { session, rounds })
+}
+
+#[tauri::command]
+pub async fn get_session_players(
+    session_id: i64,
+    state: State<'_, AppState>,
+) -> Result<Vec<SessionPlayer>, AppError> {
+    session_players(&state.db, session_id).await
+}
+
+/// Everyone who came to a session, including those who have checked out.
+pub async fn session_players(db: &SqlitePool, session_id: i64) -> Result<Vec<SessionPlayer>, AppError> {
+    let rows = sqlx::query(&format!("{} WHERE sp.session_id = ? ORDER BY p.first_name, p.last_name", SESSION_PLAYER_SELECT))
+        .bind(session_id)
+        .fetch_all(db)
+        .await?;
+
+    Ok(rows.iter().map(session_player_from_row).collect())
+}
+
+#[tauri::command]
+pub async fn check_in_player(
+    session_id: i64,
+    player_id: i64,
+    state: State<'_, AppState>,
+) -> Result<SessionPlayer, AppError> {
+    check_in(&state.db, session_id, player_id).await
+}
+
+/// Adds a player to a running session, or brings them back if they had checked
+/// out. Shared by the organiser's check-in and players checking themselves in.
+pub async fn check_in(db: &SqlitePool, session_id: i64, player_id: i64) -> Result<SessionPlayer, AppError> {
+    // Start a transaction
+    let mut tx = db.begin().await?;
+
+    let session = fetch_session(&mut tx, session_id).await?;
+    ensure_running(&session)?;
+
+    let player = sqlx::query("SELECT id FROM players WHERE id = ?")
+        .bind(player_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+    if player.is_none() {
+        return Err(AppError::not_found("Player not found"));
+    }
+
+    let membership = sqlx::query("SELECT 1 FROM club_memberships WHERE player_id = ? AND club_id = ? AND status = 'Active'")
+        .bind(player_id)
+        .bind(session.club_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+    if membership.is_none() {
+        return Err(AppError::validation("player_id", "Player is not an active member of this club"));
+    }
+
+    let existing = sqlx::query("SELECT checked_out_at FROM session_players WHERE session_id = ? AND player_id = ?")
+        .bind(session_id)
+        .bind(player_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+    if let Some(row) = &existing {
+        if row.get::<Option<String>, _>("checked_out_at").is_none() {
+            return Err(AppError::Conflict {
+                field: None,
+                message: "Player is already checked in".to_string(),
+            });
+        }
+    }
+
+    // Someone arriving late has been waiting, not playing. Credit them with the
+    // average sit-outs of everyone here and treat them as having sat out the
+    // latest round, so they get onto a court next rather than sitting out first.
+    let fair_share = sqlx::query(
+        "SELECT AVG(sit_out_count) as average FROM session_players
+         WHERE session_id = ? AND player_id != ? AND checked_out_at IS NULL"
+    )
+    .bind(session_id)
+    .bind(player_id)
+    .fetch_one(&mut *tx)
+    .await?
+    .get::<Option<f64>, _>("average")
+    .map_or(0, |average| average.ceil() as i32);
+
+    let last_round: Option<i32> = sqlx::query("SELECT MAX(round_number) as last_round FROM rounds WHERE session_id = ?")
+        .bind(session_id)
+        .fetch_one(&mut *tx)
+        .await?
+        .get("last_round");
+
+    if existing.is_some() {
+        sqlx::query(
+            "UPDATE session_players
+             SET checked_out_at = NULL, sit_out_count = MAX(sit_out_count, ?), last_sat_out_round = COALESCE(?, last_sat_out_round)
+             WHERE session_id = ? AND player_id = ?"
+        )
+        .bind(fair_share)
+        .bind(last_round)
+        .bind(session_id)
+        .bind(player_id)
+        .execute(&mut *tx)
+        .await?;
+    } else {
+        sqlx::query(
+            "INSERT INTO session_players (session_id, player_id, sit_out_count, last_sat_out_round, checked_in_at)
+             VALUES (?, ?, ?, ?, CURRENT_TIMESTAMP)"
+        )
+        .bind(session_id)
+        .bind(player_id)
+        .bind(fair_share)
+        .bind(last_round)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query(
+        "UPDATE players SET sit_out_count = (SELECT sit_out_count FROM session_players WHERE session_id = ? AND player_id = ?) WHERE id = ?"
+    )
+    .bind(session_id)
+    .bind(player_id)
+    .bind(player_id)
+    .execute(&mut *tx)
+    .await?;
+
+    let row = sqlx::query(&format!("{} WHERE sp.session_id = ? AND sp.player_id = ?", SESSION_PLAYER_SELECT))
+        .bind(session_id)
+        .bind(player_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    // Commit the transaction
+    tx.commit().await?;
+
+    Ok(session_player_from_row(&row))
+}
+
+#[tauri::command]
+pub async fn check_out_player(
+    session_id: i64,
+    player_id: i64,
+    state: State<'_, AppState>,
+) -> Result<SessionPlayer, AppError> {
+    check_out(&state.db, session_id, player_id).await
+}
+
+/// Takes a player out of the rotation of a running session. Their counters are
+/// kept in case they come back.
+pub async fn check_out(db: &SqlitePool, session_id: i64, player_id: i64) -> Result<SessionPlayer, AppError> {
+    let session = fetch_session(&mut *db.acquire().await?, session_id).await?;
+    ensure_running(&session)?;
+
+    let result = sqlx::query(
+        "UPDATE session_players SET checked_out_at = CURRENT_TIMESTAMP
+         WHERE session_id = ? AND player_id = ? AND checked_out_at IS NULL"
+    )
+    .bind(session_id)
+    .bind(player_id)
+    .execute(db)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::Conflict {
+            field: None,
+            message: "Player is not checked in to this session".to_string(),
+        });
+    }
+
+    let row = sqlx::query(&format!("{} WHERE sp.session_id = ? AND sp.player_id = ?", SESSION_PLAYER_SELECT))
+        .bind(session_id)
+        .bind(player_id)
+        .fetch_one(db)
+        .await?;
+
+    Ok(session_player_from_row(&row))
+}
+
+const SESSION_PLAYER_SELECT: &str = "SELECT p.id, p.first_name, p.last_name, p.skill_level, p.gender,
+     sp.sit_out_count, sp.games_played, sp.last_sat_out_round, sp.checked_in_at, sp.checked_out_at
+     FROM session_players sp
+     JOIN players p ON p.id = sp.player_id";
+
+fn session_player_from_row(row: &SqliteRow) -> SessionPlayer {
+    SessionPlayer {
+        player_id: row.get("id"),
+        first_name: row.get("first_name"),
+        last_name: row.get("last_name"),
+        skill_level: row.get("skill_level"),
+        gender: Gender::from_db(row.get("gender")),
+        sit_out_count: row.get("sit_out_count"),
+        games_played: row.get("games_played"),
+        last_sat_out_round: row.get("last_sat_out_round"),
+        checked_in_at: row.get("checked_in_at"),
+        checked_out_at: row.get("checked_out_at"),
+    }
+}
+
+fn session_from_row(row: &SqliteRow) -> Session {
+    Session {
+        id: row.get("id"),
+        club_id: row.get("club_id"),
+        court_count: row.get("court_count"),
+        started_at: row.get("started_at"),
+        ended_at: row.get("ended_at"),
+    }
+}
+
+async fn fetch_session(conn: &mut SqliteConnection, session_id: i64) -> Result<Session, AppError> {
+    let row = sqlx::query("SELECT id, club_id, court_count, started_at, ended_at FROM sessions WHERE id = ?")
+        .bind(session_id)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+    match row {
+        Some(row) => Ok(session_from_row(&row)),
+        None => Err(AppError::not_found("Session not found")),
+    }
+}
+
+/// Rounds can only be drawn and players checked in or out while a session is running.
+fn ensure_running(session: &Session) -> Result<(), AppError> {
+    if session.ended_at.is_some() {
+        return Err(AppError::Conflict {
+            field: None,
+            message: "Session has already ended".to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Loads the attendees still checked in to a session, with their sit-out
+/// count for that session.
+async fn fetch_session_players(conn: &mut SqliteConnection, session_id: i64) -> Result<Vec<InGamePlayer>, AppError> {
+    let rows = sqlx::query(
+        "SELECT p.id, p.first_name, p.last_name, p.skill_level, p.gender, p.rating, sp.sit_out_count
+         FROM session_players sp
+         JOIN players p ON p.id = sp.player_id
+         WHERE sp.session_id = ? AND sp.checked_out_at IS NULL
+         ORDER BY p.first_name, p.last_name"
+    )
+    .bind(session_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let players = rows
+        .into_iter()
+        .map(|row| InGamePlayer {
+            id: row.get("id"),
+            first_name: row.get("first_name"),
+            last_name: row.get("last_name"),
+            skill_level: row.get("skill_level"),
+            gender: Gender::from_db(row.get("gender")),
+            sit_out_count: row.get("sit_out_count"),
+            rating: row.get("rating"),
+        })
+        .collect();
+
+    Ok(players)
+}
+
+/// Players who sat out the latest round of a session, who get priority for the next game.
+async fn fetch_previous_sit_out_ids(conn: &mut SqliteConnection, session_id: i64) -> Result<HashSet<i64>, AppError> {
+    let ids = sqlx::query(
+        "SELECT player_id FROM session_players
+         WHERE session_id = ? AND last_sat_out_round = (SELECT MAX(round_number) FROM rounds WHERE session_id = ?)"
+    )
+    .bind(session_id)
+    .bind(session_id)
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .map(|row| row.get("player_id"))
+    .collect();
+
+    Ok(ids)
+}
+
+/// How many games have started since each checked-in player last played, or
+/// since they arrived if they haven't played yet.
+async fn fetch_games_waited(conn: &mut SqliteConnection, session_id: i64) -> Result<HashMap<i64, i32>, AppError> {
+    let waited = sqlx::query(
+        "SELECT sp.player_id,
+                (SELECT COUNT(*) FROM round_games rg
+                 JOIN rounds r ON r.id = rg.round_id
+                 WHERE r.session_id = sp.session_id
+                   AND rg.started_at >= sp.checked_in_at
+                   AND rg.id > COALESCE((
+                       SELECT MAX(rgp.game_id) FROM round_game_players rgp
+                       JOIN round_games played ON played.id = rgp.game_id
+                       JOIN rounds pr ON pr.id = played.round_id
+                       WHERE pr.session_id = sp.session_id AND rgp.player_id = sp.player_id
+                   ), 0)) as games_waited
+         FROM session_players sp
+         WHERE sp.session_id = ? AND sp.checked_out_at IS NULL"
+    )
+    .bind(session_id)
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .map(|row| (row.get("player_id"), row.get("games_waited")))
+    .collect();
+
+    Ok(waited)
+}
+
+/// Stores a freshly generated round as the next round of the session and updates
+/// the games played and sit-out counters of everyone in it.
+async fn save_round(conn: &mut SqliteConnection, session_id: i64, round: GamesRound) -> Result<SessionRound, AppError> {
+    let (round_id, round_number) = insert_round(conn, session_id, round.seed).await?;
+
+    let mut games = Vec::with_capacity(round.games.len());
+    for game in round.games {
+        games.push(save_game(conn, session_id, round_id, game).await?);
+    }
+
+    for player in &round.sitting_out {
+        sqlx::query("INSERT INTO round_sit_outs (round_id, player_id) VALUES (?, ?)")
+            .bind(round_id)
+            .bind(player.id)
+            .execute(&mut *conn)
+            .await?;
+
+        sqlx::query(
+            "UPDATE session_players SET sit_out_count = ?, last_sat_out_round = ? WHERE session_id = ? AND player_id = ?"
+        )
+        .bind(player.sit_out_count)
+        .bind(round_number)
+        .bind(session_id)
+        .bind(player.id)
+        .execute(&mut *conn)
+        .await?;
+
+        // Mirror the count onto the player so the members list shows the current session
+        sqlx::query("UPDATE players SET sit_out_count = ? WHERE id = ?")
+            .bind(player.sit_out_count)
+            .bind(player.id)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    Ok(SessionRound {
+        id: round_id,
+        round_number,
+        seed: Some(round.seed),
+        games,
+        sitting_out: round.sitting_out,
+        warnings: round.warnings,
+    })
+}
+
+/// Adds the next round number to a session, returning its id and number.
+async fn insert_round(conn: &mut SqliteConnection, session_id: i64, seed: u64) -> Result<(i64, i32), AppError> {
+    let row = sqlx::query(
+        "INSERT INTO rounds (session_id, round_number, seed)
+         VALUES (?, (SELECT COALESCE(MAX(round_number), 0) + 1 FROM rounds WHERE session_id = ?), ?)
+         RETURNING id, round_number"
+    )
+    .bind(session_id)
+    .bind(session_id)
+    .bind(seed as i64)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok((row.get("id"), row.get("round_number")))
+}
+
+/// Stores a game as started now and counts it towards its players' games played.
+async fn save_game(conn: &mut SqliteConnection, session_id: i64, round_id: i64, mut game: Game) -> Result<Game, AppError> {
+    let game_row = sqlx::query("INSERT INTO round_games (round_id, court, started_at) VALUES (?, ?, CURRENT_TIMESTAMP) RETURNING id")
+        .bind(round_id)
+        .bind(game.court as i64)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    let game_id: i64 = game_row.get("id");
+
+    let sides = game.team_a.iter().map(|p| (p, "A")).chain(game.team_b.iter().map(|p| (p, "B")));
+    for (position, (player, team)) in sides.enumerate() {
+        sqlx::query("INSERT INTO round_game_players (game_id, player_id, position, team) VALUES (?, ?, ?, ?)")
+            .bind(game_id)
+            .bind(player.id)
+            .bind(position as i64)
+            .bind(team)
+            .execute(&mut *conn)
+            .await?;
+
+        sqlx::query("UPDATE session_players SET games_played = games_played + 1 WHERE session_id = ? AND player_id = ?")
+            .bind(session_id)
+            .bind(player.id)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    game.id = Some(game_id);
+    Ok(game)
+}
+
+/// Rebuilds every stored round of a session in order. Each player's
+/// `sit_out_count` is the running total as of that round.
+pub async fn fetch_session_rounds(conn: &mut SqliteConnection, session_id: i64) -> Result<Vec<SessionRound>, AppError> {
+    let round_rows = sqlx::query("SELECT id, round_number, seed FROM rounds WHERE session_id = ? ORDER BY round_number")
+        .bind(session_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+    let game_rows = sqlx::query(
+        "SELECT rg.id as game_id, rg.round_id, rg.court, rgp.team, p.id, p.first_name, p.last_name, p.skill_level, p.gender, p.rating
+         FROM round_games rg
+         JOIN rounds r ON r.id = rg.round_id
+         JOIN round_game_players rgp ON rgp.game_id = rg.id
+         JOIN players p ON p.id = rgp.player_id
+         WHERE r.session_id = ?
+         ORDER BY r.round_number, rg.court, rg.id, rgp.position"
+    )
+    .bind(session_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let sit_out_rows = sqlx::query(
+        "SELECT rso.round_id, p.id, p.first_name, p.last_name, p.skill_level, p.gender, p.rating
+         FROM round_sit_outs rso
+         JOIN rounds r ON r.id = rso.round_id
+         JOIN players p ON p.id = rso.player_id
+         WHERE r.session_id = ?
+         ORDER BY r.round_number, p.first_name, p.last_name"
+    )
+    .bind(session_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut rounds: Vec<SessionRound> = round_rows
+        .into_iter()
+        .map(|row| SessionRound {
+            id: row.get("id"),
+            round_number: row.get("round_number"),
+            seed: row.get::<Option<i64>, _>("seed").map(|seed| seed as u64),
+            games: Vec::new(),
+            sitting_out: Vec::new(),
+            warnings: Vec::new(),
+        })
+        .collect();
+
+    let round_index: HashMap<i64, usize> = rounds.iter().enumerate().map(|(i, r)| (r.id, i)).collect();
+
+    for row in &game_rows {
+        let round = &mut rounds[round_index[&row.get::<i64, _>("round_id")]];
+        let game_id: i64 = row.get("game_id");
+
+        if round.games.last().map(|g| g.id) != Some(Some(game_id)) {
+            let court = row.get::<i64, _>("court") as usize;
+            round.games.push(Game::new(Some(game_id), court, Vec::new(), Vec::new()));
+        }
+
+        if let Some(game) = round.games.last_mut() {
+            let player = in_game_player_from_row(row);
+            match row.get::<String, _>("team").as_str() {
+                "A" => game.team_a.push(player),
+                _ => game.team_b.push(player),
+            }
+        }
+    }
+
+    for row in &sit_out_rows {
+        rounds[round_index[&row.get::<i64, _>("round_id")]]
+            .sitting_out
+            .push(in_game_player_from_row(row));
+    }
+
+    for game in rounds.iter_mut().flat_map(|r| r.games.iter_mut()) {
+        let team_a = std::mem::take(&mut game.team_a);
+        let team_b = std::mem::take(&mut game.team_b);
+        *game = Game::new(game.id, game.court, team_a, team_b);
+    }
+
+    let mut results = fetch_session_results(&mut *conn, session_id).await?;
+    for game in rounds.iter_mut().flat_map(|r| r.games.iter_mut()) {
+        game.result = game.id.and_then(|id| results.remove(&id));
+    }
+
+    // Replay the rounds so each player carries their sit-out count at that point in the night
+    let mut sit_out_counts: HashMap<i64, i32> = HashMap::new();
+    for round in &mut rounds {
+        for player in &mut round.sitting_out {
+            let count = sit_out_counts.entry(player.id).or_insert(0);
+            *count += 1;
+            player.sit_out_count = *count;
+        }
+        for player in round
+            .games
+            .iter_mut()
+            .flat_map(|g| g.team_a.iter_mut().chain(g.team_b.iter_mut()))
+        {
+            player.sit_out_count = sit_out_counts.get(&player.id).copied().unwrap_or(0);
+        }
+    }
+
+    Ok(rounds)
+}
+
+fn in_game_player_from_row(row: &SqliteRow) -> InGamePlayer {
+    InGamePlayer {
+        id: row.get("id"),
+        first_name: row.get("first_name"),
+        last_name: row.get("last_name"),
+        skill_level: row.get("skill_level"),
+        gender: Gender::from_db(row.get("gender")),
+        sit_out_count: 0,
+        rating: row.get("rating"),
+    }
+}
@@ -0,0 +1,298 @@
+use crate::commands::results::results_from_rows;
+use crate::models::{AppState, PlayerGame, PlayerStats, PlayerSummary, PlayerTally, RatingPoint, Team};
+use crate::utils::errors::AppError;
+use sqlx::{Row, SqlitePool};
+use std::collections::HashMap;
+use tauri::State;
+
+/// How many partners and opponents the stats list.
+const MOST_FREQUENT_LIMIT: usize = 5;
+
+/// Limits a query to sessions that started within an optional inclusive date
+/// range. Binds `from` twice, then `to` twice.
+pub const SESSION_DATE_RANGE: &str = "(? IS NULL OR date(s.started_at) >= date(?)) AND (? IS NULL OR date(s.started_at) <= date(?))";
+
+/// Totals for a player over sessions between `from` and `to` (inclusive,
+/// `YYYY-MM-DD`). Either end can be left open.
+#[tauri::command]
+pub async fn get_player_stats(
+    player_id: i64,
+    from: Option<String>,
+    to: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<PlayerStats, AppError> {
+    player_stats(&state.db, player_id, from, to).await
+}
+
+pub async fn player_stats(
+    db: &SqlitePool,
+    player_id: i64,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<PlayerStats, AppError> {
+    validate_date("from", &from)?;
+    validate_date("to", &to)?;
+    ensure_player_exists(db, player_id).await?;
+
+    let games = fetch_player_games(db, player_id, &from, &to).await?;
+
+    let sit_outs: i64 = sqlx::query(&format!(
+        "SELECT COUNT(*) as sit_outs FROM round_sit_outs rso
+         JOIN rounds r ON r.id = rso.round_id
+         JOIN sessions s ON s.id = r.session_id
+         WHERE rso.player_id = ? AND {}",
+        SESSION_DATE_RANGE
+    ))
+    .bind(player_id)
+    .bind(&from)
+    .bind(&from)
+    .bind(&to)
+    .bind(&to)
+    .fetch_one(db)
+    .await?
+    .get("sit_outs");
+
+    let rating_trend = sqlx::query(&format!(
+        "SELECT rh.game_id, rh.recorded_at, rh.rating_after, rh.deviation_after FROM rating_history rh
+         JOIN round_games rg ON rg.id = rh.game_id
+         JOIN rounds r ON r.id = rg.round_id
+         JOIN sessions s ON s.id = r.session_id
+         WHERE rh.player_id = ? AND {}
+         ORDER BY rh.id",
+        SESSION_DATE_RANGE
+    ))
+    .bind(player_id)
+    .bind(&from)
+    .bind(&from)
+    .bind(&to)
+    .bind(&to)
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|row| RatingPoint {
+        game_id: row.get("game_id"),
+        recorded_at: row.get("recorded_at"),
+        rating: row.get("rating_after"),
+        deviation: row.get("deviation_after"),
+    })
+    .collect();
+
+    let mut wins = 0;
+    let mut losses = 0;
+    let mut points_for = 0;
+    let mut points_against = 0;
+    let mut partners: HashMap<i64, PlayerTally> = HashMap::new();
+    let mut opponents: HashMap<i64, PlayerTally> = HashMap::new();
+
+    for game in &games {
+        let won = game.won.unwrap_or(false);
+        match game.won {
+            Some(true) => wins += 1,
+            Some(false) => losses += 1,
+            None => {}
+        }
+
+        if let Some(result) = &game.result {
+            for set in &result.sets {
+                let (mine, theirs) = match game.team {
+                    Team::A => (set.team_a, set.team_b),
+                    Team::B => (set.team_b, set.team_a),
+                };
+                points_for += mine;
+                points_against += theirs;
+            }
+        }
+
+        for (people, tallies) in [(&game.partners, &mut partners), (&game.opponents, &mut opponents)] {
+            for person in people {
+                let tally = tallies.entry(person.player_id).or_insert_with(|| PlayerTally {
+                    player_id: person.player_id,
+                    first_name: person.first_name.clone(),
+                    last_name: person.last_name.clone(),
+                    games: 0,
+                    wins: 0,
+                });
+                tally.games += 1;
+                if won {
+                    tally.wins += 1;
+                }
+            }
+        }
+    }
+
+    let decided = wins + losses;
+
+    Ok(PlayerStats {
+        player_id,
+        from,
+        to,
+        games_played: games.len() as i32,
+        sit_outs: sit_outs as i32,
+        wins,
+        losses,
+        win_rate: (decided > 0).then(|| wins as f64 / decided as f64),
+        points_for,
+        points_against,
+        points_difference: points_for - points_against,
+        partners: most_frequent(partners),
+        opponents: most_frequent(opponents),
+        rating_trend,
+    })
+}
+
+/// Every game a player has played, oldest first, with who they played with and against.
+#[tauri::command]
+pub async fn get_player_history(player_id: i64, state: State<'_, AppState>) -> Result<Vec<PlayerGame>, AppError> {
+    player_history(&state.db, player_id).await
+}
+
+pub async fn player_history(db: &SqlitePool, player_id: i64) -> Result<Vec<PlayerGame>, AppError> {
+    ensure_player_exists(db, player_id).await?;
+    fetch_player_games(db, player_id, &None, &None).await
+}
+
+async fn ensure_player_exists(db: &SqlitePool, player_id: i64) -> Result<(), AppError> {
+    let player = sqlx::query("SELECT id FROM players WHERE id = ?")
+        .bind(player_id)
+        .fetch_optional(db)
+        .await?;
+
+    match player {
+        Some(_) => Ok(()),
+        None => Err(AppError::not_found("Player not found")),
+    }
+}
+
+pub fn validate_date(field: &str, date: &Option<String>) -> Result<(), AppError> {
+    let Some(date) = date else {
+        return Ok(());
+    };
+
+    let well_formed = date.len() == 10
+        && date.char_indices().all(|(i, c)| if i == 4 || i == 7 { c == '-' } else { c.is_ascii_digit() });
+
+    if well_formed {
+        Ok(())
+    } else {
+        Err(AppError::validation(field, format!("Invalid date '{}', expected YYYY-MM-DD", date)))
+    }
+}
+
+fn most_frequent(tallies: HashMap<i64, PlayerTally>) -> Vec<PlayerTally> {
+    let mut tallies: Vec<PlayerTally> = tallies.into_values().collect();
+    tallies.sort_by(|a, b| {
+        b.games
+            .cmp(&a.games)
+            .then_with(|| b.wins.cmp(&a.wins))
+            .then_with(|| (&a.first_name, &a.last_name).cmp(&(&b.first_name, &b.last_name)))
+    });
+    tallies.truncate(MOST_FREQUENT_LIMIT);
+    tallies
+}
+
+async fn fetch_player_games(
+    db: &SqlitePool,
+    player_id: i64,
+    from: &Option<String>,
+    to: &Option<String>,
+) -> Result<Vec<PlayerGame>, AppError> {
+    let game_rows = sqlx::query(&format!(
+        "SELECT rg.id as game_id, rgp.team, r.session_id, r.round_number, rg.court, rg.started_at
+         FROM round_game_players rgp
+         JOIN round_games rg ON rg.id = rgp.game_id
+         JOIN rounds r ON r.id = rg.round_id
+         JOIN sessions s ON s.id = r.session_id
+         WHERE rgp.player_id = ? AND {}
+         ORDER BY s.started_at, r.round_number, rg.id",
+        SESSION_DATE_RANGE
+    ))
+    .bind(player_id)
+    .bind(from)
+    .bind(from)
+    .bind(to)
+    .bind(to)
+    .fetch_all(db)
+    .await?;
+
+    let other_rows = sqlx::query(
+        "SELECT mine.game_id, mine.team = other.team as same_team, p.id, p.first_name, p.last_name
+         FROM round_game_players mine
+         JOIN round_game_players other ON other.game_id = mine.game_id AND other.player_id != mine.player_id
+         JOIN players p ON p.id = other.player_id
+         WHERE mine.player_id = ?
+         ORDER BY other.position"
+    )
+    .bind(player_id)
+    .fetch_all(db)
+    .await?;
+
+    let result_rows = sqlx::query(
+        "SELECT gr.game_id, gr.winner, gr.recorded_at, grs.team_a_score, grs.team_b_score
+         FROM round_game_players mine
+         JOIN game_results gr ON gr.game_id = mine.game_id
+         JOIN game_result_sets grs ON grs.game_id = gr.game_id
+         WHERE mine.player_id = ?
+         ORDER BY gr.game_id, grs.set_number"
+    )
+    .bind(player_id)
+    .fetch_all(db)
+    .await?;
+
+    let rating_rows = sqlx::query("SELECT game_id, rating_before, rating_after FROM rating_history WHERE player_id = ?")
+        .bind(player_id)
+        .fetch_all(db)
+        .await?;
+
+    let mut results = results_from_rows(&result_rows);
+    let ratings: HashMap<i64, (f64, f64)> = rating_rows
+        .iter()
+        .map(|row| (row.get("game_id"), (row.get("rating_before"), row.get("rating_after"))))
+        .collect();
+
+    let mut games: Vec<PlayerGame> = game_rows
+        .iter()
+        .map(|row| {
+            let game_id: i64 = row.get("game_id");
+            let team = Team::from_db(row.get("team"));
+            let result = results.remove(&game_id);
+            let rating = ratings.get(&game_id);
+
+            PlayerGame {
+                game_id,
+                session_id: row.get("session_id"),
+                round_number: row.get("round_number"),
+                court: row.get("court"),
+                played_at: row.get("started_at"),
+                team,
+                partners: Vec::new(),
+                opponents: Vec::new(),
+                won: result.as_ref().map(|r| r.winner == team),
+                result,
+                rating_before: rating.map(|r| r.0),
+                rating_after: rating.map(|r| r.1),
+            }
+        })
+        .collect();
+
+    let game_index: HashMap<i64, usize> = games.iter().enumerate().map(|(i, g)| (g.game_id, i)).collect();
+
+    for row in &other_rows {
+        let Some(&index) = game_index.get(&row.get::<i64, _>("game_id")) else {
+            continue;
+        };
+
+        let person = PlayerSummary {
+            player_id: row.get("id"),
+            first_name: row.get("first_name"),
+            last_name: row.get("last_name"),
+        };
+
+        if row.get::<bool, _>("same_team") {
+            games[index].partners.push(person);
+        } else {
+            games[index].opponents.push(person);
+        }
+    }
+
+    Ok(games)
+}
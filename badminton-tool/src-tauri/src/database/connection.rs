@@ -1,9 +1,8 @@
-use crate::utils::config::get_database_path;
+use sqlx::sqlite::SqliteConnectOptions;
 use sqlx::SqlitePool;
+use std::path::{Path, PathBuf};
 
-pub async fn establish_connection() -> Result<SqlitePool, sqlx::Error> {
-    let db_path = get_database_path();
-
+pub async fn establish_connection(db_path: &Path) -> Result<SqlitePool, sqlx::Error> {
     // Create the parent directories if they don't exist
     if let Some(parent) = db_path.parent() {
         std::fs::create_dir_all(parent).map_err(|e| {
@@ -14,7 +13,35 @@ pub async fn establish_connection() -> Result<SqlitePool, sqlx::Error> {
 
     println!("Database will be created at: {:?}", db_path);
 
-    let db = SqlitePool::connect(&format!("sqlite:{}", db_path.display())).await?;
+    let options = SqliteConnectOptions::new().filename(db_path).create_if_missing(true);
+    let db = SqlitePool::connect_with(options).await?;
 
     Ok(db)
 }
+
+/// Copies the database from where older releases kept it to `db_path`, unless
+/// there's already a database there. Returns whether anything was copied.
+pub fn copy_legacy_database(legacy_path: &Path, db_path: &Path) -> std::io::Result<bool> {
+    if db_path.exists() || !legacy_path.is_file() {
+        return Ok(false);
+    }
+
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    // Bring the write-ahead log too, it may hold changes not yet in the main file
+    let legacy_wal = wal_path(legacy_path);
+    if legacy_wal.is_file() {
+        std::fs::copy(&legacy_wal, wal_path(db_path))?;
+    }
+    std::fs::copy(legacy_path, db_path)?;
+
+    Ok(true)
+}
+
+fn wal_path(db_path: &Path) -> PathBuf {
+    let mut path = db_path.as_os_str().to_owned();
+    path.push("-wal");
+    PathBuf::from(path)
+}
@@ -0,0 +1,97 @@
+use sqlx::{Row, SqliteConnection};
+
+/// Tables a database from before migrations were versioned can have. Every
+/// release made clubs and players; the database the app shipped with also has
+/// users and the unused club_members.
+const BASELINE_TABLES: &[&str] = &["clubs", "players", "users", "club_members"];
+
+const BASELINE_CLUB_COLUMNS: &[&str] = &["id", "name", "description", "created_by", "created_at"];
+
+/// Every release has these, along with `sit_out_count` or the older `sit_off_count`.
+const BASELINE_PLAYER_COLUMNS: &[&str] = &[
+    "id",
+    "first_name",
+    "last_name",
+    "email",
+    "gender",
+    "club_id",
+    "created_at",
+    "skill_level",
+];
+
+/// Brings a database made before migrations were versioned into the shape of
+/// migration 1, which it's then recorded at. Only the schema the app shipped
+/// with is recognised; anything else is refused rather than guessed at.
+pub async fn adopt_baseline(conn: &mut SqliteConnection) -> Result<(), sqlx::Error> {
+    let tables: Vec<String> = sqlx::query(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND name != 'schema_migrations'"
+    )
+        .fetch_all(&mut *conn)
+        .await?
+        .iter()
+        .map(|row| row.get("name"))
+        .collect();
+
+    let clubs = columns(conn, "clubs").await?;
+    let players = columns(conn, "players").await?;
+
+    let recognised = tables.iter().all(|table| BASELINE_TABLES.contains(&table.as_str()))
+        && BASELINE_CLUB_COLUMNS.iter().all(|column| clubs.iter().any(|c| c == column))
+        && BASELINE_PLAYER_COLUMNS.iter().all(|column| players.iter().any(|c| c == column))
+        && players.iter().any(|c| c == "sit_out_count" || c == "sit_off_count");
+
+    if !recognised {
+        return Err(sqlx::Error::Configuration(
+            "The database doesn't match the schema of any release of the app, so it can't be upgraded".into(),
+        ));
+    }
+
+    // Only the database the app shipped with had users, which clubs.created_by refers to
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS users (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            username TEXT UNIQUE NOT NULL,
+            password_hash TEXT NOT NULL,
+            role TEXT NOT NULL
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    if !clubs.iter().any(|c| c == "member_count") {
+        sqlx::query("ALTER TABLE clubs ADD COLUMN member_count INTEGER DEFAULT 0")
+            .execute(&mut *conn)
+            .await?;
+
+        sqlx::query("UPDATE clubs SET member_count = (SELECT COUNT(*) FROM players WHERE players.club_id = clubs.id)")
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    // The earliest releases called the sit-out count sit_off_count
+    if !players.iter().any(|c| c == "sit_out_count") {
+        sqlx::query("ALTER TABLE players ADD COLUMN sit_out_count INTEGER DEFAULT 0")
+            .execute(&mut *conn)
+            .await?;
+
+        sqlx::query("UPDATE players SET sit_out_count = COALESCE(sit_off_count, 0)")
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    Ok(())
+}
+
+async fn columns(conn: &mut SqliteConnection, table: &str) -> Result<Vec<String>, sqlx::Error> {
+    let columns = sqlx::query("SELECT name FROM pragma_table_info(?)")
+        .bind(table)
+        .fetch_all(&mut *conn)
+        .await?
+        .iter()
+        .map(|row| row.get("name"))
+        .collect();
+
+    Ok(columns)
+}
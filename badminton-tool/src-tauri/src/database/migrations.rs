@@ -1,70 +1,171 @@
-use sqlx::SqlitePool;
+use super::legacy::adopt_baseline;
+use sqlx::{Row, SqliteConnection, SqlitePool};
+
+/// A numbered schema change. Migrations only go forward and a released one must
+/// never be edited; fix mistakes with a new migration instead.
+pub struct Migration {
+    pub version: i64,
+    pub description: &'static str,
+    pub sql: &'static str,
+}
+
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Clubs and players",
+        sql: include_str!("migrations/0001_clubs_and_players.sql"),
+    },
+    Migration {
+        version: 2,
+        description: "Sessions and rounds",
+        sql: include_str!("migrations/0002_sessions_and_rounds.sql"),
+    },
+    Migration {
+        version: 3,
+        description: "Game results",
+        sql: include_str!("migrations/0003_game_results.sql"),
+    },
+    Migration {
+        version: 4,
+        description: "Player ratings",
+        sql: include_str!("migrations/0004_ratings.sql"),
+    },
+    Migration {
+        version: 5,
+        description: "Club memberships",
+        sql: include_str!("migrations/0005_club_memberships.sql"),
+    },
+    Migration {
+        version: 6,
+        description: "Player check-in PINs",
+        sql: include_str!("migrations/0006_player_pins.sql"),
+    },
+    Migration {
+        version: 7,
+        description: "Club roles",
+        sql: include_str!("migrations/0007_club_roles.sql"),
+    },
+    Migration {
+        version: 8,
+        description: "Audit log",
+        sql: include_str!("migrations/0008_audit_log.sql"),
+    },
+];
+
+/// The version a database made before migrations were versioned is adopted at.
+const BASELINE_SCHEMA_VERSION: i64 = 1;
+
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
 
 pub async fn run_migrations(db: &SqlitePool) -> Result<(), sqlx::Error> {
-    // Create clubs table with member_count
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS clubs (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            name TEXT UNIQUE NOT NULL,
-            description TEXT,
-            member_count INTEGER DEFAULT 0,
-            created_by INTEGER,
-            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-            FOREIGN KEY (created_by) REFERENCES users (id)
-        )
-        "#,
-    )
-    .execute(db)
-    .await?;
+    migrate_to(db, latest_version()).await
+}
+
+/// Applies every pending migration up to and including `target`, each in its own
+/// transaction. Stops at the first one that fails, leaving the database at the
+/// last version that succeeded.
+pub async fn migrate_to(db: &SqlitePool, target: i64) -> Result<(), sqlx::Error> {
+    adopt_unversioned(db).await?;
+
+    let current = schema_version(db).await?;
+    if current > latest_version() {
+        return Err(sqlx::Error::Configuration(
+            format!(
+                "Database schema version {} is newer than this app supports ({})",
+                current,
+                latest_version()
+            )
+            .into(),
+        ));
+    }
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current && m.version <= target) {
+        // Start a transaction
+        let mut tx = db.begin().await?;
+
+        sqlx::Executor::execute(&mut *tx, migration.sql).await?;
+        record_migration(&mut tx, migration).await?;
+
+        // Commit the transaction
+        tx.commit().await?;
+    }
+
+    Ok(())
+}
+
+/// The highest migration applied to the database, 0 for an empty one.
+pub async fn schema_version(db: &SqlitePool) -> Result<i64, sqlx::Error> {
+    if !table_exists(db, "schema_migrations").await? {
+        return Ok(0);
+    }
+
+    let version: i64 = sqlx::query("SELECT COALESCE(MAX(version), 0) as version FROM schema_migrations")
+        .fetch_one(db)
+        .await?
+        .get("version");
+
+    Ok(version)
+}
 
-    // Add member_count column if it doesn't exist (for existing databases)
-    sqlx::query("ALTER TABLE clubs ADD COLUMN member_count INTEGER DEFAULT 0")
-        .execute(db)
-        .await
-        .ok(); // Ignore error if column already exists
+/// Creates the schema_migrations table. A database that already has tables without
+/// it predates versioning, so it's brought into the shape of the first migration
+/// and marked as being at `BASELINE_SCHEMA_VERSION`.
+async fn adopt_unversioned(db: &SqlitePool) -> Result<(), sqlx::Error> {
+    if table_exists(db, "schema_migrations").await? {
+        return Ok(());
+    }
+
+    let unversioned = sqlx::query("SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' LIMIT 1")
+        .fetch_optional(db)
+        .await?
+        .is_some();
+
+    // Start a transaction
+    let mut tx = db.begin().await?;
 
-    // Create players table
     sqlx::query(
         r#"
-        CREATE TABLE IF NOT EXISTS players (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            first_name TEXT NOT NULL,
-            last_name TEXT NOT NULL,
-            email TEXT UNIQUE NOT NULL,
-            gender TEXT NOT NULL CHECK (gender IN ('Male', 'Female')),
-            club_id INTEGER NOT NULL,
-            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-            skill_level INTEGER NOT NULL CHECK (skill_level BETWEEN 1 AND 50),
-            sit_out_count INTEGER DEFAULT 0,
-            FOREIGN KEY (club_id) REFERENCES clubs(id) ON DELETE CASCADE
+        CREATE TABLE schema_migrations (
+            version INTEGER PRIMARY KEY,
+            description TEXT NOT NULL,
+            applied_at DATETIME DEFAULT CURRENT_TIMESTAMP
         )
         "#,
     )
-    .execute(db)
+    .execute(&mut *tx)
     .await?;
 
-    // Reset sit_out_count for all players on app load
-    sqlx::query("UPDATE players SET sit_out_count = 0")
-        .execute(db)
-        .await
-        .ok();
+    if unversioned {
+        adopt_baseline(&mut tx).await?;
 
-    // Initialize member_count for existing clubs
-    sqlx::query(
-        r#"
-        UPDATE clubs 
-        SET member_count = (
-            SELECT COUNT(*) 
-            FROM players 
-            WHERE players.club_id = clubs.id
-        ) 
-        WHERE member_count = 0
-        "#
-    )
-    .execute(db)
-    .await?;
+        for migration in MIGRATIONS.iter().take_while(|m| m.version <= BASELINE_SCHEMA_VERSION) {
+            record_migration(&mut tx, migration).await?;
+        }
+    }
+
+    // Commit the transaction
+    tx.commit().await?;
+
+    Ok(())
+}
+
+async fn record_migration(conn: &mut SqliteConnection, migration: &Migration) -> Result<(), sqlx::Error> {
+    sqlx::query("INSERT INTO schema_migrations (version, description) VALUES (?, ?)")
+        .bind(migration.version)
+        .bind(migration.description)
+        .execute(&mut *conn)
+        .await?;
 
     Ok(())
-    
-}
\ No newline at end of file
+}
+
+pub(crate) async fn table_exists(db: &SqlitePool, name: &str) -> Result<bool, sqlx::Error> {
+    let table = sqlx::query("SELECT name FROM sqlite_master WHERE type = 'table' AND name = ?")
+        .bind(name)
+        .fetch_optional(db)
+        .await?;
+
+    Ok(table.is_some())
+}
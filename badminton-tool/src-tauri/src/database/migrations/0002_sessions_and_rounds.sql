@@ -0,0 +1,61 @@
+-- One row per club night
+CREATE TABLE sessions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    club_id INTEGER NOT NULL,
+    court_count INTEGER NOT NULL CHECK (court_count >= 1),
+    started_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    ended_at DATETIME,
+    FOREIGN KEY (club_id) REFERENCES clubs(id) ON DELETE CASCADE
+);
+
+-- Attendees of a session with their fairness counters for that session
+CREATE TABLE session_players (
+    session_id INTEGER NOT NULL,
+    player_id INTEGER NOT NULL,
+    sit_out_count INTEGER NOT NULL DEFAULT 0,
+    games_played INTEGER NOT NULL DEFAULT 0,
+    last_sat_out_round INTEGER,
+    checked_in_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    checked_out_at DATETIME,
+    PRIMARY KEY (session_id, player_id),
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE,
+    FOREIGN KEY (player_id) REFERENCES players(id) ON DELETE CASCADE
+);
+
+CREATE TABLE rounds (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    session_id INTEGER NOT NULL,
+    round_number INTEGER NOT NULL,
+    seed INTEGER,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (session_id, round_number),
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+-- One row per court in a round
+CREATE TABLE round_games (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    round_id INTEGER NOT NULL,
+    court INTEGER NOT NULL,
+    started_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    finished_at DATETIME,
+    FOREIGN KEY (round_id) REFERENCES rounds(id) ON DELETE CASCADE
+);
+
+CREATE TABLE round_game_players (
+    game_id INTEGER NOT NULL,
+    player_id INTEGER NOT NULL,
+    position INTEGER NOT NULL,
+    team TEXT CHECK (team IN ('A', 'B')),
+    PRIMARY KEY (game_id, player_id),
+    FOREIGN KEY (game_id) REFERENCES round_games(id) ON DELETE CASCADE,
+    FOREIGN KEY (player_id) REFERENCES players(id) ON DELETE CASCADE
+);
+
+CREATE TABLE round_sit_outs (
+    round_id INTEGER NOT NULL,
+    player_id INTEGER NOT NULL,
+    PRIMARY KEY (round_id, player_id),
+    FOREIGN KEY (round_id) REFERENCES rounds(id) ON DELETE CASCADE,
+    FOREIGN KEY (player_id) REFERENCES players(id) ON DELETE CASCADE
+);
@@ -0,0 +1,17 @@
+-- The winner of each scored game
+CREATE TABLE game_results (
+    game_id INTEGER PRIMARY KEY,
+    winner TEXT NOT NULL CHECK (winner IN ('A', 'B')),
+    recorded_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (game_id) REFERENCES round_games(id) ON DELETE CASCADE
+);
+
+-- The points of each set played
+CREATE TABLE game_result_sets (
+    game_id INTEGER NOT NULL,
+    set_number INTEGER NOT NULL,
+    team_a_score INTEGER NOT NULL,
+    team_b_score INTEGER NOT NULL,
+    PRIMARY KEY (game_id, set_number),
+    FOREIGN KEY (game_id) REFERENCES game_results(game_id) ON DELETE CASCADE
+);
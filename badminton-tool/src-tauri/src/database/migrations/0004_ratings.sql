@@ -0,0 +1,19 @@
+-- Live ratings, missing until a player's first rated game
+ALTER TABLE players ADD COLUMN rating REAL;
+ALTER TABLE players ADD COLUMN rating_deviation REAL;
+
+ALTER TABLE clubs ADD COLUMN auto_skill_level BOOLEAN NOT NULL DEFAULT 0;
+
+-- One row per player per rated game
+CREATE TABLE rating_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    player_id INTEGER NOT NULL,
+    game_id INTEGER NOT NULL,
+    rating_before REAL NOT NULL,
+    deviation_before REAL NOT NULL,
+    rating_after REAL NOT NULL,
+    deviation_after REAL NOT NULL,
+    recorded_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (player_id) REFERENCES players(id) ON DELETE CASCADE,
+    FOREIGN KEY (game_id) REFERENCES game_results(game_id) ON DELETE CASCADE
+);
@@ -0,0 +1,14 @@
+-- Every club a player belongs to. players.club_id stays as their home club.
+CREATE TABLE club_memberships (
+    player_id INTEGER NOT NULL,
+    club_id INTEGER NOT NULL,
+    role TEXT NOT NULL DEFAULT 'Member' CHECK (role IN ('Member', 'Coach', 'Committee')),
+    joined_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    status TEXT NOT NULL DEFAULT 'Active' CHECK (status IN ('Active', 'Inactive')),
+    PRIMARY KEY (player_id, club_id),
+    FOREIGN KEY (player_id) REFERENCES players(id) ON DELETE CASCADE,
+    FOREIGN KEY (club_id) REFERENCES clubs(id) ON DELETE CASCADE
+);
+
+INSERT INTO club_memberships (player_id, club_id, joined_at)
+SELECT id, club_id, created_at FROM players;
@@ -0,0 +1,2 @@
+-- bcrypt hash of the PIN players check themselves in with, missing until one is set
+ALTER TABLE players ADD COLUMN check_in_pin_hash TEXT;
@@ -0,0 +1,15 @@
+-- What each account may do in a club
+CREATE TABLE club_roles (
+    user_id INTEGER NOT NULL,
+    club_id INTEGER NOT NULL,
+    role TEXT NOT NULL CHECK (role IN ('Owner', 'Organiser', 'Viewer')),
+    PRIMARY KEY (user_id, club_id),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (club_id) REFERENCES clubs(id) ON DELETE CASCADE
+);
+
+-- Accounts from before logins existed have passwords nobody knows, so they
+-- can't be logged into. Turning them off lets whoever registers next take
+-- over as admin rather than being locked out by them.
+ALTER TABLE users ADD COLUMN disabled BOOLEAN NOT NULL DEFAULT 0;
+UPDATE users SET disabled = 1;
@@ -0,0 +1,17 @@
+-- Who changed which player or club, and what it looked like before and after.
+-- Nothing references the rows changed, so entries outlive what they describe.
+CREATE TABLE audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    -- Username of the account logged in at the time, NULL when nobody was
+    actor TEXT,
+    action TEXT NOT NULL CHECK (action IN ('Create', 'Update', 'Delete')),
+    entity TEXT NOT NULL CHECK (entity IN ('Player', 'Club')),
+    entity_id INTEGER NOT NULL,
+    -- The club itself, or the player's home club
+    club_id INTEGER,
+    before_json TEXT,
+    after_json TEXT
+);
+
+CREATE INDEX idx_audit_log_club ON audit_log (club_id, created_at);
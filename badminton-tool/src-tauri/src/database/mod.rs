@@ -1,5 +1,6 @@
 pub mod connection;
+mod legacy;
 pub mod migrations;
 
 pub use connection::*;
-pub use migrations::*;
+pub use migrations::*;
\ No newline at end of file
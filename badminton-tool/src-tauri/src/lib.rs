@@ -0,0 +1,5 @@
+pub mod commands;
+pub mod database;
+pub mod models;
+pub mod services;
+pub mod utils;
@@ -1,29 +1,47 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-mod commands;
-mod database;
-mod models;
-mod services;
-mod utils;
+use lib::commands;
+use lib::database::{copy_legacy_database, establish_connection, run_migrations};
+use lib::models::AppState;
+use lib::services::auth::Logins;
+use lib::utils::config::{default_database_path, get_auto_backup, get_database_path, legacy_database_path};
+use std::sync::Arc;
+use tauri::Manager;
 
-use database::{establish_connection, run_migrations};
-use models::AppState;
+fn main() {
+    tauri::Builder::default()
+        .plugin(tauri_plugin_log::Builder::new().level(log::LevelFilter::Info).build())
+        .setup(|app| {
+            let app_data_dir = app.path().app_data_dir()?;
+            let config_dir = app.path().app_config_dir()?;
+            let db_path = get_database_path(&app_data_dir, &config_dir)?;
+            let auto_backup = get_auto_backup(&app_data_dir, &config_dir)?;
 
-#[tokio::main]
-async fn main() {
-    // Initialize database connection
-    let db = establish_connection()
-        .await
-        .expect("Failed to establish database connection");
+            // Bring across the database from where older releases kept it
+            if db_path == default_database_path(&app_data_dir) && copy_legacy_database(&legacy_database_path(), &db_path)? {
+                log::info!("Copied existing database to: {:?}", db_path);
+            }
 
-    // Run migrations
-    run_migrations(&db).await.expect("Failed to run migrations");
+            let db = tauri::async_runtime::block_on(async {
+                // Initialize database connection
+                let db = establish_connection(&db_path).await?;
 
-    // Create app state
-    let app_state = AppState { db };
+                // Run migrations
+                run_migrations(&db).await?;
 
-    tauri::Builder::default()
-        .manage(app_state)
+                Ok::<_, sqlx::Error>(db)
+            })?;
+
+            // Create app state
+            app.manage(AppState {
+                db,
+                auto_backup,
+                board: Arc::default(),
+                logins: Logins::default(),
+            });
+
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             commands::clubs::get_clubs,
             commands::clubs::create_club,
@@ -34,6 +52,43 @@ async fn main() {
             commands::player::update_player,
             commands::player::delete_player,
             commands::games_maker::make_games,
+            commands::sessions::start_session,
+            commands::sessions::get_active_session,
+            commands::sessions::end_session,
+            commands::sessions::generate_next_round,
+            commands::sessions::next_game_for_court,
+            commands::sessions::get_session_history,
+            commands::sessions::get_session_players,
+            commands::sessions::check_in_player,
+            commands::sessions::check_out_player,
+            commands::results::record_result,
+            commands::results::correct_result,
+            commands::clubs::set_auto_skill_level,
+            commands::memberships::get_player_memberships,
+            commands::memberships::join_club,
+            commands::memberships::leave_club,
+            commands::stats::get_player_stats,
+            commands::stats::get_player_history,
+            commands::reports::get_club_report,
+            commands::reports::export_club_report_csv,
+            commands::backup::backup_database,
+            commands::backup::restore_database,
+            commands::import::import_players_csv,
+            commands::export::export_club_json,
+            commands::export::export_club_csv,
+            commands::export::import_club_json,
+            commands::board::start_board_server,
+            commands::board::stop_board_server,
+            commands::board::get_board_server_address,
+            commands::board::get_board,
+            commands::board::set_board_timer,
+            commands::check_in::set_player_pin,
+            commands::check_in::get_check_in_link,
+            commands::auth::register_user,
+            commands::auth::login,
+            commands::auth::logout,
+            commands::auth::set_club_role,
+            commands::audit::get_audit_log,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
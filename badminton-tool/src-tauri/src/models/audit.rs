@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Create => "Create",
+            AuditAction::Update => "Update",
+            AuditAction::Delete => "Delete",
+        }
+    }
+
+    pub fn from_db(value: &str) -> AuditAction {
+        match value {
+            "Create" => AuditAction::Create,
+            "Delete" => AuditAction::Delete,
+            _ => AuditAction::Update,
+        }
+    }
+}
+
+/// Kind of record an audit entry is about.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AuditEntity {
+    Player,
+    Club,
+}
+
+impl AuditEntity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditEntity::Player => "Player",
+            AuditEntity::Club => "Club",
+        }
+    }
+
+    pub fn from_db(value: &str) -> AuditEntity {
+        match value {
+            "Club" => AuditEntity::Club,
+            _ => AuditEntity::Player,
+        }
+    }
+}
+
+/// A change about to be written to the audit log.
+#[derive(Debug, Clone)]
+pub struct AuditChange {
+    pub action: AuditAction,
+    pub entity: AuditEntity,
+    pub entity_id: i64,
+    /// The club itself, or the player's home club.
+    pub club_id: i64,
+    /// The record before the change, missing when it was just created.
+    pub before: Option<Value>,
+    /// The record after the change, missing when it was deleted.
+    pub after: Option<Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub created_at: String,
+    /// Username of whoever made the change, missing when nobody was logged in.
+    pub actor: Option<String>,
+    pub action: AuditAction,
+    pub entity: AuditEntity,
+    pub entity_id: i64,
+    pub club_id: Option<i64>,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+/// Narrows the audit log down. Every filter left out matches everything.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct AuditLogFilters {
+    pub action: Option<AuditAction>,
+    pub entity: Option<AuditEntity>,
+    pub entity_id: Option<i64>,
+    pub actor: Option<String>,
+    /// Earliest time to include, as stored: "YYYY-MM-DD HH:MM:SS" in UTC.
+    pub since: Option<String>,
+    /// Latest time to include, in the same form as `since`.
+    pub until: Option<String>,
+    /// Most entries to return, newest first. Defaults to 100.
+    pub limit: Option<i64>,
+}
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+use super::GamesRound;
+
+/// What the court board shows: the games on court, who is sitting out and the
+/// round timer. Served read-only to phones on the hall network.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BoardSnapshot {
+    /// The session the round belongs to, missing until a round is generated.
+    pub session_id: Option<i64>,
+    pub round: Option<GamesRound>,
+    pub timer: BoardTimer,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BoardTimer {
+    /// Seconds left as of when the snapshot was taken.
+    pub remaining_seconds: u32,
+    pub running: bool,
+}
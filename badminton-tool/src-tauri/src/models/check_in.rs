@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+/// A member of the session's club who can check themselves in.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CheckInPlayer {
+    pub id: i64,
+    pub first_name: String,
+    pub last_name: String,
+    pub checked_in: bool,
+}
+
+/// Sent from a phone by a player checking themselves in.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SelfCheckInRequest {
+    pub player_id: i64,
+    pub pin: String,
+}
+
+/// The check-in page for a session, with a QR code of its address for players to scan.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CheckInLink {
+    pub url: String,
+    /// The QR code as an SVG document.
+    pub qr_svg: String,
+}
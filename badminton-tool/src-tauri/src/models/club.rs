@@ -4,10 +4,75 @@ use serde::{Deserialize, Serialize};
 pub struct Club {
     pub id: i64,
     pub name: String,
+    /// Active memberships of the club.
     pub member_count: i32,
+    /// Whether recorded results move members' skill levels to match their rating.
+    pub auto_skill_level: bool,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct CreateClubRequest {
     pub name: String,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MembershipRole {
+    #[default]
+    Member,
+    Coach,
+    Committee,
+}
+
+impl MembershipRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MembershipRole::Member => "Member",
+            MembershipRole::Coach => "Coach",
+            MembershipRole::Committee => "Committee",
+        }
+    }
+
+    /// Parses the value stored in `club_memberships.role`, falling back to Member.
+    pub fn from_db(value: &str) -> MembershipRole {
+        match value {
+            "Coach" => MembershipRole::Coach,
+            "Committee" => MembershipRole::Committee,
+            _ => MembershipRole::Member,
+        }
+    }
+}
+
+/// Members who leave are made inactive rather than removed, so their history
+/// with the club is kept and they can rejoin.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum MembershipStatus {
+    Active,
+    Inactive,
+}
+
+impl MembershipStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MembershipStatus::Active => "Active",
+            MembershipStatus::Inactive => "Inactive",
+        }
+    }
+
+    /// Parses the value stored in `club_memberships.status`, falling back to Active.
+    pub fn from_db(value: &str) -> MembershipStatus {
+        match value {
+            "Inactive" => MembershipStatus::Inactive,
+            _ => MembershipStatus::Active,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClubMembership {
+    pub player_id: i64,
+    pub club_id: i64,
+    pub club_name: String,
+    pub role: MembershipRole,
+    pub joined_at: String,
+    pub status: MembershipStatus,
+}
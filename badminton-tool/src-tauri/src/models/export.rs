@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Gender, MembershipRole, MembershipStatus, SetScore, Team};
+
+/// Version of the club export document. Bump it whenever the document changes
+/// shape, and keep importing the older versions.
+pub const CLUB_EXPORT_VERSION: u32 = 1;
+
+/// Everything a club has recorded, as a self-contained document that can be
+/// imported on another machine. Players are referred to by their `number`
+/// within the document rather than by database id.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ClubExport {
+    pub format_version: u32,
+    pub exported_at: String,
+    pub club: ExportedClub,
+    pub players: Vec<ExportedPlayer>,
+    pub sessions: Vec<ExportedSession>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ExportedClub {
+    pub name: String,
+    pub description: Option<String>,
+    pub auto_skill_level: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ExportedPlayer {
+    pub number: i64,
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+    pub gender: Gender,
+    pub skill_level: i32,
+    pub rating: Option<f64>,
+    pub rating_deviation: Option<f64>,
+    /// Missing for players who came to the club's sessions without being members.
+    pub membership: Option<ExportedMembership>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ExportedMembership {
+    pub role: MembershipRole,
+    pub status: MembershipStatus,
+    pub joined_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ExportedSession {
+    pub court_count: i32,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    pub attendees: Vec<ExportedAttendee>,
+    pub rounds: Vec<ExportedRound>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ExportedAttendee {
+    pub player: i64,
+    pub sit_out_count: i32,
+    pub games_played: i32,
+    pub last_sat_out_round: Option<i32>,
+    pub checked_in_at: Option<String>,
+    pub checked_out_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ExportedRound {
+    pub round_number: i32,
+    pub seed: Option<i64>,
+    pub created_at: String,
+    pub games: Vec<ExportedGame>,
+    pub sitting_out: Vec<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ExportedGame {
+    pub court: i32,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+    pub players: Vec<ExportedGamePlayer>,
+    pub result: Option<ExportedResult>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ExportedGamePlayer {
+    pub player: i64,
+    pub position: i32,
+    pub team: Team,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ExportedResult {
+    pub winner: Team,
+    pub recorded_at: String,
+    pub sets: Vec<SetScore>,
+    pub rating_changes: Vec<ExportedRatingChange>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ExportedRatingChange {
+    pub player: i64,
+    pub rating_before: f64,
+    pub deviation_before: f64,
+    pub rating_after: f64,
+    pub deviation_after: f64,
+}
+
+/// The club export as CSV files for spreadsheets.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClubCsvExport {
+    pub players: String,
+    pub sessions: String,
+    pub games: String,
+}
@@ -1,9 +1,16 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
+use super::{skill_level_for_rating, GameResult, Gender};
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct GamesRound {
     pub games: Vec<Game>,
     pub sitting_out: Vec<InGamePlayer>,
+    /// Explains where the requested options could not be fully met.
+    pub warnings: Vec<String>,
+    /// Seed that produced this round. Passing it back with the same players
+    /// and options reproduces the round exactly.
+    pub seed: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -12,11 +19,115 @@ pub struct InGamePlayer {
     pub first_name: String,
     pub last_name: String,
     pub skill_level: i32,
+    pub gender: Gender,
     pub sit_out_count: i32,
+    /// Live rating from recorded results, missing until the player has one.
+    #[serde(default)]
+    pub rating: Option<f64>,
+}
+
+impl InGamePlayer {
+    /// Level the games maker balances on: the live rating mapped onto the skill
+    /// scale once the player has one, otherwise the entered skill level.
+    pub fn balance_level(&self) -> i32 {
+        self.rating.map_or(self.skill_level, skill_level_for_rating)
+    }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Game {
+    pub id: Option<i64>,
     pub court: usize,
-    pub players: Vec<InGamePlayer>,
+    pub team_a: Vec<InGamePlayer>,
+    pub team_b: Vec<InGamePlayer>,
+    /// Difference between the summed balance levels of the two sides.
+    pub skill_gap: i32,
+    /// Set once a score has been recorded for the game.
+    #[serde(default)]
+    pub result: Option<GameResult>,
+}
+
+impl Game {
+    pub fn new(id: Option<i64>, court: usize, team_a: Vec<InGamePlayer>, team_b: Vec<InGamePlayer>) -> Self {
+        let team_skill = |team: &[InGamePlayer]| team.iter().map(|p| p.balance_level()).sum::<i32>();
+        let skill_gap = (team_skill(&team_a) - team_skill(&team_b)).abs();
+
+        Game {
+            id,
+            court,
+            team_a,
+            team_b,
+            skill_gap,
+            result: None,
+        }
+    }
+
+    pub fn players(&self) -> impl Iterator<Item = &InGamePlayer> {
+        self.team_a.iter().chain(self.team_b.iter())
+    }
+}
+
+/// How the games maker trades skill balance against mixing people up.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct GamesOptions {
+    /// Cost per skill point of imbalance between courts and between pairs.
+    pub skill_weight: f64,
+    /// Cost of pairing two players who have partnered before, scaled by the
+    /// square of how often they already have.
+    pub partner_repeat_weight: f64,
+    /// Cost of putting two players against each other again, scaled the same way.
+    pub opponent_repeat_weight: f64,
+    pub format: MatchFormat,
+    /// Format of each court, starting at court 1. Courts not listed play doubles.
+    pub court_formats: Vec<CourtFormat>,
+}
+
+impl GamesOptions {
+    /// Format of the court at `index`, counting from 0.
+    pub fn court_format(&self, index: usize) -> CourtFormat {
+        self.court_formats.get(index).copied().unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CourtFormat {
+    #[default]
+    Doubles,
+    Singles,
+}
+
+impl CourtFormat {
+    pub fn players(&self) -> usize {
+        match self {
+            CourtFormat::Doubles => 4,
+            CourtFormat::Singles => 2,
+        }
+    }
+}
+
+/// How courts are made up by gender.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatchFormat {
+    /// Gender is ignored.
+    #[default]
+    Any,
+    /// Mixed pairs on as many courts as possible, any mix on the rest.
+    PreferMixed,
+    /// Only mixed pairs play; courts that can't be filled that way stay empty.
+    StrictMixed,
+    /// Men's and ladies' doubles, each court a single gender where possible.
+    Level,
+}
+
+impl Default for GamesOptions {
+    fn default() -> Self {
+        GamesOptions {
+            skill_weight: 1.0,
+            partner_repeat_weight: 8.0,
+            opponent_repeat_weight: 2.0,
+            format: MatchFormat::Any,
+            court_formats: Vec::new(),
+        }
+    }
 }
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+use super::Gender;
+
+/// Headers of the CSV columns holding each field. A missing header is found by
+/// looking for the usual names, such as "Surname" for the last name.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ColumnMapping {
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub email: Option<String>,
+    pub gender: Option<String>,
+    pub skill_level: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ImportOptions {
+    /// Only report what would happen, without saving anything.
+    pub dry_run: bool,
+    pub columns: ColumnMapping,
+}
+
+/// A row of the CSV that passed validation.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ImportRow {
+    /// Line of the CSV the row is on, counting the header as line 1.
+    pub line: u64,
+    /// The existing player with this email, for rows that update one.
+    pub player_id: Option<i64>,
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+    pub gender: Gender,
+    pub skill_level: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RejectedRow {
+    pub line: u64,
+    /// The field at fault, if the problem is with a single field.
+    pub field: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub created: Vec<ImportRow>,
+    /// Members of the club already known by their email, who are updated.
+    pub updated: Vec<ImportRow>,
+    pub rejected: Vec<RejectedRow>,
+}
@@ -1,13 +1,43 @@
+use crate::services::auth::Logins;
+use crate::services::board::CourtBoard;
+use crate::utils::config::AutoBackup;
 use sqlx::SqlitePool;
+use std::sync::Arc;
 
+pub mod audit;
+pub mod board;
+pub mod check_in;
 pub mod club;
+pub mod export;
 pub mod games;
+pub mod import;
 pub mod player;
+pub mod report;
+pub mod result;
+pub mod session;
+pub mod stats;
+pub mod user;
 
+pub use audit::*;
+pub use board::*;
+pub use check_in::*;
 pub use club::*;
+pub use export::*;
 pub use games::*;
+pub use import::*;
 pub use player::*;
+pub use report::*;
+pub use result::*;
+pub use session::*;
+pub use stats::*;
+pub use user::*;
 
 pub struct AppState {
     pub db: SqlitePool,
+    /// Set when the database should be backed up at the end of every session.
+    pub auto_backup: Option<AutoBackup>,
+    /// Current round and timer, shared with the court board server.
+    pub board: Arc<CourtBoard>,
+    /// Tokens of the accounts logged in since the app started.
+    pub logins: Logins,
 }
@@ -1,11 +1,44 @@
+use crate::utils::errors::AppError;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 pub enum Gender {
     Male,
     Female,
 }
 
+impl Gender {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Gender::Male => "Male",
+            Gender::Female => "Female",
+        }
+    }
+
+    /// Parses the value stored in `players.gender`, falling back to Male.
+    pub fn from_db(value: &str) -> Gender {
+        match value {
+            "Female" => Gender::Female,
+            _ => Gender::Male,
+        }
+    }
+}
+
+/// Rating of skill level 1. Each skill level above it is worth `RATING_PER_SKILL_LEVEL`.
+pub const BASE_RATING: f64 = 1000.0;
+pub const RATING_PER_SKILL_LEVEL: f64 = 20.0;
+
+/// Rating a player starts from when they have only been given a skill level.
+pub fn rating_for_skill_level(skill_level: i32) -> f64 {
+    BASE_RATING + (skill_level - 1) as f64 * RATING_PER_SKILL_LEVEL
+}
+
+/// Maps a rating back onto the 1 to 50 skill level scale.
+pub fn skill_level_for_rating(rating: f64) -> i32 {
+    let level = ((rating - BASE_RATING) / RATING_PER_SKILL_LEVEL).round() as i32 + 1;
+    level.clamp(1, 50)
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Player {
     pub id: i64,
@@ -27,6 +60,8 @@ pub struct GetPlayer {
     pub skill_level: i32,
     pub gender: Gender,
     pub sit_out_count: i32,
+    /// Live rating from recorded results, missing until the player has one.
+    pub rating: Option<f64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -40,21 +75,21 @@ pub struct CreatePlayerRequest {
 }
 
 impl CreatePlayerRequest {
-    pub fn validate(&self) -> Result<(), String> {
+    pub fn validate(&self) -> Result<(), AppError> {
         if self.skill_level < 1 || self.skill_level > 50 {
-            return Err("Skill level must be between 1 and 50".to_string());
+            return Err(AppError::validation("skill_level", "Skill level must be between 1 and 50"));
         }
 
         if self.first_name.trim().is_empty() {
-            return Err("First name cannot be empty".to_string());
+            return Err(AppError::validation("first_name", "First name cannot be empty"));
         }
 
         if self.last_name.trim().is_empty() {
-            return Err("Last name cannot be empty".to_string());
+            return Err(AppError::validation("last_name", "Last name cannot be empty"));
         }
 
         if self.email.trim().is_empty() {
-            return Err("Email cannot be empty".to_string());
+            return Err(AppError::validation("email", "Email cannot be empty"));
         }
 
         Ok(())
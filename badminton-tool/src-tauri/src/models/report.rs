@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+/// Attendance and court use for one session of a club report.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SessionReport {
+    pub session_id: i64,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    pub court_count: i32,
+    pub attendees: i32,
+    pub games: i32,
+    pub player_minutes_played: f64,
+    pub player_minutes_available: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClubReport {
+    pub club_id: i64,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub sessions_held: i32,
+    pub unique_attendees: i32,
+    pub average_attendance: f64,
+    pub player_minutes_played: f64,
+    /// Every court filled with four players for the whole of each session.
+    pub player_minutes_available: f64,
+    /// Share of the available player-minutes spent on court, missing if there were none.
+    pub court_utilisation: Option<f64>,
+    /// Attendees whose first session at the club falls within the report.
+    pub new_members: i32,
+    pub returning_members: i32,
+    pub sessions: Vec<SessionReport>,
+}
@@ -0,0 +1,105 @@
+use crate::utils::errors::AppError;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Team {
+    A,
+    B,
+}
+
+impl Team {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Team::A => "A",
+            Team::B => "B",
+        }
+    }
+
+    /// Parses the value stored in a `team` or `winner` column, falling back to B.
+    pub fn from_db(value: &str) -> Team {
+        match value {
+            "A" => Team::A,
+            _ => Team::B,
+        }
+    }
+}
+
+/// Points scored by each side in one set.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct SetScore {
+    pub team_a: i32,
+    pub team_b: i32,
+}
+
+impl SetScore {
+    /// Checks the score against rally scoring to 21 and returns who won the set.
+    /// From 20-all a side must lead by 2, and at 29-all the next rally wins.
+    pub fn winner(&self) -> Result<Team, AppError> {
+        if self.team_a < 0 || self.team_b < 0 {
+            return Err(AppError::validation("sets", "Scores cannot be negative"));
+        }
+
+        let high = self.team_a.max(self.team_b);
+        let low = self.team_a.min(self.team_b);
+
+        let problem = if high == low {
+            Some("a set cannot finish level")
+        } else if high < 21 {
+            Some("the winning side needs 21 points")
+        } else if high > 30 {
+            Some("a set is capped at 30 points")
+        } else if high == 21 && low > 19 {
+            Some("from 20-all the winning side must lead by 2")
+        } else if high > 21 && low != high - 2 && !(high == 30 && low == 29) {
+            Some("past 21 a set is only won by a 2 point lead")
+        } else {
+            None
+        };
+
+        match problem {
+            Some(problem) => Err(AppError::validation(
+                "sets",
+                format!("{}-{} is not a valid set score: {}", self.team_a, self.team_b, problem),
+            )),
+            None if self.team_a > self.team_b => Ok(Team::A),
+            None => Ok(Team::B),
+        }
+    }
+}
+
+/// Works out who won a game played as a single set or as best of three.
+pub fn match_winner(sets: &[SetScore]) -> Result<Team, AppError> {
+    if sets.is_empty() {
+        return Err(AppError::validation("sets", "At least one set score is required"));
+    }
+
+    if sets.len() > 3 {
+        return Err(AppError::validation("sets", "A match is at most three sets"));
+    }
+
+    let mut sets_won = [0, 0];
+    for (index, set) in sets.iter().enumerate() {
+        if sets_won.contains(&2) {
+            return Err(AppError::validation("sets", format!("The match was already decided after set {}", index)));
+        }
+
+        match set.winner().map_err(|e| AppError::validation("sets", format!("Set {}: {}", index + 1, e)))? {
+            Team::A => sets_won[0] += 1,
+            Team::B => sets_won[1] += 1,
+        }
+    }
+
+    if sets.len() > 1 && !sets_won.contains(&2) {
+        return Err(AppError::validation("sets", "A best of three match needs one side to win two sets"));
+    }
+
+    Ok(if sets_won[0] > sets_won[1] { Team::A } else { Team::B })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GameResult {
+    pub game_id: i64,
+    pub sets: Vec<SetScore>,
+    pub winner: Team,
+    pub recorded_at: String,
+}
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Game, GamesRound, Gender, InGamePlayer};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Session {
+    pub id: i64,
+    pub club_id: i64,
+    pub court_count: i32,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SessionRound {
+    pub id: i64,
+    pub round_number: i32,
+    /// Missing on rounds generated before seeds were recorded.
+    pub seed: Option<u64>,
+    pub games: Vec<Game>,
+    pub sitting_out: Vec<InGamePlayer>,
+    /// Only set on a freshly generated round, these are not stored.
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+impl From<&SessionRound> for GamesRound {
+    fn from(round: &SessionRound) -> Self {
+        GamesRound {
+            games: round.games.clone(),
+            sitting_out: round.sitting_out.clone(),
+            warnings: round.warnings.clone(),
+            seed: round.seed.unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SessionHistory {
+    pub session: Session,
+    pub rounds: Vec<SessionRound>,
+}
+
+/// An attendee of a session with their fairness counters for that session.
+/// Late arrivals start with a credited share of sit-outs, so the counters
+/// reflect fairness rather than a literal tally.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SessionPlayer {
+    pub player_id: i64,
+    pub first_name: String,
+    pub last_name: String,
+    pub skill_level: i32,
+    pub gender: Gender,
+    pub sit_out_count: i32,
+    pub games_played: i32,
+    pub last_sat_out_round: Option<i32>,
+    pub checked_in_at: Option<String>,
+    pub checked_out_at: Option<String>,
+}
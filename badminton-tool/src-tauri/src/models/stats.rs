@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+use super::{GameResult, Team};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlayerSummary {
+    pub player_id: i64,
+    pub first_name: String,
+    pub last_name: String,
+}
+
+/// One game from a player's point of view.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlayerGame {
+    pub game_id: i64,
+    pub session_id: i64,
+    pub round_number: i32,
+    pub court: i32,
+    pub played_at: Option<String>,
+    /// The side the player was on.
+    pub team: Team,
+    pub partners: Vec<PlayerSummary>,
+    pub opponents: Vec<PlayerSummary>,
+    pub result: Option<GameResult>,
+    /// Missing until a result is recorded.
+    pub won: Option<bool>,
+    pub rating_before: Option<f64>,
+    pub rating_after: Option<f64>,
+}
+
+/// How often a player shared a court with someone, and how many of those games they won.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlayerTally {
+    pub player_id: i64,
+    pub first_name: String,
+    pub last_name: String,
+    pub games: i32,
+    pub wins: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RatingPoint {
+    pub game_id: i64,
+    pub recorded_at: String,
+    pub rating: f64,
+    pub deviation: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlayerStats {
+    pub player_id: i64,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub games_played: i32,
+    pub sit_outs: i32,
+    pub wins: i32,
+    pub losses: i32,
+    /// Share of games with a result that were won, missing if none have one.
+    pub win_rate: Option<f64>,
+    pub points_for: i32,
+    pub points_against: i32,
+    pub points_difference: i32,
+    pub partners: Vec<PlayerTally>,
+    pub opponents: Vec<PlayerTally>,
+    pub rating_trend: Vec<RatingPoint>,
+}
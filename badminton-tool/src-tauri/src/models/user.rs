@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+
+/// Kind of account. Admins can do anything in every club, the way the only
+/// organiser could before there were accounts. Stored lowercase, as the users
+/// table of older releases already holds them.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AccountRole {
+    Admin,
+    User,
+}
+
+impl AccountRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccountRole::Admin => "admin",
+            AccountRole::User => "user",
+        }
+    }
+
+    pub fn from_db(value: &str) -> AccountRole {
+        match value {
+            "admin" => AccountRole::Admin,
+            _ => AccountRole::User,
+        }
+    }
+}
+
+/// What an account may do in a club, each role allowing everything the ones
+/// before it do.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ClubRole {
+    /// Sees the club without changing it.
+    Viewer,
+    /// Runs sessions and manages members, including deleting them.
+    Organiser,
+    /// Also decides who else has a role in the club.
+    Owner,
+}
+
+impl ClubRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClubRole::Viewer => "Viewer",
+            ClubRole::Organiser => "Organiser",
+            ClubRole::Owner => "Owner",
+        }
+    }
+
+    pub fn from_db(value: &str) -> ClubRole {
+        match value {
+            "Owner" => ClubRole::Owner,
+            "Organiser" => ClubRole::Organiser,
+            _ => ClubRole::Viewer,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct User {
+    pub id: i64,
+    pub username: String,
+    pub role: AccountRole,
+}
+
+/// Returned by a successful login. The token goes with every command that needs
+/// the account, until logging out.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LoginResponse {
+    pub token: String,
+    pub user: User,
+}
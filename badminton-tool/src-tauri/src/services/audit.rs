@@ -0,0 +1,114 @@
+use crate::models::{AuditAction, AuditChange, AuditEntity, AuditEntry, AuditLogFilters, User};
+use crate::utils::errors::AppError;
+use serde_json::{json, Value};
+use sqlx::{Row, SqliteConnection, SqlitePool};
+
+const DEFAULT_LIMIT: i64 = 100;
+const MAX_LIMIT: i64 = 1000;
+
+/// Writes a change to the audit log. Pass the transaction making the change, so
+/// the entry is saved if and only if the change is.
+pub async fn record(conn: &mut SqliteConnection, actor: Option<&User>, change: AuditChange) -> Result<(), AppError> {
+    sqlx::query(
+        "INSERT INTO audit_log (actor, action, entity, entity_id, club_id, before_json, after_json)
+         VALUES (?, ?, ?, ?, ?, ?, ?)"
+    )
+    .bind(actor.map(|user| user.username.as_str()))
+    .bind(change.action.as_str())
+    .bind(change.entity.as_str())
+    .bind(change.entity_id)
+    .bind(change.club_id)
+    .bind(change.before.map(|value| value.to_string()))
+    .bind(change.after.map(|value| value.to_string()))
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// The fields of a player an organiser can change, as they are now.
+pub async fn player_snapshot(conn: &mut SqliteConnection, player_id: i64) -> Result<Option<Value>, AppError> {
+    let row = sqlx::query("SELECT first_name, last_name, email, gender, club_id, skill_level FROM players WHERE id = ?")
+        .bind(player_id)
+        .fetch_optional(conn)
+        .await?;
+
+    Ok(row.map(|row| {
+        json!({
+            "first_name": row.get::<String, _>("first_name"),
+            "last_name": row.get::<String, _>("last_name"),
+            "email": row.get::<String, _>("email"),
+            "gender": row.get::<String, _>("gender"),
+            "club_id": row.get::<i64, _>("club_id"),
+            "skill_level": row.get::<i32, _>("skill_level"),
+        })
+    }))
+}
+
+/// The fields of a club an organiser can change, as they are now.
+pub async fn club_snapshot(conn: &mut SqliteConnection, club_id: i64) -> Result<Option<Value>, AppError> {
+    let row = sqlx::query("SELECT name, description, auto_skill_level FROM clubs WHERE id = ?")
+        .bind(club_id)
+        .fetch_optional(conn)
+        .await?;
+
+    Ok(row.map(|row| {
+        json!({
+            "name": row.get::<String, _>("name"),
+            "description": row.get::<Option<String>, _>("description"),
+            "auto_skill_level": row.get::<bool, _>("auto_skill_level"),
+        })
+    }))
+}
+
+/// The club's audit log, newest first, including players who moved to another
+/// club. Entries stay after the club or player they describe is deleted.
+pub async fn audit_log(db: &SqlitePool, club_id: i64, filters: &AuditLogFilters) -> Result<Vec<AuditEntry>, AppError> {
+    let limit = filters.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let rows = sqlx::query(
+        "SELECT id, created_at, actor, action, entity, entity_id, club_id, before_json, after_json
+         FROM audit_log
+         WHERE (club_id = ?1 OR json_extract(before_json, '$.club_id') = ?1)
+           AND (?2 IS NULL OR action = ?2)
+           AND (?3 IS NULL OR entity = ?3)
+           AND (?4 IS NULL OR entity_id = ?4)
+           AND (?5 IS NULL OR actor = ?5)
+           AND (?6 IS NULL OR created_at >= ?6)
+           AND (?7 IS NULL OR created_at <= ?7)
+         ORDER BY created_at DESC, id DESC
+         LIMIT ?8"
+    )
+    .bind(club_id)
+    .bind(filters.action.map(|action| action.as_str()))
+    .bind(filters.entity.map(|entity| entity.as_str()))
+    .bind(filters.entity_id)
+    .bind(filters.actor.as_deref())
+    .bind(filters.since.as_deref())
+    .bind(filters.until.as_deref())
+    .bind(limit)
+    .fetch_all(db)
+    .await?;
+
+    rows.iter()
+        .map(|row| {
+            Ok(AuditEntry {
+                id: row.get("id"),
+                created_at: row.get("created_at"),
+                actor: row.get("actor"),
+                action: AuditAction::from_db(row.get("action")),
+                entity: AuditEntity::from_db(row.get("entity")),
+                entity_id: row.get("entity_id"),
+                club_id: row.get("club_id"),
+                before: parse_json(row.get("before_json"))?,
+                after: parse_json(row.get("after_json"))?,
+            })
+        })
+        .collect()
+}
+
+fn parse_json(text: Option<String>) -> Result<Option<Value>, AppError> {
+    text.map(|text| serde_json::from_str(&text))
+        .transpose()
+        .map_err(|e| AppError::Database(format!("Unreadable audit log entry: {}", e)))
+}
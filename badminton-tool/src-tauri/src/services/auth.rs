@@ -0,0 +1,204 @@
+use crate::models::{AccountRole, ClubRole, LoginResponse, User};
+use crate::utils::errors::AppError;
+use sqlx::{Row, SqlitePool};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Login tokens and the accounts they belong to. Kept in memory only, so
+/// everyone logs in again after the app restarts.
+#[derive(Default)]
+pub struct Logins {
+    tokens: Mutex<HashMap<String, i64>>,
+}
+
+impl Logins {
+    fn start(&self, user_id: i64) -> Result<String, AppError> {
+        let mut bytes = [0u8; 32];
+        getrandom::fill(&mut bytes).map_err(|e| AppError::Database(format!("Failed to make a login token: {}", e)))?;
+        let token: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+
+        self.tokens.lock().unwrap().insert(token.clone(), user_id);
+        Ok(token)
+    }
+
+    /// Ends the login with this token, if there is one.
+    pub fn end(&self, token: &str) {
+        self.tokens.lock().unwrap().remove(token);
+    }
+
+    pub fn user_id(&self, token: &str) -> Option<i64> {
+        self.tokens.lock().unwrap().get(token).copied()
+    }
+
+    /// Ends every login, for when the accounts they belong to may have changed.
+    pub fn clear(&self) {
+        self.tokens.lock().unwrap().clear();
+    }
+}
+
+/// Makes an account. Until there is an organiser to log in as, the new account
+/// is an admin, as whoever sets up accounts has been running every club so far;
+/// later ones get roles club by club.
+pub async fn register(db: &SqlitePool, username: &str, password: &str) -> Result<User, AppError> {
+    let username = username.trim();
+
+    if !(3..=50).contains(&username.chars().count()) {
+        return Err(AppError::validation("username", "Username must be 3 to 50 characters"));
+    }
+
+    if password.chars().count() < 8 {
+        return Err(AppError::validation("password", "Password must be at least 8 characters"));
+    }
+
+    let password = password.to_string();
+    let password_hash = tokio::task::spawn_blocking(move || bcrypt::hash(password, bcrypt::DEFAULT_COST))
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to hash password: {}", e)))?
+        .map_err(|e| AppError::Database(format!("Failed to hash password: {}", e)))?;
+
+    let role = if has_organisers(db).await? { AccountRole::User } else { AccountRole::Admin };
+
+    let row = sqlx::query("INSERT INTO users (username, password_hash, role) VALUES (?, ?, ?) RETURNING id, username, role")
+        .bind(username)
+        .bind(password_hash)
+        .bind(role.as_str())
+        .fetch_one(db)
+        .await?;
+
+    Ok(User {
+        id: row.get("id"),
+        username: row.get("username"),
+        role: AccountRole::from_db(row.get("role")),
+    })
+}
+
+/// Checks the password and starts a login for the account.
+pub async fn log_in(db: &SqlitePool, logins: &Logins, username: &str, password: &str) -> Result<LoginResponse, AppError> {
+    // The same error either way, so usernames can't be found by trying them
+    let wrong = || AppError::Unauthorized("Wrong username or password".to_string());
+
+    let row = sqlx::query("SELECT id, username, password_hash, role FROM users WHERE username = ? AND NOT disabled")
+        .bind(username.trim())
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(wrong)?;
+
+    let password = password.to_string();
+    let password_hash: String = row.get("password_hash");
+    let correct = tokio::task::spawn_blocking(move || bcrypt::verify(password, &password_hash))
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to check password: {}", e)))?
+        .map_err(|e| AppError::Database(format!("Failed to check password: {}", e)))?;
+
+    if !correct {
+        return Err(wrong());
+    }
+
+    let user = User {
+        id: row.get("id"),
+        username: row.get("username"),
+        role: AccountRole::from_db(row.get("role")),
+    };
+
+    Ok(LoginResponse { token: logins.start(user.id)?, user })
+}
+
+/// The account logged in with `token`.
+pub async fn current_user(db: &SqlitePool, logins: &Logins, token: Option<&str>) -> Result<User, AppError> {
+    let token = token.ok_or_else(|| AppError::Unauthorized("Log in to do that".to_string()))?;
+    let user_id = logins
+        .user_id(token)
+        .ok_or_else(|| AppError::Unauthorized("Your login has ended, log in again".to_string()))?;
+
+    let row = sqlx::query("SELECT id, username, role FROM users WHERE id = ? AND NOT disabled")
+        .bind(user_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| AppError::Unauthorized("Your account no longer exists".to_string()))?;
+
+    Ok(User {
+        id: row.get("id"),
+        username: row.get("username"),
+        role: AccountRole::from_db(row.get("role")),
+    })
+}
+
+/// The account logged in with `token`, or nobody when no token is given.
+pub async fn logged_in_user(db: &SqlitePool, logins: &Logins, token: Option<&str>) -> Result<Option<User>, AppError> {
+    match token {
+        Some(token) => Ok(Some(current_user(db, logins, Some(token)).await?)),
+        None => Ok(None),
+    }
+}
+
+/// Checks that the account logged in with `token` is an admin. As with club
+/// roles, everything is allowed until there is an organiser to log in as.
+pub async fn require_admin(db: &SqlitePool, logins: &Logins, token: Option<&str>) -> Result<(), AppError> {
+    if !has_organisers(db).await? {
+        return Ok(());
+    }
+
+    let user = current_user(db, logins, token).await?;
+    if user.role != AccountRole::Admin {
+        return Err(AppError::Forbidden("Only an admin can do that".to_string()));
+    }
+
+    Ok(())
+}
+
+/// Checks that the account logged in with `token` has at least `minimum` in the
+/// club. Until there is an organiser to log in as the app is run without
+/// logging in, and everything is allowed.
+pub async fn require_club_role(
+    db: &SqlitePool,
+    logins: &Logins,
+    token: Option<&str>,
+    club_id: i64,
+    minimum: ClubRole,
+) -> Result<(), AppError> {
+    if !has_organisers(db).await? {
+        return Ok(());
+    }
+
+    let user = current_user(db, logins, token).await?;
+    if user.role == AccountRole::Admin {
+        return Ok(());
+    }
+
+    let role = sqlx::query("SELECT role FROM club_roles WHERE user_id = ? AND club_id = ?")
+        .bind(user.id)
+        .bind(club_id)
+        .fetch_optional(db)
+        .await?
+        .map(|row| ClubRole::from_db(row.get("role")));
+
+    if role.is_some_and(|role| role >= minimum) {
+        return Ok(());
+    }
+
+    let message = match minimum {
+        ClubRole::Owner => "Only the club's owners can do that",
+        ClubRole::Organiser => "You need to be an organiser of this club to do that",
+        ClubRole::Viewer => "You need a role in this club to do that",
+    };
+    Err(AppError::Forbidden(message.to_string()))
+}
+
+/// Whether there is an admin or club organiser someone can log in as. Until
+/// then the app runs open, with no logins.
+pub async fn has_organisers(db: &SqlitePool) -> Result<bool, AppError> {
+    let organisers = sqlx::query(
+        "SELECT EXISTS (
+             SELECT 1 FROM users u
+             WHERE NOT u.disabled
+               AND (u.role = 'admin' OR EXISTS (
+                   SELECT 1 FROM club_roles r WHERE r.user_id = u.id AND r.role IN ('Owner', 'Organiser')
+               ))
+         ) as organisers"
+    )
+    .fetch_one(db)
+    .await?
+    .get("organisers");
+
+    Ok(organisers)
+}
@@ -0,0 +1,239 @@
+use crate::database::migrations::{latest_version, run_migrations, schema_version, table_exists};
+use crate::utils::config::AutoBackup;
+use crate::utils::errors::AppError;
+use sqlx::pool::PoolConnection;
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{Row, Sqlite, SqliteConnection, SqlitePool};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Automatic backups are named `backup-<timestamp>-session-<id>.db`.
+const AUTO_BACKUP_PREFIX: &str = "backup-";
+const AUTO_BACKUP_EXTENSION: &str = "db";
+
+/// Numbers the staging copies, so restores running at once don't share one.
+static NEXT_STAGING: AtomicU64 = AtomicU64::new(1);
+
+/// Writes a consistent copy of the whole database to `path`, which mustn't exist yet.
+/// The database stays usable while the copy is made.
+pub async fn backup_to(db: &SqlitePool, path: &Path) -> Result<(), AppError> {
+    if path.exists() {
+        return Err(AppError::Conflict {
+            field: Some("path".to_string()),
+            message: format!("{} already exists", path.display()),
+        });
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    sqlx::query("VACUUM INTO ?")
+        .bind(path.to_string_lossy().into_owned())
+        .execute(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Replaces everything in the database with the contents of the backup at `path`.
+/// Backups from older releases are brought up to date first; backups from newer
+/// releases are refused. Nothing changes unless the whole restore succeeds.
+pub async fn restore_from(db: &SqlitePool, path: &Path) -> Result<(), AppError> {
+    if !path.is_file() {
+        return Err(AppError::not_found(format!("Backup {} not found", path.display())));
+    }
+
+    // Work on a copy so the backup itself is never changed by the migrations
+    let staging_path = std::env::temp_dir().join(format!(
+        "badminton-restore-{}-{}.db",
+        std::process::id(),
+        NEXT_STAGING.fetch_add(1, Ordering::Relaxed)
+    ));
+    let _ = std::fs::remove_file(&staging_path);
+    std::fs::copy(path, &staging_path)?;
+
+    let result = restore_from_staging(db, &staging_path).await;
+    let _ = std::fs::remove_file(&staging_path);
+
+    result
+}
+
+async fn restore_from_staging(db: &SqlitePool, staging_path: &Path) -> Result<(), AppError> {
+    let staging = SqlitePool::connect_with(SqliteConnectOptions::new().filename(staging_path))
+        .await
+        .map_err(|_| not_a_backup())?;
+
+    let prepared = async {
+        check_backup(&staging).await?;
+        run_migrations(&staging).await?;
+        Ok::<_, AppError>(())
+    }
+    .await;
+    staging.close().await;
+    prepared?;
+
+    // Hold every connection of the pool, so nothing else reads or writes until
+    // the restore is over. The first one does the restoring
+    let mut connections = drain_pool(db).await?;
+    let conn = &mut *connections[0];
+
+    // ATTACH isn't allowed inside a transaction, so the backup is attached first
+    sqlx::query("ATTACH DATABASE ? AS backup")
+        .bind(staging_path.to_string_lossy().into_owned())
+        .execute(&mut *conn)
+        .await?;
+
+    // Foreign keys can only be switched off outside a transaction. With them off,
+    // clearing a table doesn't cascade into the tables that refer to it, whatever
+    // order the tables are copied in
+    sqlx::query("PRAGMA foreign_keys = OFF").execute(&mut *conn).await?;
+
+    let copied = copy_tables_from_backup(conn).await;
+
+    sqlx::query("PRAGMA foreign_keys = ON").execute(&mut *conn).await?;
+    sqlx::query("DETACH DATABASE backup").execute(&mut *conn).await?;
+
+    copied
+}
+
+/// Waits for every connection the pool can hand out, which also waits out any
+/// query or transaction already running on one of them.
+async fn drain_pool(db: &SqlitePool) -> Result<Vec<PoolConnection<Sqlite>>, AppError> {
+    let mut connections = Vec::new();
+    for _ in 0..db.options().get_max_connections() {
+        connections.push(db.acquire().await?);
+    }
+
+    Ok(connections)
+}
+
+/// Refuses anything that isn't a database of this app at a schema version it knows.
+async fn check_backup(staging: &SqlitePool) -> Result<(), AppError> {
+    let has_schema = table_exists(staging, "schema_migrations").await.map_err(|_| not_a_backup())?;
+    let has_clubs = table_exists(staging, "clubs").await.map_err(|_| not_a_backup())?;
+
+    if !has_schema && !has_clubs {
+        return Err(not_a_backup());
+    }
+
+    let version = schema_version(staging).await?;
+    if version > latest_version() {
+        return Err(AppError::validation(
+            "path",
+            format!(
+                "The backup is from a newer version of the app (schema version {}, this app supports up to {})",
+                version,
+                latest_version()
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+fn not_a_backup() -> AppError {
+    AppError::validation("path", "The file is not a backup of this app's database")
+}
+
+async fn copy_tables_from_backup(conn: &mut SqliteConnection) -> Result<(), AppError> {
+    let tables: Vec<String> = sqlx::query(
+        "SELECT m.name FROM main.sqlite_master m
+         WHERE m.type = 'table' AND m.name NOT LIKE 'sqlite_%' AND m.name != 'schema_migrations'
+         AND EXISTS (SELECT 1 FROM backup.sqlite_master b WHERE b.type = 'table' AND b.name = m.name)"
+    )
+    .fetch_all(&mut *conn)
+    .await?
+    .iter()
+    .map(|row| row.get("name"))
+    .collect();
+
+    // Start a transaction
+    let mut tx = sqlx::Connection::begin(&mut *conn).await?;
+
+    for table in &tables {
+        let columns: Vec<String> = sqlx::query(
+            "SELECT m.name FROM pragma_table_info(?, 'main') m
+             JOIN pragma_table_info(?, 'backup') b ON b.name = m.name
+             ORDER BY m.cid"
+        )
+        .bind(table)
+        .bind(table)
+        .fetch_all(&mut *tx)
+        .await?
+        .iter()
+        .map(|row| format!("\"{}\"", row.get::<String, _>("name")))
+        .collect();
+
+        sqlx::query(&format!("DELETE FROM main.\"{}\"", table))
+            .execute(&mut *tx)
+            .await?;
+
+        let columns = columns.join(", ");
+        sqlx::query(&format!(
+            "INSERT INTO main.\"{}\" ({}) SELECT {} FROM backup.\"{}\"",
+            table, columns, columns, table
+        ))
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    // References aren't enforced during the copy, so check them all before committing
+    let broken = sqlx::query("PRAGMA main.foreign_key_check").fetch_optional(&mut *tx).await?;
+    if broken.is_some() {
+        return Err(AppError::validation(
+            "path",
+            "The backup has rows that refer to missing records and can't be restored",
+        ));
+    }
+
+    // Commit the transaction
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Backs the database up into the automatic backup folder after a session ends,
+/// then deletes the oldest automatic backups beyond the number to keep.
+pub async fn auto_backup(db: &SqlitePool, settings: &AutoBackup, session_id: i64) -> Result<PathBuf, AppError> {
+    let timestamp: String = sqlx::query("SELECT strftime('%Y%m%d-%H%M%S', 'now') as timestamp")
+        .fetch_one(db)
+        .await?
+        .get("timestamp");
+
+    let path = settings.directory.join(format!(
+        "{}{}-session-{}.{}",
+        AUTO_BACKUP_PREFIX, timestamp, session_id, AUTO_BACKUP_EXTENSION
+    ));
+    backup_to(db, &path).await?;
+
+    let mut backups = list_auto_backups(&settings.directory)?;
+
+    // Names start with the timestamp, so they sort oldest first
+    backups.sort();
+    let excess = backups.len().saturating_sub(settings.keep);
+    for old in &backups[..excess] {
+        std::fs::remove_file(old)?;
+    }
+
+    Ok(path)
+}
+
+pub fn list_auto_backups(directory: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut backups = Vec::new();
+
+    for entry in std::fs::read_dir(directory)? {
+        let path = entry?.path();
+        let is_backup = path.extension().is_some_and(|ext| ext == AUTO_BACKUP_EXTENSION)
+            && path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(AUTO_BACKUP_PREFIX));
+
+        if is_backup {
+            backups.push(path);
+        }
+    }
+
+    Ok(backups)
+}
@@ -0,0 +1,365 @@
+use crate::models::{BoardSnapshot, BoardTimer, CheckInPlayer, GamesRound, InGamePlayer, SelfCheckInRequest, SessionPlayer};
+use crate::services::check_in::{check_in_list, self_check_in, PinAttempts};
+use crate::utils::errors::AppError;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use sqlx::SqlitePool;
+use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// Page phones load from the server root. It shows the board and follows the
+/// WebSocket updates.
+const BOARD_PAGE: &str = include_str!("board.html");
+
+/// Page the check-in QR code leads to, where players pick their name and enter their PIN.
+const CHECK_IN_PAGE: &str = include_str!("check_in.html");
+
+/// The round and timer shown on the court board. Every change is pushed to the
+/// phones connected to the board server, when it is running.
+pub struct CourtBoard {
+    state: watch::Sender<BoardState>,
+    server: Mutex<Option<RunningServer>>,
+    /// Kept here rather than with the server, so restarting the server doesn't
+    /// lift a lockout.
+    pin_attempts: PinAttempts,
+}
+
+#[derive(Clone, Default)]
+struct BoardState {
+    session_id: Option<i64>,
+    round: Option<GamesRound>,
+    timer: TimerState,
+}
+
+/// The timer as last set. A running timer counts down from `remaining_seconds`
+/// starting at `set_at`.
+#[derive(Clone, Copy)]
+struct TimerState {
+    remaining_seconds: u32,
+    running: bool,
+    set_at: Instant,
+}
+
+impl Default for TimerState {
+    fn default() -> Self {
+        TimerState { remaining_seconds: 0, running: false, set_at: Instant::now() }
+    }
+}
+
+impl TimerState {
+    fn now(&self) -> BoardTimer {
+        if !self.running {
+            return BoardTimer { remaining_seconds: self.remaining_seconds, running: false };
+        }
+
+        let elapsed = u32::try_from(self.set_at.elapsed().as_secs()).unwrap_or(u32::MAX);
+        let remaining_seconds = self.remaining_seconds.saturating_sub(elapsed);
+        BoardTimer { remaining_seconds, running: remaining_seconds > 0 }
+    }
+}
+
+impl BoardState {
+    fn snapshot(&self) -> BoardSnapshot {
+        BoardSnapshot {
+            session_id: self.session_id,
+            round: self.round.clone(),
+            timer: self.timer.now(),
+        }
+    }
+}
+
+struct RunningServer {
+    address: SocketAddr,
+    shutdown: watch::Sender<bool>,
+    task: JoinHandle<()>,
+}
+
+impl Default for CourtBoard {
+    fn default() -> Self {
+        CourtBoard {
+            state: watch::Sender::new(BoardState::default()),
+            server: Mutex::new(None),
+            pin_attempts: PinAttempts::default(),
+        }
+    }
+}
+
+impl CourtBoard {
+    pub fn snapshot(&self) -> BoardSnapshot {
+        self.state.borrow().snapshot()
+    }
+
+    /// Shows a whole new round of the session.
+    pub fn show_round(&self, session_id: i64, round: GamesRound) {
+        self.state.send_modify(|state| {
+            state.session_id = Some(session_id);
+            state.round = Some(round);
+        });
+    }
+
+    /// Shows a round drawn in the lobby, outside any session.
+    pub fn show_games(&self, round: GamesRound) {
+        self.state.send_modify(|state| {
+            state.session_id = None;
+            state.round = Some(round);
+        });
+    }
+
+    /// Shows the games of a round that refilled some of the courts, keeping the
+    /// games still being played on the others.
+    pub fn show_courts(&self, session_id: i64, round: GamesRound) {
+        self.state.send_modify(|state| {
+            let current = match state.round.take() {
+                Some(current) if state.session_id == Some(session_id) => current,
+                _ => {
+                    state.session_id = Some(session_id);
+                    state.round = Some(round);
+                    return;
+                }
+            };
+
+            let refilled: Vec<usize> = round.games.iter().map(|game| game.court).collect();
+            let mut games: Vec<_> = current
+                .games
+                .into_iter()
+                .filter(|game| !refilled.contains(&game.court))
+                .chain(round.games)
+                .collect();
+            games.sort_by_key(|game| game.court);
+
+            state.round = Some(GamesRound { games, ..round });
+        });
+    }
+
+    /// Empties the board when the session it shows has ended.
+    pub fn clear_session(&self, session_id: i64) {
+        self.state.send_if_modified(|state| {
+            if state.session_id != Some(session_id) {
+                return false;
+            }
+
+            state.session_id = None;
+            state.round = None;
+            true
+        });
+    }
+
+    pub fn set_timer(&self, remaining_seconds: u32, running: bool) {
+        self.state.send_modify(|state| {
+            state.timer = TimerState { remaining_seconds, running, set_at: Instant::now() };
+        });
+    }
+
+    /// Where the board server is listening, if it has been started.
+    pub fn server_address(&self) -> Option<SocketAddr> {
+        self.server.lock().unwrap().as_ref().map(|server| server.address)
+    }
+}
+
+/// Starts serving the board and the self check-in page on `bind_address`, such as
+/// `0.0.0.0:8080` to reach every phone on the hall network. Returns the address
+/// actually listened on, which tells the port when port 0 was asked for.
+pub async fn start_server(db: SqlitePool, board: Arc<CourtBoard>, bind_address: &str) -> Result<SocketAddr, AppError> {
+    if let Some(address) = board.server_address() {
+        return Err(AppError::Conflict {
+            field: None,
+            message: format!("The court board is already being served on {}", address),
+        });
+    }
+
+    let bind_address: SocketAddr = bind_address.trim().parse().map_err(|_| {
+        AppError::validation("bind_address", format!("'{}' isn't an address and port like 0.0.0.0:8080", bind_address))
+    })?;
+
+    let listener = TcpListener::bind(bind_address)
+        .await
+        .map_err(|e| AppError::validation("bind_address", format!("Couldn't listen on {}: {}", bind_address, e)))?;
+    let address = listener.local_addr()?;
+
+    let (shutdown, mut shutdown_signal) = watch::channel(false);
+    let app = Router::new()
+        .route("/", get(board_page))
+        .route("/api/board", get(get_board))
+        .route("/api/round", get(get_round))
+        .route("/api/sit-outs", get(get_sit_outs))
+        .route("/api/timer", get(get_timer))
+        .route("/ws", get(board_socket))
+        .route("/check-in", get(check_in_page))
+        .route("/api/sessions/:session_id/check-in", get(get_check_in_list).post(post_check_in))
+        .with_state(ServerState {
+            db,
+            board: board.clone(),
+            shutdown: shutdown.subscribe(),
+        });
+
+    let task = tokio::spawn(async move {
+        let server = axum::serve(listener, app).with_graceful_shutdown(async move {
+            let _ = shutdown_signal.wait_for(|stop| *stop).await;
+        });
+
+        if let Err(e) = server.await {
+            log::error!("Court board server stopped: {}", e);
+        }
+    });
+
+    let mut running = board.server.lock().unwrap();
+    if running.is_some() {
+        // Another start won the race while this one was binding
+        let _ = shutdown.send(true);
+        return Err(AppError::Conflict { field: None, message: "The court board is already being served".to_string() });
+    }
+    *running = Some(RunningServer { address, shutdown, task });
+
+    Ok(address)
+}
+
+/// Stops the board server and disconnects the phones. Does nothing if it isn't running.
+pub async fn stop_server(board: &CourtBoard) {
+    let server = board.server.lock().unwrap().take();
+
+    if let Some(server) = server {
+        let _ = server.shutdown.send(true);
+        let _ = server.task.await;
+    }
+}
+
+/// Where phones on the network reach a server listening on `address`. One
+/// listening on every interface is reached through the interface the laptop uses
+/// for outside traffic, found by pointing a UDP socket at a documentation address
+/// without sending anything.
+pub fn reachable_address(address: SocketAddr) -> SocketAddr {
+    if !address.ip().is_unspecified() {
+        return address;
+    }
+
+    let ip = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+        .and_then(|socket| {
+            socket.connect((Ipv4Addr::new(192, 0, 2, 1), 9))?;
+            socket.local_addr()
+        })
+        .map(|local| local.ip())
+        .unwrap_or(Ipv4Addr::LOCALHOST.into());
+
+    SocketAddr::new(ip, address.port())
+}
+
+#[derive(Clone)]
+struct ServerState {
+    db: SqlitePool,
+    board: Arc<CourtBoard>,
+    shutdown: watch::Receiver<bool>,
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Conflict { .. } => StatusCode::CONFLICT,
+            AppError::Validation { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::Database(_) | AppError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        (status, Json(self)).into_response()
+    }
+}
+
+async fn board_page() -> Html<&'static str> {
+    Html(BOARD_PAGE)
+}
+
+async fn get_board(State(server): State<ServerState>) -> Json<BoardSnapshot> {
+    Json(server.board.snapshot())
+}
+
+async fn get_round(State(server): State<ServerState>) -> Json<Option<GamesRound>> {
+    Json(server.board.snapshot().round)
+}
+
+async fn get_sit_outs(State(server): State<ServerState>) -> Json<Vec<InGamePlayer>> {
+    Json(server.board.snapshot().round.map(|round| round.sitting_out).unwrap_or_default())
+}
+
+async fn get_timer(State(server): State<ServerState>) -> Json<BoardTimer> {
+    Json(server.board.snapshot().timer)
+}
+
+async fn check_in_page() -> Html<&'static str> {
+    Html(CHECK_IN_PAGE)
+}
+
+async fn get_check_in_list(
+    Path(session_id): Path<i64>,
+    State(server): State<ServerState>,
+) -> Result<Json<Vec<CheckInPlayer>>, AppError> {
+    Ok(Json(check_in_list(&server.db, session_id).await?))
+}
+
+async fn post_check_in(
+    Path(session_id): Path<i64>,
+    State(server): State<ServerState>,
+    Json(request): Json<SelfCheckInRequest>,
+) -> Result<Json<SessionPlayer>, AppError> {
+    Ok(Json(self_check_in(&server.db, &server.board.pin_attempts, session_id, &request).await?))
+}
+
+async fn board_socket(upgrade: WebSocketUpgrade, State(server): State<ServerState>) -> Response {
+    upgrade
+        .on_failed_upgrade(|e| log::warn!("Couldn't open a court board connection: {}", e))
+        .on_upgrade(move |socket| push_updates(socket, server))
+}
+
+/// Sends the board as JSON when the phone connects and again after every change,
+/// until the phone goes away or the server stops.
+async fn push_updates(mut socket: WebSocket, mut server: ServerState) {
+    let mut updates = server.board.state.subscribe();
+
+    loop {
+        let snapshot = updates.borrow_and_update().snapshot();
+        let json = match serde_json::to_string(&snapshot) {
+            Ok(json) => json,
+            Err(e) => {
+                log::error!("Couldn't turn the court board into JSON: {}", e);
+                return;
+            }
+        };
+
+        if let Err(e) = socket.send(Message::Text(json)).await {
+            log::warn!("Couldn't send the court board to a phone: {}", e);
+            return;
+        }
+
+        loop {
+            tokio::select! {
+                changed = updates.changed() => {
+                    if changed.is_err() {
+                        return;
+                    }
+                    break;
+                }
+                _ = server.shutdown.changed() => {
+                    let _ = socket.send(Message::Close(None)).await;
+                    return;
+                }
+                message = socket.recv() => match message {
+                    Some(Ok(Message::Close(_))) | None => return,
+                    Some(Err(e)) => {
+                        log::warn!("Lost a court board connection: {}", e);
+                        return;
+                    }
+                    // Phones only listen, so anything else they send is ignored
+                    Some(Ok(_)) => {}
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use crate::commands::sessions::check_in;
+use crate::models::{CheckInPlayer, SelfCheckInRequest, SessionPlayer};
+use crate::utils::errors::AppError;
+use sqlx::{Row, SqlitePool};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// PINs are too short for hashing cost to protect them, so the lockout does
+/// that, and a cheaper hash keeps check-in quick on the hall laptop.
+const PIN_HASH_COST: u32 = 10;
+
+/// Wrong PINs allowed for a player before their self check-in is locked.
+const MAX_PIN_ATTEMPTS: u32 = 5;
+const PIN_LOCKOUT: Duration = Duration::from_secs(5 * 60);
+
+/// PIN attempts made for each player, so a PIN can't be found by trying them all.
+#[derive(Default)]
+pub struct PinAttempts {
+    attempts: Mutex<HashMap<i64, (u32, Instant)>>,
+}
+
+impl PinAttempts {
+    /// Counts an attempt at a player's PIN, or refuses it while they are locked
+    /// out. The attempt is counted before the PIN is checked, so guesses sent
+    /// at the same time can't slip past the limit.
+    fn check(&self, player_id: i64) -> Result<(), AppError> {
+        let mut attempts = self.attempts.lock().unwrap();
+        let (count, last) = attempts.entry(player_id).or_insert((0, Instant::now()));
+
+        if last.elapsed() >= PIN_LOCKOUT {
+            *count = 0;
+        }
+
+        if *count >= MAX_PIN_ATTEMPTS {
+            return Err(AppError::validation("pin", "Too many wrong PINs, ask an organiser to check you in"));
+        }
+
+        *count += 1;
+        *last = Instant::now();
+        Ok(())
+    }
+
+    /// Forgets a player's attempts once they get their PIN right.
+    fn reset(&self, player_id: i64) {
+        self.attempts.lock().unwrap().remove(&player_id);
+    }
+}
+
+/// A PIN is 4 to 6 digits.
+pub fn validate_pin(pin: &str) -> Result<(), AppError> {
+    if !(4..=6).contains(&pin.len()) || !pin.chars().all(|c| c.is_ascii_digit()) {
+        return Err(AppError::validation("pin", "PIN must be 4 to 6 digits"));
+    }
+
+    Ok(())
+}
+
+/// Sets the PIN a player checks themselves in with, or removes it so they can't.
+pub async fn set_pin(db: &SqlitePool, player_id: i64, pin: Option<&str>) -> Result<(), AppError> {
+    let hash = match pin {
+        Some(pin) => {
+            validate_pin(pin)?;
+            let pin = pin.to_string();
+            let hash = tokio::task::spawn_blocking(move || bcrypt::hash(pin, PIN_HASH_COST))
+                .await
+                .map_err(|e| AppError::Database(format!("Failed to hash PIN: {}", e)))?
+                .map_err(|e| AppError::Database(format!("Failed to hash PIN: {}", e)))?;
+            Some(hash)
+        }
+        None => None,
+    };
+
+    let result = sqlx::query("UPDATE players SET check_in_pin_hash = ? WHERE id = ?")
+        .bind(hash)
+        .bind(player_id)
+        .execute(db)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found("Player not found"));
+    }
+
+    Ok(())
+}
+
+/// The members of a running session's club who have a PIN, for players to find
+/// themselves in.
+pub async fn check_in_list(db: &SqlitePool, session_id: i64) -> Result<Vec<CheckInPlayer>, AppError> {
+    let club_id = running_session_club(db, session_id).await?;
+
+    let rows = sqlx::query(
+        "SELECT p.id, p.first_name, p.last_name,
+         EXISTS (SELECT 1 FROM session_players sp
+                 WHERE sp.session_id = ? AND sp.player_id = p.id AND sp.checked_out_at IS NULL) as checked_in
+         FROM players p
+         JOIN club_memberships cm ON cm.player_id = p.id AND cm.club_id = ? AND cm.status = 'Active'
+         WHERE p.check_in_pin_hash IS NOT NULL
+         ORDER BY p.first_name, p.last_name"
+    )
+    .bind(session_id)
+    .bind(club_id)
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| CheckInPlayer {
+            id: row.get("id"),
+            first_name: row.get("first_name"),
+            last_name: row.get("last_name"),
+            checked_in: row.get("checked_in"),
+        })
+        .collect())
+}
+
+/// Checks a player into a running session of their club once their PIN matches.
+pub async fn self_check_in(
+    db: &SqlitePool,
+    attempts: &PinAttempts,
+    session_id: i64,
+    request: &SelfCheckInRequest,
+) -> Result<SessionPlayer, AppError> {
+    let club_id = running_session_club(db, session_id).await?;
+
+    let row = sqlx::query(
+        "SELECT p.check_in_pin_hash FROM players p
+         JOIN club_memberships cm ON cm.player_id = p.id AND cm.club_id = ? AND cm.status = 'Active'
+         WHERE p.id = ?"
+    )
+    .bind(club_id)
+    .bind(request.player_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| AppError::not_found("Player not found"))?;
+
+    let Some(hash) = row.get::<Option<String>, _>("check_in_pin_hash") else {
+        return Err(AppError::validation("pin", "You don't have a PIN yet, ask an organiser to set one"));
+    };
+
+    attempts.check(request.player_id)?;
+
+    let pin = request.pin.clone();
+    let correct = tokio::task::spawn_blocking(move || bcrypt::verify(pin, &hash))
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to check PIN: {}", e)))?
+        .map_err(|e| AppError::Database(format!("Failed to check PIN: {}", e)))?;
+
+    if !correct {
+        return Err(AppError::validation("pin", "That PIN isn't right"));
+    }
+    attempts.reset(request.player_id);
+
+    check_in(db, session_id, request.player_id).await
+}
+
+/// The club of a session that is still running.
+pub(crate) async fn running_session_club(db: &SqlitePool, session_id: i64) -> Result<i64, AppError> {
+    let session = sqlx::query("SELECT club_id, ended_at FROM sessions WHERE id = ?")
+        .bind(session_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| AppError::not_found("Session not found"))?;
+
+    if session.get::<Option<String>, _>("ended_at").is_some() {
+        return Err(AppError::Conflict { field: None, message: "This session has ended".to_string() });
+    }
+
+    Ok(session.get("club_id"))
+}
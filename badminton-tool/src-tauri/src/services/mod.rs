@@ -1 +1,6 @@
-
+pub mod audit;
+pub mod auth;
+pub mod backup;
+pub mod board;
+pub mod check_in;
+pub mod rating;
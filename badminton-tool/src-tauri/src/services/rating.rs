@@ -0,0 +1,217 @@
+use crate::models::{rating_for_skill_level, skill_level_for_rating, Team};
+use crate::utils::errors::AppError;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqliteConnection};
+use std::collections::HashSet;
+use std::f64::consts::{LN_10, PI};
+
+/// Deviation of a player with no rated games.
+pub const INITIAL_DEVIATION: f64 = 350.0;
+/// Floor on the deviation, so a settled rating can still follow real improvement.
+pub const MIN_DEVIATION: f64 = 50.0;
+
+const Q: f64 = LN_10 / 400.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rating {
+    pub rating: f64,
+    pub deviation: f64,
+}
+
+impl Rating {
+    /// Starting rating for a player who has only been given a skill level.
+    pub fn from_skill_level(skill_level: i32) -> Self {
+        Rating {
+            rating: rating_for_skill_level(skill_level),
+            deviation: INITIAL_DEVIATION,
+        }
+    }
+}
+
+/// Glicko-style update for one game, singles or doubles. Each side is treated as
+/// a single opponent rated at the average of its players, so a pair is judged on
+/// what the pair was expected to do, then every player moves by their own
+/// deviation. Returns the new ratings of each side in the order given.
+pub fn rate_game(team_a: &[Rating], team_b: &[Rating], winner: Team) -> (Vec<Rating>, Vec<Rating>) {
+    let side_a = combine(team_a);
+    let side_b = combine(team_b);
+    let (score_a, score_b) = match winner {
+        Team::A => (1.0, 0.0),
+        Team::B => (0.0, 1.0),
+    };
+
+    let new_a = team_a.iter().map(|player| update(player, &side_a, &side_b, score_a)).collect();
+    let new_b = team_b.iter().map(|player| update(player, &side_b, &side_a, score_b)).collect();
+
+    (new_a, new_b)
+}
+
+fn combine(team: &[Rating]) -> Rating {
+    let count = team.len().max(1) as f64;
+    Rating {
+        rating: team.iter().map(|r| r.rating).sum::<f64>() / count,
+        deviation: (team.iter().map(|r| r.deviation.powi(2)).sum::<f64>() / count).sqrt(),
+    }
+}
+
+fn g(deviation: f64) -> f64 {
+    1.0 / (1.0 + 3.0 * Q.powi(2) * deviation.powi(2) / PI.powi(2)).sqrt()
+}
+
+fn update(player: &Rating, own_side: &Rating, other_side: &Rating, score: f64) -> Rating {
+    let g = g(other_side.deviation);
+    let expected = 1.0 / (1.0 + 10f64.powf(-g * (own_side.rating - other_side.rating) / 400.0));
+    let d_squared = 1.0 / (Q.powi(2) * g.powi(2) * expected * (1.0 - expected));
+    let precision = 1.0 / player.deviation.powi(2) + 1.0 / d_squared;
+
+    Rating {
+        rating: player.rating + Q / precision * g * (score - expected),
+        deviation: (1.0 / precision).sqrt().max(MIN_DEVIATION),
+    }
+}
+
+/// Applies a recorded result to the ratings of everyone who played in the game,
+/// logging each change in `rating_history`. Players at clubs that let ratings
+/// drive skill levels also have their `skill_level` moved to match.
+pub async fn apply_result(conn: &mut SqliteConnection, game_id: i64) -> Result<(), AppError> {
+    let winner = sqlx::query("SELECT winner FROM game_results WHERE game_id = ?")
+        .bind(game_id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .map(|row| Team::from_db(row.get("winner")))
+        .ok_or_else(|| AppError::not_found("Game has no result"))?;
+
+    let rows = sqlx::query(
+        "SELECT p.id, p.skill_level, p.rating, p.rating_deviation, c.auto_skill_level, rgp.team
+         FROM round_game_players rgp
+         JOIN players p ON p.id = rgp.player_id
+         JOIN clubs c ON c.id = p.club_id
+         WHERE rgp.game_id = ?
+         ORDER BY rgp.position"
+    )
+    .bind(game_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let (side_a, side_b): (Vec<&SqliteRow>, Vec<&SqliteRow>) =
+        rows.iter().partition(|row| row.get::<String, _>("team") == "A");
+    let before_a: Vec<Rating> = side_a.iter().map(|row| current_rating(row)).collect();
+    let before_b: Vec<Rating> = side_b.iter().map(|row| current_rating(row)).collect();
+    let (after_a, after_b) = rate_game(&before_a, &before_b, winner);
+
+    let changes = side_a
+        .iter()
+        .zip(before_a.iter().zip(&after_a))
+        .chain(side_b.iter().zip(before_b.iter().zip(&after_b)));
+
+    for (row, (before, after)) in changes {
+        let player_id: i64 = row.get("id");
+
+        sqlx::query(
+            "INSERT INTO rating_history (player_id, game_id, rating_before, deviation_before, rating_after, deviation_after)
+             VALUES (?, ?, ?, ?, ?, ?)"
+        )
+        .bind(player_id)
+        .bind(game_id)
+        .bind(before.rating)
+        .bind(before.deviation)
+        .bind(after.rating)
+        .bind(after.deviation)
+        .execute(&mut *conn)
+        .await?;
+
+        let skill_level = if row.get::<bool, _>("auto_skill_level") {
+            skill_level_for_rating(after.rating)
+        } else {
+            row.get("skill_level")
+        };
+
+        sqlx::query("UPDATE players SET rating = ?, rating_deviation = ?, skill_level = ? WHERE id = ?")
+            .bind(after.rating)
+            .bind(after.deviation)
+            .bind(skill_level)
+            .bind(player_id)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Players who have not been rated yet start from their skill level.
+fn current_rating(row: &SqliteRow) -> Rating {
+    match (row.get::<Option<f64>, _>("rating"), row.get::<Option<f64>, _>("rating_deviation")) {
+        (Some(rating), Some(deviation)) => Rating { rating, deviation },
+        _ => Rating::from_skill_level(row.get("skill_level")),
+    }
+}
+
+/// Replays the results from `from_game_id` onward after that game's result was
+/// corrected, in the order the games were played. Only the games it could have
+/// changed are replayed: the corrected game and every later game with a player
+/// whose rating has already been changed by the replay. Everyone else keeps
+/// their ratings and history.
+pub async fn replay_ratings(conn: &mut SqliteConnection, from_game_id: i64) -> Result<(), AppError> {
+    let rows = sqlx::query(
+        "SELECT gr.game_id, rgp.player_id
+         FROM game_results gr
+         JOIN round_game_players rgp ON rgp.game_id = gr.game_id
+         WHERE gr.game_id >= ?
+         ORDER BY gr.game_id"
+    )
+    .bind(from_game_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut games: Vec<(i64, Vec<i64>)> = Vec::new();
+    for row in &rows {
+        let game_id: i64 = row.get("game_id");
+        match games.last_mut() {
+            Some((last_id, players)) if *last_id == game_id => players.push(row.get("player_id")),
+            _ => games.push((game_id, vec![row.get("player_id")])),
+        }
+    }
+
+    let mut affected: HashSet<i64> = HashSet::new();
+    let mut replayed: Vec<i64> = Vec::new();
+    for (game_id, players) in games {
+        if game_id == from_game_id || players.iter().any(|id| affected.contains(id)) {
+            affected.extend(players);
+            replayed.push(game_id);
+        }
+    }
+
+    // Put each affected player back where the first replayed game found them
+    let mut reset: HashSet<i64> = HashSet::new();
+    for game_id in &replayed {
+        let history = sqlx::query("SELECT player_id, rating_before, deviation_before FROM rating_history WHERE game_id = ? ORDER BY id")
+            .bind(game_id)
+            .fetch_all(&mut *conn)
+            .await?;
+
+        for row in history {
+            let player_id: i64 = row.get("player_id");
+            if !reset.insert(player_id) {
+                continue;
+            }
+
+            sqlx::query("UPDATE players SET rating = ?, rating_deviation = ? WHERE id = ?")
+                .bind(row.get::<f64, _>("rating_before"))
+                .bind(row.get::<f64, _>("deviation_before"))
+                .bind(player_id)
+                .execute(&mut *conn)
+                .await?;
+        }
+
+        sqlx::query("DELETE FROM rating_history WHERE game_id = ?")
+            .bind(game_id)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    for game_id in replayed {
+        apply_result(conn, game_id).await?;
+    }
+
+    Ok(())
+}
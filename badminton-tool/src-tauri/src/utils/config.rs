@@ -1,5 +1,94 @@
-use std::path::PathBuf;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
 
-pub fn get_database_path() -> PathBuf {
-    PathBuf::from("../data").join("app_data.db")
-}
\ No newline at end of file
+/// Environment variable that overrides where the database is kept.
+pub const DATABASE_PATH_ENV: &str = "BADMINTON_DB_PATH";
+
+/// Name of the settings file looked for in the app config directory.
+pub const CONFIG_FILE_NAME: &str = "config.toml";
+
+pub const DATABASE_FILE_NAME: &str = "app_data.db";
+
+/// Backups kept by automatic backups unless `keep` says otherwise.
+pub const DEFAULT_BACKUPS_KEPT: usize = 10;
+
+/// Settings read from `config.toml`. Every setting is optional.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct Config {
+    /// Database file to use. A relative path is taken from the config directory.
+    pub database_path: Option<PathBuf>,
+    /// Back the database up whenever a session ends. Off unless the section is present.
+    pub auto_backup: Option<AutoBackupConfig>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct AutoBackupConfig {
+    /// Folder for the backups, `backups` in the app data directory if not given.
+    /// A relative path is taken from the config directory.
+    pub directory: Option<PathBuf>,
+    /// How many of the newest backups to keep.
+    #[serde(default = "default_backups_kept")]
+    pub keep: usize,
+}
+
+fn default_backups_kept() -> usize {
+    DEFAULT_BACKUPS_KEPT
+}
+
+/// Where automatic backups go and how many are kept.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutoBackup {
+    pub directory: PathBuf,
+    pub keep: usize,
+}
+
+/// Reads `config.toml` from `config_dir`, or the defaults if there isn't one.
+pub fn load_config(config_dir: &Path) -> Result<Config, String> {
+    let path = config_dir.join(CONFIG_FILE_NAME);
+
+    match std::fs::read_to_string(&path) {
+        Ok(text) => toml::from_str(&text).map_err(|e| format!("Invalid config file {}: {}", path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Config::default()),
+        Err(e) => Err(format!("Failed to read config file {}: {}", path.display(), e)),
+    }
+}
+
+/// Where the database lives when nothing overrides it.
+pub fn default_database_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join(DATABASE_FILE_NAME)
+}
+
+/// Where the database was kept before it moved to the app data directory. It's
+/// relative to the working directory, which is why it moved.
+pub fn legacy_database_path() -> PathBuf {
+    PathBuf::from("../data").join(DATABASE_FILE_NAME)
+}
+
+/// Picks the database file: `BADMINTON_DB_PATH` first, then `database_path` from
+/// `config.toml`, then the platform app data directory.
+pub fn get_database_path(app_data_dir: &Path, config_dir: &Path) -> Result<PathBuf, String> {
+    if let Some(path) = std::env::var_os(DATABASE_PATH_ENV).filter(|path| !path.is_empty()) {
+        return Ok(PathBuf::from(path));
+    }
+
+    let config = load_config(config_dir)?;
+
+    Ok(match config.database_path {
+        Some(path) => config_dir.join(path),
+        None => default_database_path(app_data_dir),
+    })
+}
+
+/// The automatic backup settings from `config.toml`, if they're turned on.
+pub fn get_auto_backup(app_data_dir: &Path, config_dir: &Path) -> Result<Option<AutoBackup>, String> {
+    let config = load_config(config_dir)?;
+
+    Ok(config.auto_backup.map(|backup| AutoBackup {
+        directory: match backup.directory {
+            Some(directory) => config_dir.join(directory),
+            None => app_data_dir.join("backups"),
+        },
+        keep: backup.keep.max(1),
+    }))
+}
@@ -1 +1,107 @@
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::fmt;
 
+/// Error returned by commands. Reaches the frontend as `{ kind, message, field }`,
+/// where `field` names the offending request field when there is one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppError {
+    NotFound(String),
+    /// The change clashes with data already stored, such as a duplicate email.
+    Conflict { field: Option<String>, message: String },
+    Validation { field: String, message: String },
+    /// Not logged in, or the login has ended.
+    Unauthorized(String),
+    /// Logged in, but without the role the command needs.
+    Forbidden(String),
+    Database(String),
+    /// Reading or writing a file outside the database failed.
+    Io(String),
+}
+
+impl AppError {
+    pub fn not_found(message: impl Into<String>) -> AppError {
+        AppError::NotFound(message.into())
+    }
+
+    pub fn validation(field: impl Into<String>, message: impl Into<String>) -> AppError {
+        AppError::Validation { field: field.into(), message: message.into() }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AppError::NotFound(_) => "NotFound",
+            AppError::Conflict { .. } => "Conflict",
+            AppError::Validation { .. } => "Validation",
+            AppError::Unauthorized(_) => "Unauthorized",
+            AppError::Forbidden(_) => "Forbidden",
+            AppError::Database(_) => "Database",
+            AppError::Io(_) => "Io",
+        }
+    }
+
+    pub fn field(&self) -> Option<&str> {
+        match self {
+            AppError::Conflict { field, .. } => field.as_deref(),
+            AppError::Validation { field, .. } => Some(field),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::NotFound(message) => write!(f, "{}", message),
+            AppError::Conflict { message, .. } => write!(f, "{}", message),
+            AppError::Validation { message, .. } => write!(f, "{}", message),
+            AppError::Unauthorized(message) => write!(f, "{}", message),
+            AppError::Forbidden(message) => write!(f, "{}", message),
+            AppError::Database(message) => write!(f, "Database error: {}", message),
+            AppError::Io(message) => write!(f, "File error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut error = serializer.serialize_struct("AppError", 3)?;
+        error.serialize_field("kind", self.kind())?;
+        error.serialize_field("message", &self.to_string())?;
+        error.serialize_field("field", &self.field())?;
+        error.end()
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> AppError {
+        AppError::Io(e.to_string())
+    }
+}
+
+impl From<sqlx::Error> for AppError {
+    fn from(e: sqlx::Error) -> AppError {
+        if let sqlx::Error::Database(db_error) = &e {
+            if db_error.is_unique_violation() {
+                // SQLite reports "UNIQUE constraint failed: players.email"
+                let field = db_error
+                    .message()
+                    .rsplit(['.', ' '])
+                    .next()
+                    .filter(|column| !column.is_empty())
+                    .map(str::to_string);
+
+                let message = match &field {
+                    Some(field) => format!("That {} is already in use", field),
+                    None => "That record already exists".to_string(),
+                };
+
+                return AppError::Conflict { field, message };
+            }
+        }
+
+        AppError::Database(e.to_string())
+    }
+}
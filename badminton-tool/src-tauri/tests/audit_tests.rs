@@ -0,0 +1,269 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::*;
+    use lib::commands::clubs::*;
+    use lib::commands::export::*;
+    use lib::commands::import::*;
+    use lib::commands::memberships::*;
+    use lib::commands::player::*;
+    use lib::models::*;
+    use lib::services::audit::*;
+    use lib::services::auth::register;
+    use sqlx::{Row, SqlitePool};
+
+    fn alice() -> User {
+        User { id: 1, username: "alice".to_string(), role: AccountRole::Admin }
+    }
+
+    /// Saves a player with a new skill level and home club through `edit_player`.
+    async fn set_skill_level(db: &SqlitePool, actor: Option<&User>, player_id: i64, club_id: i64, skill_level: i32) {
+        let player = sqlx::query("SELECT first_name, last_name, email, gender FROM players WHERE id = ?")
+            .bind(player_id)
+            .fetch_one(db)
+            .await
+            .unwrap();
+
+        let request = CreatePlayerRequest {
+            first_name: player.get("first_name"),
+            last_name: player.get("last_name"),
+            email: player.get("email"),
+            gender: Gender::from_db(player.get("gender")),
+            club_id,
+            skill_level,
+        };
+        edit_player(db, actor, player_id, request).await.unwrap();
+    }
+
+    fn dan(club_id: i64) -> CreatePlayerRequest {
+        CreatePlayerRequest {
+            first_name: "Dan".to_string(),
+            last_name: "Dale".to_string(),
+            email: "dan@example.com".to_string(),
+            gender: Gender::Male,
+            club_id,
+            skill_level: 8,
+        }
+    }
+
+    async fn entry_ids(db: &SqlitePool, club_id: i64, filters: AuditLogFilters) -> Vec<i64> {
+        audit_log(db, club_id, &filters).await.unwrap().iter().map(|entry| entry.id).collect()
+    }
+
+    /// Action, entity and entity id of each entry in a club's log, oldest first.
+    async fn entries(db: &SqlitePool, club_id: i64) -> Vec<(AuditAction, AuditEntity, i64)> {
+        let log = audit_log(db, club_id, &AuditLogFilters::default()).await.unwrap();
+        log.iter().rev().map(|entry| (entry.action, entry.entity, entry.entity_id)).collect()
+    }
+
+    async fn entry_count(db: &SqlitePool) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM audit_log").fetch_one(db).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_entries_are_saved_only_with_their_change() {
+        let db = seeded_database(&[TWO_CLUBS]).await;
+
+        let mut tx = db.begin().await.unwrap();
+        let before = club_snapshot(&mut tx, 2).await.unwrap();
+        sqlx::query("DELETE FROM clubs WHERE id = 2").execute(&mut *tx).await.unwrap();
+        let change = AuditChange {
+            action: AuditAction::Delete,
+            entity: AuditEntity::Club,
+            entity_id: 2,
+            club_id: 2,
+            before,
+            after: None,
+        };
+        record(&mut tx, None, change).await.unwrap();
+        tx.rollback().await.unwrap();
+
+        assert!(audit_log(&db, 2, &AuditLogFilters::default()).await.unwrap().is_empty());
+
+        set_skill_level(&db, Some(&alice()), 1, 1, 11).await;
+        let log = audit_log(&db, 1, &AuditLogFilters::default()).await.unwrap();
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].actor.as_deref(), Some("alice"));
+        assert_eq!((log[0].action, log[0].entity, log[0].entity_id), (AuditAction::Update, AuditEntity::Player, 1));
+        assert_eq!(log[0].before.as_ref().unwrap()["skill_level"], 10);
+        assert_eq!(log[0].after.as_ref().unwrap()["skill_level"], 11);
+    }
+
+    #[tokio::test]
+    async fn test_audit_log_filters() {
+        let db = seeded_database(&[TWO_CLUBS]).await;
+        set_skill_level(&db, Some(&alice()), 1, 1, 11).await;
+        set_skill_level(&db, None, 1, 1, 12).await;
+        set_skill_level(&db, Some(&alice()), 3, 2, 16).await;
+
+        // Ann moving to the other club shows up in both clubs' logs
+        set_skill_level(&db, Some(&alice()), 1, 2, 12).await;
+
+        assert_eq!(entry_ids(&db, 1, AuditLogFilters::default()).await, vec![4, 2, 1]);
+        assert_eq!(entry_ids(&db, 1, AuditLogFilters { actor: Some("alice".to_string()), ..Default::default() }).await, vec![4, 1]);
+        assert_eq!(entry_ids(&db, 1, AuditLogFilters { limit: Some(1), ..Default::default() }).await, vec![4]);
+        assert_eq!(entry_ids(&db, 1, AuditLogFilters { entity: Some(AuditEntity::Club), ..Default::default() }).await, Vec::<i64>::new());
+
+        let ann_updates = AuditLogFilters { action: Some(AuditAction::Update), entity_id: Some(1), ..Default::default() };
+        assert_eq!(entry_ids(&db, 1, ann_updates).await, vec![4, 2, 1]);
+
+        let future = AuditLogFilters { since: Some("2100-01-01 00:00:00".to_string()), ..Default::default() };
+        assert_eq!(entry_ids(&db, 1, future).await, Vec::<i64>::new());
+
+        assert_eq!(entry_ids(&db, 2, AuditLogFilters::default()).await, vec![4, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_player_and_club_changes_are_logged() {
+        let db = empty_database().await;
+        let alice = register(&db, "alice", "password1").await.unwrap();
+
+        let club = add_club(&db, Some(&alice), CreateClubRequest { name: "Shuttlers".to_string() }).await.unwrap();
+        let player = add_player(&db, Some(&alice), dan(club.id)).await.unwrap();
+        edit_player(&db, None, player.id, CreatePlayerRequest { skill_level: 9, ..dan(club.id) }).await.unwrap();
+        remove_player(&db, Some(&alice), player.id).await.unwrap();
+
+        assert_eq!(
+            entries(&db, club.id).await,
+            vec![
+                (AuditAction::Create, AuditEntity::Club, club.id),
+                (AuditAction::Create, AuditEntity::Player, player.id),
+                (AuditAction::Update, AuditEntity::Player, player.id),
+                (AuditAction::Delete, AuditEntity::Player, player.id),
+            ]
+        );
+
+        let log = audit_log(&db, club.id, &AuditLogFilters::default()).await.unwrap();
+        assert_eq!(log[0].before.as_ref().unwrap()["skill_level"], 9);
+        assert_eq!(log[0].after, None);
+        assert_eq!(log[3].after.as_ref().unwrap()["name"], "Shuttlers");
+
+        // A refused change leaves nothing behind
+        assert!(add_player(&db, None, CreatePlayerRequest { skill_level: 0, ..dan(club.id) }).await.is_err());
+        assert!(add_club(&db, None, CreateClubRequest { name: " ".to_string() }).await.is_err());
+        assert!(remove_player(&db, None, player.id).await.is_err());
+        assert_eq!(entry_count(&db).await, 4);
+    }
+
+    #[tokio::test]
+    async fn test_deleting_a_club_logs_its_players() {
+        let db = seeded_database(&[TWO_CLUBS]).await;
+        add_membership(&db, 1, 2, None).await.unwrap();
+
+        remove_club(&db, Some(&alice()), 1).await.unwrap();
+
+        // Ann moves to the other club and Bob goes with his only club
+        assert_eq!(
+            entries(&db, 1).await,
+            vec![
+                (AuditAction::Update, AuditEntity::Player, 1),
+                (AuditAction::Delete, AuditEntity::Player, 2),
+                (AuditAction::Delete, AuditEntity::Club, 1),
+            ]
+        );
+
+        let ann = &audit_log(&db, 2, &AuditLogFilters::default()).await.unwrap()[0];
+        assert_eq!((ann.entity_id, ann.club_id), (1, Some(2)));
+        assert_eq!(ann.before.as_ref().unwrap()["club_id"], 1);
+        assert_eq!(ann.after.as_ref().unwrap()["club_id"], 2);
+        assert!(ann.actor.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_auto_skill_level_logs_the_players_it_changes() {
+        let db = seeded_database(&[FINISHED_SESSION, "UPDATE players SET skill_level = 2 WHERE id = 2"]).await;
+
+        change_auto_skill_level(&db, Some(&alice()), 1, true).await.unwrap();
+
+        // Bob's skill level already matched his rating, and Eve has no rating
+        assert_eq!(
+            entries(&db, 1).await,
+            vec![
+                (AuditAction::Update, AuditEntity::Club, 1),
+                (AuditAction::Update, AuditEntity::Player, 1),
+                (AuditAction::Update, AuditEntity::Player, 3),
+            ]
+        );
+
+        let log = audit_log(&db, 1, &AuditLogFilters::default()).await.unwrap();
+        assert_eq!(log[0].before.as_ref().unwrap()["skill_level"], 14);
+        assert_eq!(log[0].after.as_ref().unwrap()["skill_level"], 1);
+        assert_eq!(log[2].after.as_ref().unwrap()["auto_skill_level"], true);
+        assert_eq!(log[2].actor.as_deref(), Some("alice"));
+
+        // Turning it off changes no players
+        change_auto_skill_level(&db, None, 1, false).await.unwrap();
+        assert_eq!(entry_count(&db).await, 4);
+        assert_eq!(change_auto_skill_level(&db, None, 9, true).await.unwrap_err().kind(), "NotFound");
+    }
+
+    #[tokio::test]
+    async fn test_imports_are_logged() {
+        let db = seeded_database(&[TWO_CLUBS, "INSERT INTO club_memberships (player_id, club_id) VALUES (3, 1)"]).await;
+
+        // Dan is new, and Cat also plays here
+        let csv = "first_name,last_name,email,gender,skill_level\n\
+                   Dan,Dale,dan@example.com,Male,8\n\
+                   Cat,Cedar,cat@example.com,Female,15\n";
+        import_players(&db, Some(&alice()), 1, csv, ImportOptions { dry_run: true, ..Default::default() }).await.unwrap();
+        assert_eq!(entry_count(&db).await, 0);
+
+        import_players(&db, Some(&alice()), 1, csv, ImportOptions::default()).await.unwrap();
+        assert_eq!(
+            entries(&db, 1).await,
+            vec![(AuditAction::Create, AuditEntity::Player, 4), (AuditAction::Update, AuditEntity::Player, 3)]
+        );
+
+        // Cat's home club sees the change too
+        let cat = &audit_log(&db, 2, &AuditLogFilters::default()).await.unwrap()[0];
+        assert_eq!(cat.after.as_ref().unwrap()["skill_level"], 15);
+
+        let source = seeded_database(&[FINISHED_SESSION]).await;
+        let export = build_club_export(&source, 1).await.unwrap();
+        let target = empty_database().await;
+        let alice = register(&target, "alice", "password1").await.unwrap();
+        let club = import_club_export(&target, &export, Some(&alice)).await.unwrap();
+
+        let logged = entries(&target, club.id).await;
+        assert_eq!(logged[0], (AuditAction::Create, AuditEntity::Club, club.id));
+        assert_eq!(logged.len(), 1 + export.players.len());
+        assert!(logged[1..].iter().all(|entry| (entry.0, entry.1) == (AuditAction::Create, AuditEntity::Player)));
+    }
+
+    #[tokio::test]
+    async fn test_nothing_is_logged_when_a_change_is_rolled_back() {
+        let db = seeded_database(&[
+            TWO_CLUBS,
+            "CREATE TRIGGER keep_clubs BEFORE DELETE ON clubs BEGIN SELECT RAISE(ABORT, 'Clubs are kept'); END",
+        ])
+        .await;
+
+        // Bob's entry is written before the club's delete fails
+        assert!(remove_club(&db, Some(&alice()), 1).await.is_err());
+        assert_eq!(entry_count(&db).await, 0);
+
+        // The export's second player takes the first one's number, after the first is logged
+        let source = seeded_database(&[FINISHED_SESSION]).await;
+        let mut export = build_club_export(&source, 1).await.unwrap();
+        export.players[1].number = export.players[0].number;
+        let target = empty_database().await;
+        assert_eq!(import_club_export(&target, &export, None).await.unwrap_err().kind(), "Validation");
+        assert_eq!(entry_count(&target).await, 0);
+
+        // An entry that can't be written takes its change with it
+        sqlx::query("CREATE TRIGGER refuse_entries BEFORE INSERT ON audit_log BEGIN SELECT RAISE(ABORT, 'Log is full'); END")
+            .execute(&db)
+            .await
+            .unwrap();
+        assert!(add_player(&db, None, dan(1)).await.is_err());
+        assert!(edit_player(&db, None, 1, CreatePlayerRequest { skill_level: 20, ..dan(1) }).await.is_err());
+        assert!(remove_player(&db, None, 2).await.is_err());
+        assert!(add_club(&db, None, CreateClubRequest { name: "Third club".to_string() }).await.is_err());
+
+        let players: Vec<(i64, String, i32)> =
+            sqlx::query_as("SELECT id, first_name, skill_level FROM players ORDER BY id").fetch_all(&db).await.unwrap();
+        assert_eq!(players, vec![(1, "Ann".to_string(), 10), (2, "Bob".to_string(), 12), (3, "Cat".to_string(), 14)]);
+        assert_eq!(list_clubs(&db).await.unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,144 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::*;
+    use lib::commands::player::remove_player;
+    use lib::database::run_migrations;
+    use lib::models::*;
+    use lib::services::auth::*;
+    use sqlx::sqlite::SqliteConnectOptions;
+    use sqlx::SqlitePool;
+
+    /// The database the app shipped with, from before there were logins.
+    const UNVERSIONED_DB: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../data/app_data.db");
+
+    async fn give_role(db: &SqlitePool, user_id: i64, role: ClubRole) {
+        sqlx::query("INSERT INTO club_roles (user_id, club_id, role) VALUES (?, 1, ?)")
+            .bind(user_id)
+            .bind(role.as_str())
+            .execute(db)
+            .await
+            .unwrap();
+    }
+
+    /// The kind of error refusing the account, if it is refused.
+    async fn check(db: &SqlitePool, logins: &Logins, token: Option<&str>, club_id: i64, minimum: ClubRole) -> Result<(), &'static str> {
+        require_club_role(db, logins, token, club_id, minimum).await.map_err(|e| e.kind())
+    }
+
+    #[tokio::test]
+    async fn test_register_and_log_in() {
+        let db = seeded_database(&[TWO_CLUBS]).await;
+        let logins = Logins::default();
+
+        assert_eq!(register(&db, "al", "password1").await.unwrap_err().field(), Some("username"));
+        assert_eq!(register(&db, "alice", "short").await.unwrap_err().field(), Some("password"));
+
+        // The first account is the admin
+        let alice = register(&db, " alice ", "password1").await.unwrap();
+        assert_eq!((alice.username.as_str(), alice.role), ("alice", AccountRole::Admin));
+        let bob = register(&db, "bob", "password2").await.unwrap();
+        assert_eq!(bob.role, AccountRole::User);
+
+        let error = register(&db, "bob", "password3").await.unwrap_err();
+        assert_eq!((error.kind(), error.field()), ("Conflict", Some("username")));
+
+        let wrong_password = log_in(&db, &logins, "bob", "password1").await.unwrap_err();
+        let unknown_user = log_in(&db, &logins, "carol", "password1").await.unwrap_err();
+        assert_eq!(wrong_password.kind(), "Unauthorized");
+        assert_eq!(wrong_password, unknown_user);
+
+        let login = log_in(&db, &logins, "bob", "password2").await.unwrap();
+        assert_eq!(login.user, bob);
+        assert_eq!(current_user(&db, &logins, Some(&login.token)).await.unwrap(), bob);
+
+        logins.end(&login.token);
+        assert_eq!(current_user(&db, &logins, Some(&login.token)).await.unwrap_err().kind(), "Unauthorized");
+    }
+
+    #[tokio::test]
+    async fn test_club_roles_guard_destructive_commands() {
+        let db = seeded_database(&[TWO_CLUBS]).await;
+        let logins = Logins::default();
+
+        // Before anyone registers the app runs without logging in
+        assert_eq!(check(&db, &logins, None, 1, ClubRole::Owner).await, Ok(()));
+
+        register(&db, "admin", "password1").await.unwrap();
+        let organiser = register(&db, "organiser", "password2").await.unwrap();
+        let viewer = register(&db, "viewer", "password3").await.unwrap();
+        give_role(&db, organiser.id, ClubRole::Organiser).await;
+        give_role(&db, viewer.id, ClubRole::Viewer).await;
+
+        let admin_token = log_in(&db, &logins, "admin", "password1").await.unwrap().token;
+        let organiser_token = log_in(&db, &logins, "organiser", "password2").await.unwrap().token;
+        let viewer_token = log_in(&db, &logins, "viewer", "password3").await.unwrap().token;
+
+        assert_eq!(check(&db, &logins, None, 1, ClubRole::Organiser).await, Err("Unauthorized"));
+        assert_eq!(check(&db, &logins, Some("made-up"), 1, ClubRole::Organiser).await, Err("Unauthorized"));
+        assert_eq!(check(&db, &logins, Some(&viewer_token), 1, ClubRole::Organiser).await, Err("Forbidden"));
+        assert_eq!(check(&db, &logins, Some(&viewer_token), 1, ClubRole::Viewer).await, Ok(()));
+        assert_eq!(check(&db, &logins, Some(&organiser_token), 1, ClubRole::Organiser).await, Ok(()));
+        assert_eq!(check(&db, &logins, Some(&organiser_token), 1, ClubRole::Owner).await, Err("Forbidden"));
+
+        // Roles are per club, admins can do anything anywhere
+        assert_eq!(check(&db, &logins, Some(&organiser_token), 2, ClubRole::Viewer).await, Err("Forbidden"));
+        assert_eq!(check(&db, &logins, Some(&admin_token), 2, ClubRole::Owner).await, Ok(()));
+    }
+    #[tokio::test]
+    async fn test_admin_only_commands() {
+        let db = seeded_database(&[TWO_CLUBS]).await;
+        let logins = Logins::default();
+
+        assert_eq!(require_admin(&db, &logins, None).await, Ok(()));
+
+        register(&db, "admin", "password1").await.unwrap();
+        let owner = register(&db, "owner", "password2").await.unwrap();
+        give_role(&db, owner.id, ClubRole::Owner).await;
+
+        let admin_token = log_in(&db, &logins, "admin", "password1").await.unwrap().token;
+        let owner_token = log_in(&db, &logins, "owner", "password2").await.unwrap().token;
+
+        assert_eq!(require_admin(&db, &logins, None).await.unwrap_err().kind(), "Unauthorized");
+        assert_eq!(require_admin(&db, &logins, Some(&owner_token)).await.unwrap_err().kind(), "Forbidden");
+        assert_eq!(require_admin(&db, &logins, Some(&admin_token)).await, Ok(()));
+
+        // After a restore the accounts may be different, so everyone logs in again
+        logins.clear();
+        assert_eq!(require_admin(&db, &logins, Some(&admin_token)).await.unwrap_err().kind(), "Unauthorized");
+    }
+
+    #[tokio::test]
+    async fn test_upgraded_database_is_not_locked_by_old_accounts() {
+        let path = std::env::temp_dir().join(format!("auth_tests_{}_upgrade.db", std::process::id()));
+        std::fs::copy(UNVERSIONED_DB, &path).unwrap();
+        let db = SqlitePool::connect_with(SqliteConnectOptions::new().filename(&path)).await.unwrap();
+        run_migrations(&db).await.unwrap();
+        let logins = Logins::default();
+
+        // Nobody knows the old accounts' passwords, so they don't stop the app running open
+        assert!(!has_organisers(&db).await.unwrap());
+        assert_eq!(require_admin(&db, &logins, None).await, Ok(()));
+        let (player_id, club_id): (i64, i64) = sqlx::query_as("SELECT id, club_id FROM players LIMIT 1")
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert_eq!(check(&db, &logins, None, club_id, ClubRole::Organiser).await, Ok(()));
+        remove_player(&db, None, player_id).await.unwrap();
+
+        let old_account = log_in(&db, &logins, "admin@example.com", "password1").await.unwrap_err();
+        assert_eq!(old_account.kind(), "Unauthorized");
+
+        // Whoever registers first takes over as admin, and from then on logins are needed
+        let admin = register(&db, "organiser", "password1").await.unwrap();
+        assert_eq!(admin.role, AccountRole::Admin);
+        assert_eq!(check(&db, &logins, None, club_id, ClubRole::Organiser).await, Err("Unauthorized"));
+
+        let token = log_in(&db, &logins, "organiser", "password1").await.unwrap().token;
+        assert_eq!(check(&db, &logins, Some(&token), club_id, ClubRole::Organiser).await, Ok(()));
+
+        db.close().await;
+        let _ = std::fs::remove_file(path);
+    }
+}
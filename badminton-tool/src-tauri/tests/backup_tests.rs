@@ -0,0 +1,239 @@
+#[cfg(test)]
+mod tests {
+    use lib::database::*;
+    use lib::services::backup::*;
+    use lib::utils::config::AutoBackup;
+    use sqlx::sqlite::SqliteConnectOptions;
+    use sqlx::{Row, SqlitePool};
+    use std::path::{Path, PathBuf};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("backup_tests_{}_{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    async fn open(path: &Path) -> SqlitePool {
+        let options = SqliteConnectOptions::new().filename(path).create_if_missing(true);
+        let db = SqlitePool::connect_with(options).await.unwrap();
+        run_migrations(&db).await.unwrap();
+        db
+    }
+
+    async fn add_player(db: &SqlitePool, id: i64, first_name: &str) {
+        sqlx::query(
+            "INSERT INTO players (id, first_name, last_name, email, gender, club_id, skill_level)
+             VALUES (?, ?, 'Test', ?, 'Female', 1, 10)"
+        )
+        .bind(id)
+        .bind(first_name)
+        .bind(format!("{}@example.com", first_name))
+        .execute(db)
+        .await
+        .unwrap();
+    }
+
+    async fn player_names(db: &SqlitePool) -> Vec<String> {
+        sqlx::query("SELECT first_name FROM players ORDER BY id")
+            .fetch_all(db)
+            .await
+            .unwrap()
+            .iter()
+            .map(|row| row.get("first_name"))
+            .collect()
+    }
+
+    async fn seeded(dir: &Path) -> SqlitePool {
+        let db = open(&dir.join("app_data.db")).await;
+        sqlx::query("INSERT INTO clubs (id, name) VALUES (1, 'Shuttlers')")
+            .execute(&db)
+            .await
+            .unwrap();
+        add_player(&db, 1, "Ann").await;
+        add_player(&db, 2, "Bob").await;
+        db
+    }
+
+    #[tokio::test]
+    async fn test_restore_brings_back_the_backed_up_data() {
+        let dir = temp_dir("round_trip");
+        let db = seeded(&dir).await;
+        let backup_path = dir.join("saved").join("season.db");
+
+        backup_to(&db, &backup_path).await.unwrap();
+        assert!(backup_to(&db, &backup_path).await.is_err(), "an existing backup is never overwritten");
+
+        sqlx::query("DELETE FROM players WHERE id = 1").execute(&db).await.unwrap();
+        add_player(&db, 3, "Cat").await;
+        assert_eq!(player_names(&db).await, ["Bob", "Cat"]);
+
+        restore_from(&db, &backup_path).await.unwrap();
+        assert_eq!(player_names(&db).await, ["Ann", "Bob"]);
+
+        db.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_restore_waits_for_queries_already_running() {
+        let dir = temp_dir("busy");
+        let db = seeded(&dir).await;
+        let backup_path = dir.join("season.db");
+        backup_to(&db, &backup_path).await.unwrap();
+
+        let mut conn = db.acquire().await.unwrap();
+        sqlx::query("BEGIN").execute(&mut *conn).await.unwrap();
+        sqlx::query("DELETE FROM players WHERE id = 1").execute(&mut *conn).await.unwrap();
+
+        let restore = tokio::spawn({
+            let db = db.clone();
+            async move { restore_from(&db, &backup_path).await }
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        assert!(!restore.is_finished(), "the restore starts once the transaction is over");
+
+        sqlx::query("COMMIT").execute(&mut *conn).await.unwrap();
+        drop(conn);
+
+        restore.await.unwrap().unwrap();
+        assert_eq!(player_names(&db).await, ["Ann", "Bob"]);
+
+        db.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_restore_upgrades_older_backups() {
+        let dir = temp_dir("older");
+        let db = seeded(&dir).await;
+
+        // The database the app shipped with, from before migrations were versioned
+        let older_path = dir.join("older.db");
+        std::fs::copy(concat!(env!("CARGO_MANIFEST_DIR"), "/../data/app_data.db"), &older_path).unwrap();
+
+        restore_from(&db, &older_path).await.unwrap();
+
+        let counts = sqlx::query(
+            "SELECT (SELECT COUNT(*) FROM players) as players, (SELECT COUNT(*) FROM club_memberships) as memberships"
+        )
+        .fetch_one(&db)
+        .await
+        .unwrap();
+        assert!(counts.get::<i64, _>("players") > 0);
+        assert_eq!(counts.get::<i64, _>("players"), counts.get::<i64, _>("memberships"));
+        assert_eq!(schema_version(&db).await.unwrap(), latest_version());
+
+        db.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_restore_refuses_unknown_files() {
+        let dir = temp_dir("refuse");
+        let db = seeded(&dir).await;
+
+        let not_a_database = dir.join("notes.db");
+        std::fs::write(&not_a_database, "not a database").unwrap();
+        assert_eq!(restore_from(&db, &not_a_database).await.unwrap_err().field(), Some("path"));
+
+        let missing = dir.join("missing.db");
+        assert_eq!(restore_from(&db, &missing).await.unwrap_err().kind(), "NotFound");
+
+        // A backup from a newer release
+        let newer_path = dir.join("newer.db");
+        let newer = open(&newer_path).await;
+        sqlx::query("INSERT INTO schema_migrations (version, description) VALUES (?, 'From the future')")
+            .bind(latest_version() + 1)
+            .execute(&newer)
+            .await
+            .unwrap();
+        newer.close().await;
+
+        assert_eq!(restore_from(&db, &newer_path).await.unwrap_err().kind(), "Validation");
+        assert_eq!(player_names(&db).await, ["Ann", "Bob"]);
+
+        db.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_restore_doesnt_depend_on_table_order() {
+        let dir = temp_dir("table_order");
+        let db = seeded(&dir).await;
+        sqlx::query("INSERT INTO club_memberships (player_id, club_id) VALUES (1, 1), (2, 1)")
+            .execute(&db)
+            .await
+            .unwrap();
+        let backup_path = dir.join("season.db");
+        backup_to(&db, &backup_path).await.unwrap();
+
+        // Rebuilding clubs puts it after the tables that refer to it, so it's
+        // cleared after they have been copied
+        let create: String = sqlx::query_scalar("SELECT sql FROM sqlite_master WHERE name = 'clubs'")
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        sqlx::raw_sql(&format!(
+            "PRAGMA foreign_keys = OFF;
+             {};
+             INSERT INTO clubs_rebuilt SELECT * FROM clubs;
+             DROP TABLE clubs;
+             ALTER TABLE clubs_rebuilt RENAME TO clubs;
+             PRAGMA foreign_keys = ON;",
+            create.replacen("clubs", "clubs_rebuilt", 1)
+        ))
+        .execute(&db)
+        .await
+        .unwrap();
+
+        restore_from(&db, &backup_path).await.unwrap();
+
+        assert_eq!(player_names(&db).await, ["Ann", "Bob"]);
+        let memberships: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM club_memberships").fetch_one(&db).await.unwrap();
+        assert_eq!(memberships, 2);
+
+        db.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_restore_refuses_backups_with_broken_references() {
+        let dir = temp_dir("broken_references");
+        let db = seeded(&dir).await;
+        let backup_path = dir.join("season.db");
+        backup_to(&db, &backup_path).await.unwrap();
+
+        // A membership of a player the backup doesn't have
+        let backup = open(&backup_path).await;
+        sqlx::raw_sql("PRAGMA foreign_keys = OFF; INSERT INTO club_memberships (player_id, club_id) VALUES (99, 1);")
+            .execute(&backup)
+            .await
+            .unwrap();
+        backup.close().await;
+
+        sqlx::query("DELETE FROM players WHERE id = 2").execute(&db).await.unwrap();
+        assert_eq!(restore_from(&db, &backup_path).await.unwrap_err().field(), Some("path"));
+        assert_eq!(player_names(&db).await, ["Ann"]);
+
+        db.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_auto_backups_keep_the_newest() {
+        let dir = temp_dir("rotate");
+        let db = seeded(&dir).await;
+        let settings = AutoBackup { directory: dir.join("backups"), keep: 2 };
+
+        // Older backups from earlier sessions
+        std::fs::create_dir_all(&settings.directory).unwrap();
+        for name in ["backup-20240101-190000-session-1.db", "backup-20240108-190000-session-2.db"] {
+            std::fs::write(settings.directory.join(name), "").unwrap();
+        }
+        std::fs::write(settings.directory.join("keep-me.db"), "").unwrap();
+
+        let newest = auto_backup(&db, &settings, 3).await.unwrap();
+
+        let mut backups = list_auto_backups(&settings.directory).unwrap();
+        backups.sort();
+        assert_eq!(backups, [settings.directory.join("backup-20240108-190000-session-2.db"), newest]);
+        assert!(settings.directory.join("keep-me.db").exists());
+
+        db.close().await;
+    }
+}
@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+    use lib::commands::games_maker::*;
+    use lib::models::*;
+    use lib::services::board::*;
+    use sqlx::SqlitePool;
+    use std::collections::HashSet;
+    use std::net::SocketAddr;
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+    use tokio_tungstenite::tungstenite::Message;
+    use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+    fn sample_round(player_count: i64, courts: usize, seed: u64) -> GamesRound {
+        let players = (1..=player_count)
+            .map(|id| InGamePlayer {
+                id,
+                first_name: format!("First{}", id),
+                last_name: format!("Last{}", id),
+                skill_level: id as i32,
+                gender: Gender::Male,
+                sit_out_count: 0,
+                rating: None,
+            })
+            .collect();
+        generate_round(players, courts, &HashSet::new(), &PairingHistory::default(), &GamesOptions::default(), seed)
+    }
+
+    async fn unused_database() -> SqlitePool {
+        SqlitePool::connect("sqlite::memory:").await.unwrap()
+    }
+
+    async fn http_get(address: SocketAddr, path: &str) -> serde_json::Value {
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        let request = format!("GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n", path, address);
+        stream.write_all(request.as_bytes()).await.unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        serde_json::from_str(body).unwrap()
+    }
+
+    async fn next_board(socket: &mut WebSocketStream<MaybeTlsStream<TcpStream>>) -> serde_json::Value {
+        match socket.next().await {
+            Some(Ok(Message::Text(text))) => serde_json::from_str(&text).unwrap(),
+            other => panic!("Expected the board, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_board_server_serves_json_and_pushes_new_rounds() {
+        let board = Arc::new(CourtBoard::default());
+        let address = start_server(unused_database().await, board.clone(), "127.0.0.1:0").await.unwrap();
+        assert_eq!(board.server_address(), Some(address));
+
+        let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", address)).await.unwrap();
+        assert!(next_board(&mut socket).await["round"].is_null());
+
+        board.show_round(1, sample_round(10, 2, 7));
+        let pushed = next_board(&mut socket).await;
+        assert_eq!(pushed["session_id"], 1);
+        assert_eq!(pushed["round"]["games"].as_array().unwrap().len(), 2);
+
+        assert_eq!(http_get(address, "/api/round").await, pushed["round"]);
+        assert_eq!(http_get(address, "/api/sit-outs").await.as_array().unwrap().len(), 2);
+
+        board.set_timer(600, false);
+        assert_eq!(next_board(&mut socket).await["timer"]["remaining_seconds"], 600);
+        assert_eq!(http_get(address, "/api/timer").await, serde_json::json!({ "remaining_seconds": 600, "running": false }));
+
+        // Stopping the server disconnects the phones
+        stop_server(&board).await;
+        assert_eq!(board.server_address(), None);
+        assert!(matches!(socket.next().await, Some(Ok(Message::Close(_))) | None));
+        assert!(TcpStream::connect(address).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_start_server_checks_the_address() {
+        let board = Arc::new(CourtBoard::default());
+
+        let error = start_server(unused_database().await, board.clone(), "hall wifi").await.unwrap_err();
+        assert_eq!(error.field(), Some("bind_address"));
+
+        start_server(unused_database().await, board.clone(), "127.0.0.1:0").await.unwrap();
+        let error = start_server(unused_database().await, board.clone(), "127.0.0.1:0").await.unwrap_err();
+        assert_eq!(error.kind(), "Conflict");
+
+        stop_server(&board).await;
+        stop_server(&board).await;
+    }
+
+    #[test]
+    fn test_refilled_courts_keep_the_other_games() {
+        let board = CourtBoard::default();
+        board.show_round(1, sample_round(8, 2, 3));
+        let court_two = serde_json::to_value(&board.snapshot().round.unwrap().games[1]).unwrap();
+
+        let mut refill = sample_round(6, 1, 4);
+        refill.games[0].court = 1;
+        board.show_courts(1, refill);
+
+        let round = board.snapshot().round.unwrap();
+        assert_eq!(round.games.iter().map(|game| game.court).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(serde_json::to_value(&round.games[1]).unwrap(), court_two);
+        assert_eq!(round.sitting_out.len(), 2);
+
+        // Ending another session leaves the board alone
+        board.clear_session(2);
+        assert!(board.snapshot().round.is_some());
+        board.clear_session(1);
+        assert!(board.snapshot().round.is_none());
+    }
+
+    #[test]
+    fn test_lobby_rounds_replace_the_session_on_the_board() {
+        let board = CourtBoard::default();
+        board.show_round(1, sample_round(8, 2, 3));
+
+        board.show_games(draw_games(Vec::new(), 1, None, None, Some(5)));
+        let snapshot = board.snapshot();
+        assert_eq!(snapshot.session_id, None);
+        assert!(snapshot.round.is_some_and(|round| round.games.is_empty()));
+
+        // The session ending doesn't take the lobby's round down
+        board.clear_session(1);
+        assert!(board.snapshot().round.is_some());
+    }
+}
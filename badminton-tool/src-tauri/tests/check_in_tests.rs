@@ -0,0 +1,153 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::*;
+    use lib::services::board::*;
+    use lib::services::check_in::*;
+    use sqlx::{Row, SqlitePool};
+    use std::net::SocketAddr;
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    /// Session 1 is running at Ann and Bob's club, not Cat's.
+    async fn session_database() -> SqlitePool {
+        seeded_database(&[TWO_CLUBS, "INSERT INTO sessions (id, club_id, court_count) VALUES (1, 1, 2)"]).await
+    }
+
+    /// Sends a request and returns the status code and JSON body of the response.
+    async fn request(address: SocketAddr, method: &str, path: &str, body: &str) -> (u16, serde_json::Value) {
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        let request = format!(
+            "{} {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            method,
+            path,
+            address,
+            body.len(),
+            body
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        let status = response[9..12].parse().unwrap();
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    #[test]
+    fn test_pins_are_four_to_six_digits() {
+        assert!(validate_pin("1234").is_ok());
+        assert!(validate_pin("123456").is_ok());
+        assert_eq!(validate_pin("123").unwrap_err().field(), Some("pin"));
+        assert_eq!(validate_pin("1234567").unwrap_err().field(), Some("pin"));
+        assert_eq!(validate_pin("12a4").unwrap_err().field(), Some("pin"));
+    }
+
+    #[tokio::test]
+    async fn test_players_check_themselves_in_with_their_pin() {
+        let db = session_database().await;
+        set_pin(&db, 1, Some("2468")).await.unwrap();
+        set_pin(&db, 3, Some("1357")).await.unwrap();
+        assert_eq!(set_pin(&db, 99, Some("1111")).await.unwrap_err().kind(), "NotFound");
+
+        // The hash is stored, never the PIN
+        let hash: String = sqlx::query("SELECT check_in_pin_hash FROM players WHERE id = 1")
+            .fetch_one(&db)
+            .await
+            .unwrap()
+            .get("check_in_pin_hash");
+        assert!(hash.starts_with("$2"));
+
+        let board = Arc::new(CourtBoard::default());
+        let address = start_server(db.clone(), board.clone(), "127.0.0.1:0").await.unwrap();
+        let path = "/api/sessions/1/check-in";
+
+        // Only this club's members with a PIN are listed
+        let (status, players) = request(address, "GET", path, "").await;
+        assert_eq!(status, 200);
+        assert_eq!(players, serde_json::json!([{ "id": 1, "first_name": "Ann", "last_name": "Ash", "checked_in": false }]));
+
+        let (status, error) = request(address, "POST", path, r#"{ "player_id": 1, "pin": "1111" }"#).await;
+        assert_eq!((status, error["field"].as_str()), (422, Some("pin")));
+
+        let (status, error) = request(address, "POST", path, r#"{ "player_id": 2, "pin": "1111" }"#).await;
+        assert_eq!((status, error["field"].as_str()), (422, Some("pin")));
+
+        let (status, error) = request(address, "POST", path, r#"{ "player_id": 3, "pin": "1357" }"#).await;
+        assert_eq!((status, error["kind"].as_str()), (404, Some("NotFound")));
+
+        let (status, player) = request(address, "POST", path, r#"{ "player_id": 1, "pin": "2468" }"#).await;
+        assert_eq!((status, player["player_id"].as_i64()), (200, Some(1)));
+        assert_eq!(request(address, "GET", path, "").await.1[0]["checked_in"], true);
+
+        let (status, _) = request(address, "POST", path, r#"{ "player_id": 1, "pin": "2468" }"#).await;
+        assert_eq!(status, 409);
+
+        sqlx::query("UPDATE sessions SET ended_at = CURRENT_TIMESTAMP WHERE id = 1").execute(&db).await.unwrap();
+        assert_eq!(request(address, "GET", path, "").await.0, 409);
+        assert_eq!(request(address, "GET", "/api/sessions/7/check-in", "").await.0, 404);
+
+        stop_server(&board).await;
+    }
+
+    #[tokio::test]
+    async fn test_wrong_pins_lock_self_check_in() {
+        let db = session_database().await;
+        set_pin(&db, 2, Some("8642")).await.unwrap();
+        let attempts = PinAttempts::default();
+        let attempt = |pin: &str| lib::models::SelfCheckInRequest { player_id: 2, pin: pin.to_string() };
+
+        for _ in 0..5 {
+            let error = self_check_in(&db, &attempts, 1, &attempt("0000")).await.unwrap_err();
+            assert_eq!(error.to_string(), "That PIN isn't right");
+        }
+
+        // Even the right PIN is refused while locked
+        let error = self_check_in(&db, &attempts, 1, &attempt("8642")).await.unwrap_err();
+        assert!(error.to_string().starts_with("Too many wrong PINs"));
+
+        let other_attempts = PinAttempts::default();
+        assert!(self_check_in(&db, &other_attempts, 1, &attempt("8642")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_guesses_sent_together_share_the_limit() {
+        let db = session_database().await;
+        set_pin(&db, 2, Some("8642")).await.unwrap();
+        let attempts = PinAttempts::default();
+        let attempt = lib::models::SelfCheckInRequest { player_id: 2, pin: "0000".to_string() };
+
+        let guesses = (0..8).map(|_| self_check_in(&db, &attempts, 1, &attempt));
+        let errors: Vec<String> = futures_util::future::join_all(guesses)
+            .await
+            .into_iter()
+            .map(|result| result.unwrap_err().to_string())
+            .collect();
+
+        assert_eq!(errors.iter().filter(|e| *e == "That PIN isn't right").count(), 5);
+        assert_eq!(errors.iter().filter(|e| e.starts_with("Too many wrong PINs")).count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_restarting_the_server_keeps_the_lockout() {
+        let db = session_database().await;
+        set_pin(&db, 2, Some("8642")).await.unwrap();
+        let board = Arc::new(CourtBoard::default());
+        let path = "/api/sessions/1/check-in";
+
+        let address = start_server(db.clone(), board.clone(), "127.0.0.1:0").await.unwrap();
+        for _ in 0..5 {
+            request(address, "POST", path, r#"{ "player_id": 2, "pin": "0000" }"#).await;
+        }
+        stop_server(&board).await;
+
+        let address = start_server(db.clone(), board.clone(), "127.0.0.1:0").await.unwrap();
+        let (status, error) = request(address, "POST", path, r#"{ "player_id": 2, "pin": "8642" }"#).await;
+        assert_eq!(status, 422);
+        assert!(error["message"].as_str().unwrap().starts_with("Too many wrong PINs"));
+        stop_server(&board).await;
+    }
+}
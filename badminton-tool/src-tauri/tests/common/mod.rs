@@ -0,0 +1,112 @@
+//! Databases shared by the integration tests. Each test binary uses only some
+//! of these.
+#![allow(dead_code)]
+
+use lib::database::run_migrations;
+use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::SqlitePool;
+
+/// Ann and Bob belong to club 1, Cat to club 2.
+pub const TWO_CLUBS: &str = r#"
+    INSERT INTO clubs (id, name) VALUES (1, 'Shuttlers'), (2, 'Other club');
+    INSERT INTO players (id, first_name, last_name, email, gender, club_id, skill_level) VALUES
+        (1, 'Ann', 'Ash', 'ann@example.com', 'Female', 1, 10),
+        (2, 'Bob', 'Birch', 'bob@example.com', 'Male', 1, 12),
+        (3, 'Cat', 'Cedar', 'cat@example.com', 'Female', 2, 14);
+    INSERT INTO club_memberships (player_id, club_id) VALUES (1, 1), (2, 1), (3, 2);
+"#;
+
+/// A club of five with one finished session: a scored game and a sit-out. Dan's
+/// home club is club 2 and Eve has left club 1.
+pub const FINISHED_SESSION: &str = r#"
+    INSERT INTO clubs (id, name, description) VALUES (1, 'Shuttlers', 'Tuesday nights'), (2, 'Other club', NULL);
+    INSERT INTO players (id, first_name, last_name, email, gender, club_id, skill_level, rating, rating_deviation) VALUES
+        (1, 'Ann', 'Ash', 'ann@example.com', 'Female', 1, 10, 1010, 300),
+        (2, 'Bob', 'Birch', 'bob@example.com', 'Male', 1, 12, 1010, 300),
+        (3, 'Cat', 'Cedar', 'cat@example.com', 'Female', 1, 14, 990, 300),
+        (4, 'Dan', 'Dogwood', 'dan@example.com', 'Male', 2, 16, 990, 300),
+        (5, 'Eve', 'Elm', 'eve@example.com', 'Female', 1, 18, NULL, NULL);
+    INSERT INTO club_memberships (player_id, club_id, role, status, joined_at) VALUES
+        (1, 1, 'Committee', 'Active', '2024-01-02 19:00:00'),
+        (2, 1, 'Member', 'Active', '2024-01-02 19:00:00'),
+        (3, 1, 'Coach', 'Active', '2024-01-02 19:00:00'),
+        (5, 1, 'Member', 'Inactive', '2024-01-02 19:00:00'),
+        (4, 2, 'Member', 'Active', '2024-01-02 19:00:00');
+    INSERT INTO sessions (id, club_id, court_count, started_at, ended_at) VALUES (1, 1, 1, '2024-01-09 19:00:00', '2024-01-09 21:00:00');
+    INSERT INTO session_players (session_id, player_id, sit_out_count, games_played, last_sat_out_round, checked_in_at) VALUES
+        (1, 1, 0, 1, NULL, '2024-01-09 19:00:00'),
+        (1, 2, 0, 1, NULL, '2024-01-09 19:00:00'),
+        (1, 3, 0, 1, NULL, '2024-01-09 19:00:00'),
+        (1, 4, 0, 1, NULL, '2024-01-09 19:00:00'),
+        (1, 5, 1, 0, 1, '2024-01-09 19:00:00');
+    INSERT INTO rounds (id, session_id, round_number, seed, created_at) VALUES (1, 1, 1, 42, '2024-01-09 19:01:00');
+    INSERT INTO round_games (id, round_id, court, started_at, finished_at) VALUES (1, 1, 1, '2024-01-09 19:01:00', '2024-01-09 19:20:00');
+    INSERT INTO round_game_players (game_id, player_id, position, team) VALUES (1, 1, 0, 'A'), (1, 2, 1, 'A'), (1, 3, 2, 'B'), (1, 4, 3, 'B');
+    INSERT INTO round_sit_outs (round_id, player_id) VALUES (1, 5);
+    INSERT INTO game_results (game_id, winner, recorded_at) VALUES (1, 'A', '2024-01-09 19:20:00');
+    INSERT INTO game_result_sets (game_id, set_number, team_a_score, team_b_score) VALUES (1, 1, 21, 17);
+    INSERT INTO rating_history (player_id, game_id, rating_before, deviation_before, rating_after, deviation_after) VALUES
+        (1, 1, 1000, 350, 1010, 300), (2, 1, 1000, 350, 1010, 300), (3, 1, 1000, 350, 990, 300), (4, 1, 1000, 350, 990, 300);
+"#;
+
+/// Two finished club nights at club 1, a month apart, each one court of two
+/// rounds. Fay first comes on the second night. Game 4 has no result.
+pub const TWO_NIGHTS: &str = r#"
+    INSERT INTO clubs (id, name) VALUES (1, 'Shuttlers'), (2, 'Other club');
+    INSERT INTO players (id, first_name, last_name, email, gender, club_id, skill_level) VALUES
+        (1, 'Ann', 'Ash', 'ann@example.com', 'Female', 1, 10),
+        (2, 'Bob', 'Birch', 'bob@example.com', 'Male', 1, 10),
+        (3, 'Cat', 'Cedar', 'cat@example.com', 'Female', 1, 10),
+        (4, 'Dan', 'Dogwood', 'dan@example.com', 'Male', 1, 10),
+        (5, 'Eve', 'Elm', 'eve@example.com', 'Female', 1, 10),
+        (6, 'Fay', 'Fir', 'fay@example.com', 'Female', 1, 10);
+    INSERT INTO club_memberships (player_id, club_id) VALUES (1, 1), (2, 1), (3, 1), (4, 1), (5, 1), (6, 1);
+    INSERT INTO sessions (id, club_id, court_count, started_at, ended_at) VALUES
+        (1, 1, 1, '2024-01-09 19:00:00', '2024-01-09 21:00:00'),
+        (2, 1, 1, '2024-02-06 19:00:00', '2024-02-06 20:00:00'),
+        (3, 2, 2, '2024-02-06 19:00:00', '2024-02-06 21:00:00');
+    INSERT INTO session_players (session_id, player_id, checked_in_at) VALUES
+        (1, 1, '2024-01-09 19:00:00'), (1, 2, '2024-01-09 19:00:00'), (1, 3, '2024-01-09 19:00:00'),
+        (1, 4, '2024-01-09 19:00:00'), (1, 5, '2024-01-09 19:00:00'),
+        (2, 1, '2024-02-06 19:00:00'), (2, 2, '2024-02-06 19:00:00'), (2, 3, '2024-02-06 19:00:00'),
+        (2, 4, '2024-02-06 19:00:00'), (2, 5, '2024-02-06 19:00:00'), (2, 6, '2024-02-06 19:00:00');
+    INSERT INTO rounds (id, session_id, round_number, seed, created_at) VALUES
+        (1, 1, 1, 1, '2024-01-09 19:00:00'), (2, 1, 2, 2, '2024-01-09 19:20:00'),
+        (3, 2, 1, 3, '2024-02-06 19:00:00'), (4, 2, 2, 4, '2024-02-06 19:30:00');
+    INSERT INTO round_games (id, round_id, court, started_at, finished_at) VALUES
+        (1, 1, 1, '2024-01-09 19:00:00', '2024-01-09 19:20:00'),
+        (2, 2, 1, '2024-01-09 19:20:00', '2024-01-09 19:50:00'),
+        (3, 3, 1, '2024-02-06 19:00:00', '2024-02-06 19:30:00'),
+        (4, 4, 1, '2024-02-06 19:30:00', '2024-02-06 19:45:00');
+    INSERT INTO round_game_players (game_id, player_id, position, team) VALUES
+        (1, 1, 0, 'A'), (1, 2, 1, 'A'), (1, 3, 2, 'B'), (1, 4, 3, 'B'),
+        (2, 1, 0, 'A'), (2, 3, 1, 'A'), (2, 2, 2, 'B'), (2, 5, 3, 'B'),
+        (3, 1, 0, 'A'), (3, 2, 1, 'A'), (3, 4, 2, 'B'), (3, 5, 3, 'B'),
+        (4, 1, 0, 'A'), (4, 4, 1, 'A'), (4, 2, 2, 'B'), (4, 3, 3, 'B');
+    INSERT INTO round_sit_outs (round_id, player_id) VALUES (1, 5), (2, 4), (3, 3), (3, 6), (4, 5), (4, 6);
+    INSERT INTO game_results (game_id, winner, recorded_at) VALUES
+        (1, 'A', '2024-01-09 19:20:00'), (2, 'B', '2024-01-09 19:50:00'), (3, 'A', '2024-02-06 19:30:00');
+    INSERT INTO game_result_sets (game_id, set_number, team_a_score, team_b_score) VALUES
+        (1, 1, 21, 17), (2, 1, 15, 21), (2, 2, 19, 21), (3, 1, 21, 10), (3, 2, 18, 21), (3, 3, 21, 19);
+    INSERT INTO rating_history (player_id, game_id, rating_before, deviation_before, rating_after, deviation_after, recorded_at) VALUES
+        (1, 1, 1000, 350, 1010, 300, '2024-01-09 19:20:00'),
+        (1, 2, 1010, 300, 1000, 280, '2024-01-09 19:50:00'),
+        (1, 3, 1000, 280, 1012, 260, '2024-02-06 19:30:00');
+"#;
+
+/// An in-memory database at the latest schema version. A single connection
+/// keeps every query on the same in-memory database.
+pub async fn empty_database() -> SqlitePool {
+    let db = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+    run_migrations(&db).await.unwrap();
+    db
+}
+
+/// An in-memory database holding `seeds`, run in order.
+pub async fn seeded_database(seeds: &[&str]) -> SqlitePool {
+    let db = empty_database().await;
+    for seed in seeds {
+        sqlx::raw_sql(seed).execute(&db).await.unwrap();
+    }
+    db
+}
@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod tests {
+    use lib::database::copy_legacy_database;
+    use lib::utils::config::*;
+    use std::path::{Path, PathBuf};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("config_tests_{}_{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_load_config_defaults_without_a_file() {
+        let dir = temp_dir("no_file");
+        assert_eq!(load_config(&dir), Ok(Config::default()));
+
+        std::fs::write(dir.join(CONFIG_FILE_NAME), "database_path = \"club.db\"\n").unwrap();
+        assert_eq!(load_config(&dir).unwrap().database_path, Some(PathBuf::from("club.db")));
+
+        std::fs::write(dir.join(CONFIG_FILE_NAME), "database_path = 3\n").unwrap();
+        assert!(load_config(&dir).is_err());
+    }
+
+    // Every override is checked in one test so nothing else sees the environment variable
+    #[test]
+    fn test_database_path_overrides() {
+        let app_data_dir = Path::new("/app-data");
+        let config_dir = temp_dir("overrides");
+
+        std::env::remove_var(DATABASE_PATH_ENV);
+        assert_eq!(get_database_path(app_data_dir, &config_dir), Ok(app_data_dir.join(DATABASE_FILE_NAME)));
+
+        std::fs::write(config_dir.join(CONFIG_FILE_NAME), "database_path = \"club.db\"\n").unwrap();
+        assert_eq!(get_database_path(app_data_dir, &config_dir), Ok(config_dir.join("club.db")));
+
+        std::env::set_var(DATABASE_PATH_ENV, "/elsewhere/club.db");
+        assert_eq!(get_database_path(app_data_dir, &config_dir), Ok(PathBuf::from("/elsewhere/club.db")));
+        std::env::remove_var(DATABASE_PATH_ENV);
+    }
+
+    #[test]
+    fn test_legacy_database_is_copied_once() {
+        let dir = temp_dir("legacy");
+        let legacy_path = dir.join("data").join(DATABASE_FILE_NAME);
+        let db_path = dir.join("app-data").join(DATABASE_FILE_NAME);
+
+        // Nothing to copy
+        assert!(!copy_legacy_database(&legacy_path, &db_path).unwrap());
+
+        std::fs::create_dir_all(legacy_path.parent().unwrap()).unwrap();
+        std::fs::write(&legacy_path, "old").unwrap();
+        assert!(copy_legacy_database(&legacy_path, &db_path).unwrap());
+        assert_eq!(std::fs::read_to_string(&db_path).unwrap(), "old");
+
+        // A database already in place is never overwritten
+        std::fs::write(&legacy_path, "older").unwrap();
+        assert!(!copy_legacy_database(&legacy_path, &db_path).unwrap());
+        assert_eq!(std::fs::read_to_string(&db_path).unwrap(), "old");
+    }
+}
@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::process::{Command, Output};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("courtctl_tests_{}_{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn courtctl(args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_courtctl"))
+            .args(args)
+            .env_remove("BADMINTON_DB_PATH")
+            .output()
+            .unwrap()
+    }
+
+    fn stdout(output: &Output) -> String {
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8(output.stdout.clone()).unwrap()
+    }
+
+    #[test]
+    fn test_round_is_repeatable_with_a_seed() {
+        let dir = temp_dir("round");
+        let players: Vec<String> = (1..=10)
+            .map(|id| {
+                format!(
+                    r#"{{ "id": {}, "first_name": "P{}", "last_name": "Test", "skill_level": {}, "gender": "Male", "sit_out_count": 0 }}"#,
+                    id, id, id * 3
+                )
+            })
+            .collect();
+        let players_file = dir.join("players.json");
+        std::fs::write(&players_file, format!("[{}]", players.join(","))).unwrap();
+
+        let args = ["round", "--players", players_file.to_str().unwrap(), "--courts", "2", "--seed", "7"];
+        let first = stdout(&courtctl(&args));
+        assert_eq!(first, stdout(&courtctl(&args)));
+
+        let round: serde_json::Value = serde_json::from_str(&first).unwrap();
+        assert_eq!(round["seed"], 7);
+        assert_eq!(round["games"].as_array().unwrap().len(), 2);
+        assert_eq!(round["sitting_out"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_players_import_and_clubs_list() {
+        let dir = temp_dir("import");
+        let db_path = dir.join("courtctl.db");
+        let db = db_path.to_str().unwrap();
+
+        // The database has to exist already rather than being made up from a typo
+        let output = courtctl(&["--db", db, "clubs", "list"]);
+        assert!(!output.status.success());
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let options = sqlx::sqlite::SqliteConnectOptions::new().filename(&db_path).create_if_missing(true);
+            let pool = sqlx::SqlitePool::connect_with(options).await.unwrap();
+            lib::database::run_migrations(&pool).await.unwrap();
+            sqlx::query("INSERT INTO clubs (id, name) VALUES (1, 'Shuttlers')")
+                .execute(&pool)
+                .await
+                .unwrap();
+            pool.close().await;
+        });
+
+        let csv_file = dir.join("members.csv");
+        std::fs::write(
+            &csv_file,
+            "First name,Last name,Email,Gender,Skill level\nAnn,Ash,ann@example.com,F,10\nBob,Birch,bob@example.com,M,eleven\n",
+        )
+        .unwrap();
+        let csv = csv_file.to_str().unwrap();
+
+        let report = stdout(&courtctl(&["--db", db, "players", "import", "--club", "1", csv, "--dry-run"]));
+        assert!(report.starts_with("Would create 1, would update 0, 1 rejected"));
+        assert!(report.contains("line 3:"));
+        assert_eq!(stdout(&courtctl(&["--db", db, "clubs", "list"])), "1\tShuttlers\t0 members\n");
+
+        stdout(&courtctl(&["--db", db, "players", "import", "--club", "1", csv]));
+        assert_eq!(stdout(&courtctl(&["--db", db, "clubs", "list"])), "1\tShuttlers\t1 members\n");
+
+        let backup = dir.join("backup.db");
+        stdout(&courtctl(&["--db", db, "backup", backup.to_str().unwrap()]));
+        assert!(backup.is_file());
+    }
+
+    #[test]
+    fn test_unknown_arguments_are_refused() {
+        let output = courtctl(&["clubs", "list"]);
+        assert!(String::from_utf8_lossy(&output.stderr).contains("BADMINTON_DB_PATH"));
+
+        let output = courtctl(&["round", "--players", "x.json", "--courts", "2", "--sead", "7"]);
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown option --sead"));
+
+        assert!(!courtctl(&["teams"]).status.success());
+    }
+}
@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod tests {
+    use lib::models::*;
+    use lib::utils::errors::AppError;
+    use serde_json::json;
+    use sqlx::{Connection, SqliteConnection};
+
+    fn sample_request() -> CreatePlayerRequest {
+        CreatePlayerRequest {
+            first_name: "Ann".to_string(),
+            last_name: "Ash".to_string(),
+            email: "ann@example.com".to_string(),
+            gender: Gender::Female,
+            club_id: 1,
+            skill_level: 10,
+        }
+    }
+
+    #[test]
+    fn test_errors_serialize_with_kind_message_and_field() {
+        let not_found = serde_json::to_value(AppError::not_found("Player not found")).unwrap();
+        assert_eq!(not_found, json!({ "kind": "NotFound", "message": "Player not found", "field": null }));
+
+        let invalid = serde_json::to_value(AppError::validation("email", "Email cannot be empty")).unwrap();
+        assert_eq!(invalid, json!({ "kind": "Validation", "message": "Email cannot be empty", "field": "email" }));
+
+        let database = serde_json::to_value(AppError::Database("disk I/O error".to_string())).unwrap();
+        assert_eq!(database, json!({ "kind": "Database", "message": "Database error: disk I/O error", "field": null }));
+    }
+
+    #[test]
+    fn test_player_validation_names_the_field() {
+        assert_eq!(sample_request().validate(), Ok(()));
+
+        let request = CreatePlayerRequest { skill_level: 51, ..sample_request() };
+        assert_eq!(request.validate().unwrap_err().field(), Some("skill_level"));
+
+        let request = CreatePlayerRequest { last_name: "  ".to_string(), ..sample_request() };
+        assert_eq!(request.validate().unwrap_err().field(), Some("last_name"));
+    }
+
+    #[tokio::test]
+    async fn test_unique_violation_is_a_conflict_on_the_column() {
+        let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE players (email TEXT UNIQUE NOT NULL)").execute(&mut conn).await.unwrap();
+        sqlx::query("INSERT INTO players (email) VALUES ('ann@example.com')").execute(&mut conn).await.unwrap();
+
+        let duplicate = sqlx::query("INSERT INTO players (email) VALUES ('ann@example.com')")
+            .execute(&mut conn)
+            .await
+            .unwrap_err();
+
+        let error = AppError::from(duplicate);
+        assert_eq!(error.kind(), "Conflict");
+        assert_eq!(error.field(), Some("email"));
+    }
+}
@@ -0,0 +1,123 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::*;
+    use lib::commands::export::*;
+    use lib::models::*;
+    use lib::services::auth::register;
+
+    #[tokio::test]
+    async fn test_club_export_round_trips_through_json() {
+        let source = seeded_database(&[FINISHED_SESSION]).await;
+        let export = build_club_export(&source, 1).await.unwrap();
+
+        // The non-member who came to the session is included, without a membership
+        assert_eq!(export.players.len(), 5);
+        let dan = export.players.iter().find(|p| p.first_name == "Dan").unwrap();
+        assert!(dan.membership.is_none());
+        let game = &export.sessions[0].rounds[0].games[0];
+        assert_eq!(game.result.as_ref().unwrap().rating_changes.len(), 4);
+
+        let json = serde_json::to_string(&export).unwrap();
+        let target = empty_database().await;
+        let club = import_club_export(&target, &parse_club_export(&json).unwrap(), None).await.unwrap();
+        assert_eq!(club.member_count, 3);
+
+        let mut round_trip = build_club_export(&target, club.id).await.unwrap();
+        round_trip.exported_at = export.exported_at.clone();
+        assert_eq!(round_trip, export);
+
+        // Importing into a database that already has the club is refused as a whole
+        let error = import_club_export(&source, &export, None).await.unwrap_err();
+        assert_eq!(error.kind(), "Conflict");
+    }
+
+    #[tokio::test]
+    async fn test_players_already_known_keep_their_ratings() {
+        let source = seeded_database(&[FINISHED_SESSION]).await;
+        let export = build_club_export(&source, 1).await.unwrap();
+
+        let target = seeded_database(&[
+            "INSERT INTO clubs (id, name) VALUES (1, 'Elsewhere')",
+            "INSERT INTO players (id, first_name, last_name, email, gender, club_id, skill_level, rating, rating_deviation)
+             VALUES (1, 'Ann', 'Ash', 'ANN@example.com', 'Female', 1, 30, 1500, 80)",
+        ])
+        .await;
+        import_club_export(&target, &export, None).await.unwrap();
+
+        let ann: (f64, f64) = sqlx::query_as("SELECT rating, rating_deviation FROM players WHERE id = 1")
+            .fetch_one(&target)
+            .await
+            .unwrap();
+        assert_eq!(ann, (1500.0, 80.0));
+
+        // The game's history is only brought across for the players the import created
+        let history: Vec<(String,)> = sqlx::query_as(
+            "SELECT p.first_name FROM rating_history rh JOIN players p ON p.id = rh.player_id ORDER BY p.first_name"
+        )
+        .fetch_all(&target)
+        .await
+        .unwrap();
+        assert_eq!(history, [("Bob".to_string(),), ("Cat".to_string(),), ("Dan".to_string(),)]);
+    }
+
+    #[tokio::test]
+    async fn test_whoever_imports_a_club_owns_it() {
+        let source = seeded_database(&[FINISHED_SESSION]).await;
+        let export = build_club_export(&source, 1).await.unwrap();
+
+        let target = empty_database().await;
+        let importer = register(&target, "importer", "password1").await.unwrap();
+        let club = import_club_export(&target, &export, Some(&importer)).await.unwrap();
+
+        let role: String = sqlx::query_scalar("SELECT role FROM club_roles WHERE user_id = ? AND club_id = ?")
+            .bind(importer.id)
+            .bind(club.id)
+            .fetch_one(&target)
+            .await
+            .unwrap();
+        assert_eq!(role, ClubRole::Owner.as_str());
+    }
+
+    #[tokio::test]
+    async fn test_club_export_includes_everyone_named_in_its_rounds() {
+        // Dan played and Eve sat out without an attendance record to show for it
+        let db = seeded_database(&[FINISHED_SESSION, "DELETE FROM session_players WHERE player_id IN (4, 5)"]).await;
+        let export = build_club_export(&db, 1).await.unwrap();
+
+        let number = |first_name: &str| export.players.iter().find(|p| p.first_name == first_name).unwrap().number;
+        let round = &export.sessions[0].rounds[0];
+        assert!(round.games[0].players.iter().any(|p| p.player == number("Dan")));
+        assert_eq!(round.sitting_out, vec![number("Eve")]);
+        assert!(export.players.iter().all(|p| p.number > 0));
+    }
+
+    #[test]
+    fn test_parse_club_export_checks_the_version() {
+        let error = parse_club_export(r#"{ "format_version": 99, "club": "anything" }"#).unwrap_err();
+        assert_eq!(error.field(), Some("format_version"));
+
+        let error = parse_club_export(r#"{ "players": [] }"#).unwrap_err();
+        assert_eq!(error.field(), Some("format_version"));
+
+        let error = parse_club_export(r#"{ "format_version": 1 }"#).unwrap_err();
+        assert_eq!(error.field(), Some("json_text"));
+    }
+
+    #[tokio::test]
+    async fn test_club_export_csv() {
+        let db = seeded_database(&[FINISHED_SESSION]).await;
+        let csv = club_export_to_csv(&build_club_export(&db, 1).await.unwrap()).unwrap();
+
+        let players: Vec<&str> = csv.players.lines().collect();
+        assert_eq!(players[0], "First name,Last name,Email,Gender,Skill level,Rating,Role,Status,Joined");
+        assert_eq!(players[1], "Ann,Ash,ann@example.com,Female,10,1010,Committee,Active,2024-01-02 19:00:00");
+
+        assert_eq!(csv.sessions.lines().nth(1), Some("2024-01-09 19:00:00,2024-01-09 21:00:00,1,5,1,1"));
+        assert_eq!(
+            csv.games.lines().nth(1),
+            Some("2024-01-09 19:00:00,1,1,Ann Ash & Bob Birch,Cat Cedar & Dan Dogwood,21-17,A,2024-01-09 19:01:00,2024-01-09 19:20:00")
+        );
+    }
+}
@@ -1,21 +1,32 @@
 #[cfg(test)]
 mod tests {
-    use super::*;
-    use std::collections::HashMap;
+    use lib::commands::games_maker::*;
+    use lib::models::*;
+    use std::collections::{HashMap, HashSet};
 
-    fn sample_player(id: i64, skill: i32) -> Player {
-        Player {
+    fn sample_player(id: i64, skill: i32) -> InGamePlayer {
+        sample_player_with_gender(id, skill, Gender::Male)
+    }
+
+    fn sample_player_with_gender(id: i64, skill: i32, gender: Gender) -> InGamePlayer {
+        InGamePlayer {
             id,
             first_name: format!("First{}", id),
             last_name: format!("Last{}", id),
-            email: format!("player{}@test.com", id),
-            gender: "Male".to_string(),
-            club_id: 1,
             skill_level: skill,
+            gender,
             sit_out_count: 0,
+            rating: None,
         }
     }
 
+    fn format_options(format: MatchFormat) -> Option<GamesOptions> {
+        Some(GamesOptions {
+            format,
+            ..GamesOptions::default()
+        })
+    }
+
     #[tokio::test]
     async fn test_make_games_exact_players() {
         let players = vec![
@@ -24,7 +35,7 @@ mod tests {
             sample_player(3, 30),
             sample_player(4, 40),
         ];
-        let result = make_games(players, 1, None).await;
+        let result = draw_games(players, 1, None, None, None);
         assert_eq!(result.games.len(), 1);
         assert_eq!(result.sitting_out.len(), 0);
     }
@@ -38,11 +49,33 @@ mod tests {
             sample_player(4, 40),
             sample_player(5, 50),
         ];
-        let result = make_games(players, 1, None).await;
+        let result = draw_games(players, 1, None, None, None);
         assert_eq!(result.games.len(), 1);
         assert_eq!(result.sitting_out.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_make_games_splits_court_into_balanced_pairs() {
+        let players = vec![
+            sample_player(1, 10),
+            sample_player(2, 20),
+            sample_player(3, 30),
+            sample_player(4, 40),
+        ];
+        let result = draw_games(players, 1, None, None, None);
+        let game = &result.games[0];
+
+        let mut team_a: Vec<i64> = game.team_a.iter().map(|p| p.id).collect();
+        let mut team_b: Vec<i64> = game.team_b.iter().map(|p| p.id).collect();
+        team_a.sort();
+        team_b.sort();
+        let mut teams = vec![team_a, team_b];
+        teams.sort();
+
+        assert_eq!(teams, vec![vec![1, 4], vec![2, 3]]);
+        assert_eq!(game.skill_gap, 0);
+    }
+
     #[tokio::test]
     async fn test_make_games_not_enough_players() {
         let players = vec![
@@ -50,8 +83,164 @@ mod tests {
             sample_player(2, 20),
             sample_player(3, 30),
         ];
-        let result = make_games(players, 1, None).await;
+        let result = draw_games(players, 1, None, None, None);
         assert_eq!(result.games.len(), 0);
         assert_eq!(result.sitting_out.len(), 3);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_make_games_strict_mixed_pairs_one_man_one_woman() {
+        let mut players: Vec<InGamePlayer> = (1..=8).map(|id| sample_player(id, 10 + id as i32)).collect();
+        players.extend((9..=12).map(|id| sample_player_with_gender(id, 10 + id as i32, Gender::Female)));
+
+        let result = draw_games(players, 3, None, format_options(MatchFormat::StrictMixed), None);
+
+        // Only four women, so only two courts can run mixed doubles
+        assert_eq!(result.games.len(), 2);
+        assert_eq!(result.sitting_out.len(), 4);
+        assert!(result.sitting_out.iter().all(|p| p.gender == Gender::Male));
+        assert_eq!(result.warnings.len(), 1);
+
+        for game in &result.games {
+            for team in [&game.team_a, &game.team_b] {
+                assert_eq!(team.len(), 2);
+                assert_ne!(team[0].gender, team[1].gender);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_make_games_level_courts_are_single_gender() {
+        let mut players: Vec<InGamePlayer> = (1..=6).map(|id| sample_player(id, 10 + id as i32)).collect();
+        players.extend((7..=12).map(|id| sample_player_with_gender(id, 10 + id as i32, Gender::Female)));
+
+        let result = draw_games(players, 3, None, format_options(MatchFormat::Level), None);
+
+        // Two single-gender courts, and the four players left over share a mixed court
+        assert_eq!(result.games.len(), 3);
+        assert_eq!(result.warnings.len(), 1);
+
+        let single_gender_courts = result
+            .games
+            .iter()
+            .filter(|game| {
+                let first = game.team_a[0].gender;
+                game.players().all(|p| p.gender == first)
+            })
+            .count();
+        assert_eq!(single_gender_courts, 2);
+    }
+
+    #[tokio::test]
+    async fn test_make_games_mixes_singles_and_doubles_courts() {
+        let players = vec![
+            sample_player(1, 10),
+            sample_player(2, 24),
+            sample_player(3, 26),
+            sample_player(4, 30),
+            sample_player(5, 35),
+            sample_player(6, 40),
+            sample_player(7, 45),
+        ];
+        let options = GamesOptions {
+            court_formats: vec![CourtFormat::Singles, CourtFormat::Doubles],
+            ..GamesOptions::default()
+        };
+
+        let result = draw_games(players, 2, None, Some(options), None);
+
+        // Two singles players plus four doubles players leaves one sitting out
+        assert_eq!(result.games.len(), 2);
+        assert_eq!(result.sitting_out.len(), 1);
+
+        let singles = result.games.iter().find(|game| game.court == 1).unwrap();
+        assert_eq!(singles.team_a.len(), 1);
+        assert_eq!(singles.team_b.len(), 1);
+        assert!(singles.skill_gap <= 5, "singles skill gap was {}", singles.skill_gap);
+
+        let doubles = result.games.iter().find(|game| game.court == 2).unwrap();
+        assert_eq!(doubles.players().count(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_make_games_same_seed_reproduces_round() {
+        let players: Vec<InGamePlayer> = (1..=14).map(|id| sample_player(id, 20 + (id as i32 % 3))).collect();
+
+        let first = draw_games(players.clone(), 3, None, None, Some(7));
+        let second = draw_games(players, 3, None, None, Some(7));
+
+        let layout = |round: &GamesRound| {
+            let courts: Vec<(Vec<i64>, Vec<i64>)> = round
+                .games
+                .iter()
+                .map(|game| {
+                    (
+                        game.team_a.iter().map(|p| p.id).collect(),
+                        game.team_b.iter().map(|p| p.id).collect(),
+                    )
+                })
+                .collect();
+            let sitting_out: Vec<i64> = round.sitting_out.iter().map(|p| p.id).collect();
+            (courts, sitting_out)
+        };
+
+        assert_eq!(first.seed, 7);
+        assert_eq!(layout(&first), layout(&second));
+    }
+
+    #[tokio::test]
+    async fn test_make_games_returns_generated_seed() {
+        let players: Vec<InGamePlayer> = (1..=6).map(|id| sample_player(id, 20)).collect();
+
+        let first = draw_games(players.clone(), 1, None, None, None);
+        let replay = draw_games(players, 1, None, None, Some(first.seed));
+
+        let sitting_out = |round: &GamesRound| round.sitting_out.iter().map(|p| p.id).collect::<Vec<_>>();
+        assert_eq!(sitting_out(&first), sitting_out(&replay));
+    }
+
+    #[test]
+    fn test_no_pair_partners_more_than_twice_over_a_session() {
+        let skills = [12, 45, 30, 8, 22, 38, 17, 27, 41, 5, 33, 19, 25, 36, 14, 29];
+        let mut players: Vec<InGamePlayer> = skills
+            .iter()
+            .enumerate()
+            .map(|(i, &skill)| sample_player(i as i64 + 1, skill))
+            .collect();
+
+        let options = GamesOptions::default();
+        let mut history = PairingHistory::default();
+        let mut previous_sit_out_ids = HashSet::new();
+
+        for round_number in 0..10 {
+            let round = generate_round(players.clone(), 3, &previous_sit_out_ids, &history, &options, round_number);
+            assert_eq!(round.games.len(), 3);
+            assert_eq!(round.sitting_out.len(), 4);
+
+            for game in &round.games {
+                history.record_game(game);
+            }
+
+            // Carry the updated sit-out counts into the next round
+            let counts: HashMap<i64, i32> = round.sitting_out.iter().map(|p| (p.id, p.sit_out_count)).collect();
+            for player in &mut players {
+                if let Some(&count) = counts.get(&player.id) {
+                    player.sit_out_count = count;
+                }
+            }
+            previous_sit_out_ids = round.sitting_out.iter().map(|p| p.id).collect();
+        }
+
+        for a in 1..=16 {
+            for b in (a + 1)..=16 {
+                assert!(
+                    history.partner_count(a, b) <= 2,
+                    "players {} and {} partnered {} times",
+                    a,
+                    b,
+                    history.partner_count(a, b)
+                );
+            }
+        }
+    }
+}
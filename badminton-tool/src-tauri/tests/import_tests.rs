@@ -0,0 +1,143 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::*;
+    use lib::commands::import::{import_players, parse_players_csv};
+    use lib::models::*;
+    use sqlx::SqlitePool;
+
+    /// Ann is a member of club 1 and gets a new name and skill level, Dee is new.
+    const ANN_AND_DEE: &str = "First name,Last name,Email,Gender,Skill level\n\
+                               Anna,Ash,ANN@example.com,Female,20\n\
+                               Dee,Dune,dee@example.com,Female,8\n";
+
+    async fn player(db: &SqlitePool, email: &str) -> Option<(i64, String, i32, i64)> {
+        sqlx::query_as("SELECT id, first_name, skill_level, club_id FROM players WHERE email = ?")
+            .bind(email)
+            .fetch_optional(db)
+            .await
+            .unwrap()
+    }
+
+    async fn member_ids(db: &SqlitePool, club_id: i64) -> Vec<i64> {
+        sqlx::query_scalar("SELECT player_id FROM club_memberships WHERE club_id = ? AND status = 'Active' ORDER BY player_id")
+            .bind(club_id)
+            .fetch_all(db)
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_players_csv_finds_columns_by_usual_names() {
+        let csv = "Surname,First Name,E-mail,Sex,Skill\n\
+                   Ash,Ann,ann@example.com,F,12\n\
+                   Birch,Bob,bob@example.com,male,30\n";
+
+        let (rows, rejected) = parse_players_csv(csv, &ColumnMapping::default(), 1).unwrap();
+
+        assert!(rejected.is_empty());
+        assert_eq!(rows.len(), 2);
+        assert_eq!((rows[0].first_name.as_str(), rows[0].last_name.as_str()), ("Ann", "Ash"));
+        assert_eq!(rows[0].gender, Gender::Female);
+        assert_eq!(rows[1].skill_level, 30);
+        assert_eq!(rows[1].line, 3);
+    }
+
+    #[test]
+    fn test_parse_players_csv_uses_the_column_mapping() {
+        let csv = "Player,Family,Contact,Gender,Grade\nAnn,Ash,ann@example.com,Female,12\n";
+        let columns = ColumnMapping {
+            first_name: Some("Player".to_string()),
+            last_name: Some("Family".to_string()),
+            email: Some("Contact".to_string()),
+            gender: None,
+            skill_level: Some("Grade".to_string()),
+        };
+
+        let (rows, _) = parse_players_csv(csv, &columns, 1).unwrap();
+        assert_eq!(rows[0].email, "ann@example.com");
+
+        // Without the mapping the first name column is the first one not found
+        let error = parse_players_csv(csv, &ColumnMapping::default(), 1).unwrap_err();
+        assert_eq!(error.field(), Some("first_name"));
+    }
+
+    #[test]
+    fn test_parse_players_csv_rejects_invalid_rows() {
+        let csv = "First name,Last name,Email,Gender,Skill level\n\
+                   Ann,Ash,ann@example.com,Female,12\n\
+                   Bob,Birch,bob@example.com,Other,12\n\
+                   Cat,Cedar,cat@example.com,Female,60\n\
+                   ,Dogwood,dan@example.com,Male,12\n\
+                   Ann,Again,ANN@example.com,Female,14\n\
+                   ,,,,\n";
+
+        let (rows, rejected) = parse_players_csv(csv, &ColumnMapping::default(), 1).unwrap();
+
+        assert_eq!(rows.len(), 1);
+        let fields: Vec<(u64, Option<&str>)> = rejected.iter().map(|r| (r.line, r.field.as_deref())).collect();
+        assert_eq!(
+            fields,
+            [(3, Some("gender")), (4, Some("skill_level")), (5, Some("first_name")), (6, Some("email"))]
+        );
+        assert!(rejected[3].message.contains("line 2"));
+    }
+
+    #[tokio::test]
+    async fn test_import_creates_new_players_and_updates_members() {
+        let db = seeded_database(&[TWO_CLUBS]).await;
+
+        let report = import_players(&db, None, 1, ANN_AND_DEE, ImportOptions::default()).await.unwrap();
+
+        assert!(!report.dry_run);
+        assert!(report.rejected.is_empty());
+        assert_eq!(report.updated.iter().map(|r| (r.line, r.player_id)).collect::<Vec<_>>(), [(2, Some(1))]);
+        assert_eq!(report.created.iter().map(|r| r.email.as_str()).collect::<Vec<_>>(), ["dee@example.com"]);
+
+        assert_eq!(player(&db, "ann@example.com").await, Some((1, "Anna".to_string(), 20, 1)));
+        let (dee_id, _, skill_level, club_id) = player(&db, "dee@example.com").await.unwrap();
+        assert_eq!((skill_level, club_id), (8, 1));
+        assert_eq!(member_ids(&db, 1).await, [1, 2, dee_id]);
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_players_who_arent_members() {
+        let db = seeded_database(&[TWO_CLUBS]).await;
+        sqlx::query("UPDATE club_memberships SET status = 'Inactive' WHERE player_id = 2").execute(&db).await.unwrap();
+
+        // Cat plays at the other club and Bob has left this one
+        let csv = "First name,Last name,Email,Gender,Skill level\n\
+                   Cat,Cedar,cat@example.com,Female,40\n\
+                   Eve,Elm,eve@example.com,Other,12\n\
+                   Bob,Birch,bob@example.com,Male,40\n";
+        let report = import_players(&db, None, 1, csv, ImportOptions::default()).await.unwrap();
+
+        assert!(report.created.is_empty() && report.updated.is_empty());
+        let rejected: Vec<(u64, Option<&str>)> = report.rejected.iter().map(|r| (r.line, r.field.as_deref())).collect();
+        assert_eq!(rejected, [(2, Some("email")), (3, Some("gender")), (4, Some("email"))]);
+        assert_eq!(report.rejected[0].message, "cat@example.com belongs to a player who isn't a member of this club");
+
+        // Neither was changed or made a member
+        assert_eq!(player(&db, "cat@example.com").await, Some((3, "Cat".to_string(), 14, 2)));
+        assert_eq!(player(&db, "bob@example.com").await, Some((2, "Bob".to_string(), 12, 1)));
+        assert_eq!(member_ids(&db, 1).await, [1]);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_reports_without_saving() {
+        let db = seeded_database(&[TWO_CLUBS]).await;
+        let options = ImportOptions { dry_run: true, ..Default::default() };
+
+        let report = import_players(&db, None, 1, ANN_AND_DEE, options).await.unwrap();
+
+        assert!(report.dry_run);
+        assert_eq!((report.created.len(), report.updated.len()), (1, 1));
+        assert_eq!(player(&db, "ann@example.com").await, Some((1, "Ann".to_string(), 10, 1)));
+        assert_eq!(player(&db, "dee@example.com").await, None);
+        assert_eq!(member_ids(&db, 1).await, [1, 2]);
+
+        let error = import_players(&db, None, 9, ANN_AND_DEE, ImportOptions::default()).await.unwrap_err();
+        assert_eq!(error.kind(), "NotFound");
+    }
+}
@@ -0,0 +1,116 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::*;
+    use lib::commands::clubs::*;
+    use lib::commands::memberships::*;
+    use lib::models::*;
+    use sqlx::SqlitePool;
+
+    const THIRD_CLUB: &str = "INSERT INTO clubs (id, name) VALUES (3, 'Third club')";
+
+    async fn home_club(db: &SqlitePool, player_id: i64) -> i64 {
+        sqlx::query_scalar("SELECT club_id FROM players WHERE id = ?").bind(player_id).fetch_one(db).await.unwrap()
+    }
+
+    /// Club ids with the status of each of a player's memberships.
+    async fn memberships(db: &SqlitePool, player_id: i64) -> Vec<(i64, MembershipStatus)> {
+        let mut memberships: Vec<_> =
+            player_memberships(db, player_id).await.unwrap().iter().map(|m| (m.club_id, m.status)).collect();
+        memberships.sort_by_key(|m| m.0);
+        memberships
+    }
+
+    async fn member_counts(db: &SqlitePool) -> Vec<(i64, i32)> {
+        let mut counts: Vec<_> = list_clubs(db).await.unwrap().iter().map(|c| (c.id, c.member_count)).collect();
+        counts.sort();
+        counts
+    }
+
+    #[tokio::test]
+    async fn test_join_club() {
+        let db = seeded_database(&[TWO_CLUBS, THIRD_CLUB]).await;
+
+        let joined = add_membership(&db, 1, 2, None).await.unwrap();
+        assert_eq!((joined.club_name.as_str(), joined.role, joined.status), ("Other club", MembershipRole::Member, MembershipStatus::Active));
+        assert_eq!(memberships(&db, 1).await, vec![(1, MembershipStatus::Active), (2, MembershipStatus::Active)]);
+        assert_eq!(member_counts(&db).await, vec![(1, 2), (2, 2), (3, 0)]);
+
+        // Joining again only changes the role when one is given
+        assert_eq!(add_membership(&db, 1, 2, Some(MembershipRole::Coach)).await.unwrap().role, MembershipRole::Coach);
+        assert_eq!(add_membership(&db, 1, 2, None).await.unwrap().role, MembershipRole::Coach);
+        assert_eq!(member_counts(&db).await, vec![(1, 2), (2, 2), (3, 0)]);
+
+        // Joining doesn't move the player's home club
+        assert_eq!(home_club(&db, 1).await, 1);
+
+        assert_eq!(add_membership(&db, 99, 2, None).await.unwrap_err().to_string(), "Player not found");
+        assert_eq!(add_membership(&db, 1, 99, None).await.unwrap_err().to_string(), "Club not found");
+    }
+
+    #[tokio::test]
+    async fn test_leave_club() {
+        let db = seeded_database(&[TWO_CLUBS, THIRD_CLUB]).await;
+        add_membership(&db, 1, 2, None).await.unwrap();
+        add_membership(&db, 1, 3, Some(MembershipRole::Committee)).await.unwrap();
+        sqlx::query("UPDATE club_memberships SET joined_at = '2020-01-01 00:00:00' WHERE player_id = 1 AND club_id = 3")
+            .execute(&db)
+            .await
+            .unwrap();
+
+        // Leaving the home club moves the player to their longest-standing other club
+        let left = end_membership(&db, 1, 1).await.unwrap();
+        assert_eq!(left.status, MembershipStatus::Inactive);
+        assert_eq!(home_club(&db, 1).await, 3);
+        assert_eq!(member_counts(&db).await, vec![(1, 1), (2, 2), (3, 1)]);
+
+        assert_eq!(end_membership(&db, 1, 1).await.unwrap_err().to_string(), "Player is not an active member of this club");
+
+        // Leaving a club that isn't home leaves home alone
+        end_membership(&db, 1, 2).await.unwrap();
+        assert_eq!(home_club(&db, 1).await, 3);
+
+        // A lapsed membership comes back with its role
+        add_membership(&db, 1, 2, None).await.unwrap();
+        assert_eq!(memberships(&db, 1).await, vec![(1, MembershipStatus::Inactive), (2, MembershipStatus::Active), (3, MembershipStatus::Active)]);
+    }
+
+    #[tokio::test]
+    async fn test_leaving_the_only_club_is_refused() {
+        let db = seeded_database(&[TWO_CLUBS]).await;
+
+        // Bob only belongs to his home club, so nothing changes
+        assert_eq!(
+            end_membership(&db, 2, 1).await.unwrap_err().to_string(),
+            "A player must belong to at least one club, delete the player instead"
+        );
+        assert_eq!(memberships(&db, 2).await, vec![(1, MembershipStatus::Active)]);
+        assert_eq!(home_club(&db, 2).await, 1);
+
+        // Ann's other membership has lapsed, which doesn't count either
+        add_membership(&db, 1, 2, None).await.unwrap();
+        end_membership(&db, 1, 2).await.unwrap();
+        assert!(end_membership(&db, 1, 1).await.is_err());
+        assert_eq!(home_club(&db, 1).await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_deleting_a_club_moves_players_who_belong_elsewhere() {
+        let db = seeded_database(&[TWO_CLUBS, THIRD_CLUB]).await;
+        add_membership(&db, 1, 2, None).await.unwrap();
+        add_membership(&db, 3, 1, None).await.unwrap();
+
+        remove_club(&db, None, 1).await.unwrap();
+
+        // Ann moves to her other club, Bob only belonged here so goes with it,
+        // and Cat keeps her home club and loses the membership
+        let players: Vec<(i64, i64)> = sqlx::query_as("SELECT id, club_id FROM players ORDER BY id").fetch_all(&db).await.unwrap();
+        assert_eq!(players, vec![(1, 2), (3, 2)]);
+        assert_eq!(memberships(&db, 1).await, vec![(2, MembershipStatus::Active)]);
+        assert_eq!(memberships(&db, 3).await, vec![(2, MembershipStatus::Active)]);
+        assert_eq!(member_counts(&db).await, vec![(2, 2), (3, 0)]);
+
+        assert_eq!(remove_club(&db, None, 1).await.unwrap_err().kind(), "NotFound");
+    }
+}
@@ -0,0 +1,287 @@
+#[cfg(test)]
+mod tests {
+    use lib::database::*;
+    use sqlx::sqlite::SqliteConnectOptions;
+    use sqlx::{Row, SqlitePool};
+    use std::collections::BTreeSet;
+    use std::path::PathBuf;
+
+    /// The database the app shipped with, from before migrations were versioned.
+    const UNVERSIONED_DB: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../data/app_data.db");
+
+    /// Rows for the tables each migration adds, indexed by version - 1.
+    const SEEDS: &[&str] = &[
+        r#"
+        INSERT INTO users (id, username, password_hash, role)
+            VALUES (1, 'admin', '$2b$12$abcdefghijklmnopqrstuu5Ab0VyS6xXD7ZQW2Qh3Yq6sCw1hJ3Pa', 'admin');
+        INSERT INTO clubs (id, name, created_by) VALUES (1, 'Shuttlers', 1);
+        INSERT INTO players (id, first_name, last_name, email, gender, club_id, skill_level) VALUES
+            (1, 'Ann', 'Ash', 'ann@example.com', 'Female', 1, 10),
+            (2, 'Bob', 'Birch', 'bob@example.com', 'Male', 1, 12),
+            (3, 'Cat', 'Cedar', 'cat@example.com', 'Female', 1, 14),
+            (4, 'Dan', 'Dogwood', 'dan@example.com', 'Male', 1, 16),
+            (5, 'Eve', 'Elm', 'eve@example.com', 'Female', 1, 18);
+        "#,
+        r#"
+        INSERT INTO sessions (id, club_id, court_count) VALUES (1, 1, 1);
+        INSERT INTO session_players (session_id, player_id) VALUES (1, 1), (1, 2), (1, 3), (1, 4), (1, 5);
+        INSERT INTO rounds (id, session_id, round_number) VALUES (1, 1, 1);
+        INSERT INTO round_games (id, round_id, court) VALUES (1, 1, 1);
+        INSERT INTO round_game_players (game_id, player_id, position, team) VALUES
+            (1, 1, 0, 'A'), (1, 2, 1, 'A'), (1, 3, 2, 'B'), (1, 4, 3, 'B');
+        INSERT INTO round_sit_outs (round_id, player_id) VALUES (1, 5);
+        "#,
+        r#"
+        INSERT INTO game_results (game_id, winner) VALUES (1, 'A');
+        INSERT INTO game_result_sets (game_id, set_number, team_a_score, team_b_score) VALUES (1, 1, 21, 17);
+        "#,
+        r#"
+        INSERT INTO rating_history (player_id, game_id, rating_before, deviation_before, rating_after, deviation_after)
+        SELECT player_id, 1, 1000, 350, 1000, 300 FROM round_game_players WHERE game_id = 1;
+        UPDATE players SET rating = 1000, rating_deviation = 300 WHERE id IN (1, 2, 3, 4);
+        "#,
+        // Players added after the backfill need their own memberships
+        r#"
+        INSERT OR IGNORE INTO club_memberships (player_id, club_id) SELECT id, club_id FROM players;
+        "#,
+        r#"
+        UPDATE players SET check_in_pin_hash = '$2b$10$abcdefghijklmnopqrstuu5Ab0VyS6xXD7ZQW2Qh3Yq6sCw1hJ3Pa' WHERE id = 1;
+        "#,
+        r#"
+        INSERT INTO club_roles (user_id, club_id, role) VALUES (1, 1, 'Owner');
+        "#,
+        r#"
+        INSERT INTO audit_log (actor, action, entity, entity_id, club_id, before_json, after_json)
+            VALUES ('admin', 'Update', 'Player', 1, 1, '{"skill_level":10}', '{"skill_level":11}');
+        "#,
+    ];
+
+    async fn open(name: &str) -> (SqlitePool, PathBuf) {
+        let path = std::env::temp_dir().join(format!("migrations_tests_{}_{}.db", std::process::id(), name));
+        let _ = std::fs::remove_file(&path);
+
+        let options = SqliteConnectOptions::new().filename(&path).create_if_missing(true);
+        let db = SqlitePool::connect_with(options).await.unwrap();
+        (db, path)
+    }
+
+    async fn close(db: SqlitePool, path: PathBuf) {
+        db.close().await;
+        let _ = std::fs::remove_file(path);
+    }
+
+    async fn seed(db: &SqlitePool, version: i64) {
+        sqlx::raw_sql(SEEDS[version as usize - 1]).execute(db).await.unwrap();
+    }
+
+    async fn count(db: &SqlitePool, table: &str) -> i64 {
+        sqlx::query(&format!("SELECT COUNT(*) as count FROM {}", table))
+            .fetch_one(db)
+            .await
+            .unwrap()
+            .get("count")
+    }
+
+    /// Every (table, column) pair in the database.
+    async fn columns(db: &SqlitePool) -> BTreeSet<(String, String)> {
+        sqlx::query(
+            "SELECT m.name as table_name, p.name as column_name FROM sqlite_master m
+             JOIN pragma_table_info(m.name) p
+             WHERE m.type = 'table' AND m.name NOT LIKE 'sqlite_%'"
+        )
+        .fetch_all(db)
+        .await
+        .unwrap()
+        .iter()
+        .map(|row| (row.get("table_name"), row.get("column_name")))
+        .collect()
+    }
+
+    #[test]
+    fn test_migrations_are_numbered_in_order() {
+        assert_eq!(SEEDS.len() as i64, latest_version());
+        for (i, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version, i as i64 + 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fresh_database_reaches_latest_version() {
+        let (db, path) = open("fresh").await;
+
+        run_migrations(&db).await.unwrap();
+        assert_eq!(schema_version(&db).await.unwrap(), latest_version());
+        assert_eq!(count(&db, "schema_migrations").await, latest_version());
+
+        // Running again finds nothing to do
+        run_migrations(&db).await.unwrap();
+        assert_eq!(count(&db, "schema_migrations").await, latest_version());
+
+        close(db, path).await;
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_from_each_version_keeps_data() {
+        let (head, head_path) = open("head").await;
+        run_migrations(&head).await.unwrap();
+        let head_columns = columns(&head).await;
+        close(head, head_path).await;
+
+        for start in 0..=latest_version() {
+            let (db, path) = open(&format!("from_{}", start)).await;
+
+            migrate_to(&db, start).await.unwrap();
+            assert_eq!(schema_version(&db).await.unwrap(), start);
+            for version in 1..=start {
+                seed(&db, version).await;
+            }
+
+            run_migrations(&db).await.unwrap();
+            assert_eq!(schema_version(&db).await.unwrap(), latest_version());
+            for version in start + 1..=latest_version() {
+                seed(&db, version).await;
+            }
+
+            assert_eq!(columns(&db).await, head_columns, "schema upgraded from version {}", start);
+
+            assert_eq!(count(&db, "players").await, 5, "upgraded from version {}", start);
+            assert_eq!(count(&db, "round_game_players").await, 4, "upgraded from version {}", start);
+            assert_eq!(count(&db, "game_result_sets").await, 1, "upgraded from version {}", start);
+            assert_eq!(count(&db, "rating_history").await, 4, "upgraded from version {}", start);
+            assert_eq!(count(&db, "club_memberships").await, 5, "upgraded from version {}", start);
+            assert_eq!(count(&db, "club_roles").await, 1, "upgraded from version {}", start);
+            assert_eq!(count(&db, "audit_log").await, 1, "upgraded from version {}", start);
+
+            close(db, path).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unversioned_database_is_adopted() {
+        let (head, head_path) = open("adopt_head").await;
+        run_migrations(&head).await.unwrap();
+        let head_columns = columns(&head).await;
+        close(head, head_path).await;
+
+        let (db, path) = open("adopt").await;
+        db.close().await;
+        std::fs::copy(UNVERSIONED_DB, &path).unwrap();
+        let db = SqlitePool::connect_with(SqliteConnectOptions::new().filename(&path)).await.unwrap();
+
+        let players = count(&db, "players").await;
+        let users = count(&db, "users").await;
+        run_migrations(&db).await.unwrap();
+
+        assert_eq!(schema_version(&db).await.unwrap(), latest_version());
+        assert!(columns(&db).await.is_superset(&head_columns));
+        assert_eq!(count(&db, "players").await, players);
+        assert_eq!(count(&db, "club_memberships").await, players);
+        assert_eq!(count(&db, "users").await, users);
+
+        close(db, path).await;
+    }
+
+    #[tokio::test]
+    async fn test_first_release_database_is_adopted() {
+        let (head, head_path) = open("first_head").await;
+        run_migrations(&head).await.unwrap();
+        let head_columns = columns(&head).await;
+        close(head, head_path).await;
+
+        // The first release made clubs and players only, with the sit-out count
+        // still called sit_off_count, and no users table for clubs to refer to
+        let (db, path) = open("first_release").await;
+        sqlx::raw_sql(
+            "PRAGMA foreign_keys = OFF;
+            CREATE TABLE clubs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT UNIQUE NOT NULL,
+                description TEXT,
+                created_by INTEGER,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (created_by) REFERENCES users (id)
+            );
+            CREATE TABLE players (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                first_name TEXT NOT NULL,
+                last_name TEXT NOT NULL,
+                email TEXT UNIQUE NOT NULL,
+                gender TEXT NOT NULL CHECK (gender IN ('Male', 'Female')),
+                club_id INTEGER NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                skill_level INTEGER NOT NULL CHECK (skill_level BETWEEN 1 AND 50),
+                sit_off_count INTEGER DEFAULT 0,
+                FOREIGN KEY (club_id) REFERENCES clubs(id) ON DELETE CASCADE
+            );
+            INSERT INTO clubs (id, name) VALUES (1, 'Shuttlers');
+            INSERT INTO players (first_name, last_name, email, gender, club_id, skill_level, sit_off_count)
+                VALUES ('Ann', 'Ash', 'ann@example.com', 'Female', 1, 10, 2);
+            PRAGMA foreign_keys = ON;"
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+
+        run_migrations(&db).await.unwrap();
+
+        assert_eq!(schema_version(&db).await.unwrap(), latest_version());
+        assert!(columns(&db).await.is_superset(&head_columns));
+        assert_eq!(count(&db, "club_memberships").await, 1);
+        let club = sqlx::query("SELECT member_count FROM clubs WHERE id = 1").fetch_one(&db).await.unwrap();
+        assert_eq!(club.get::<i64, _>("member_count"), 1);
+
+        sqlx::query("INSERT INTO clubs (name) VALUES ('Other club')").execute(&db).await.unwrap();
+
+        close(db, path).await;
+    }
+
+    #[tokio::test]
+    async fn test_unrecognised_database_is_refused() {
+        let schemas = [
+            // Tables no release made alongside the ones it did
+            "CREATE TABLE clubs (id INTEGER PRIMARY KEY, name TEXT, description TEXT, created_by INTEGER, created_at DATETIME);
+             CREATE TABLE players (id INTEGER PRIMARY KEY, first_name TEXT, last_name TEXT, email TEXT, gender TEXT,
+                 club_id INTEGER, created_at DATETIME, skill_level INTEGER, sit_out_count INTEGER);
+             CREATE TABLE sessions (id INTEGER PRIMARY KEY, club_id INTEGER);",
+            // Players without a skill level
+            "CREATE TABLE clubs (id INTEGER PRIMARY KEY, name TEXT, description TEXT, created_by INTEGER, created_at DATETIME);
+             CREATE TABLE players (id INTEGER PRIMARY KEY, first_name TEXT, last_name TEXT, email TEXT, gender TEXT,
+                 club_id INTEGER, created_at DATETIME, sit_out_count INTEGER);",
+            // Some other program's database
+            "CREATE TABLE notes (id INTEGER PRIMARY KEY, body TEXT);",
+        ];
+
+        for (i, schema) in schemas.iter().enumerate() {
+            let (db, path) = open(&format!("unrecognised_{}", i)).await;
+            sqlx::raw_sql(schema).execute(&db).await.unwrap();
+
+            assert!(run_migrations(&db).await.is_err(), "schema {} was upgraded", i);
+
+            // Nothing was changed, not even the versioning table
+            assert_eq!(schema_version(&db).await.unwrap(), 0);
+            let versioned = sqlx::query("SELECT name FROM sqlite_master WHERE name = 'schema_migrations'")
+                .fetch_optional(&db)
+                .await
+                .unwrap();
+            assert!(versioned.is_none());
+
+            close(db, path).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_newer_database_is_rejected() {
+        let (db, path) = open("newer").await;
+
+        run_migrations(&db).await.unwrap();
+        sqlx::query("INSERT INTO schema_migrations (version, description) VALUES (?, 'From the future')")
+            .bind(latest_version() + 1)
+            .execute(&db)
+            .await
+            .unwrap();
+
+        assert!(run_migrations(&db).await.is_err());
+
+        close(db, path).await;
+    }
+}
@@ -0,0 +1,163 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::*;
+    use lib::models::{rating_for_skill_level, skill_level_for_rating, Team};
+    use lib::services::rating::*;
+    use sqlx::SqlitePool;
+
+    /// Ten members of club 1 at skill level 10 and four games of one round. Ann
+    /// and Bob play Cat and Dan twice, then Ann and Eve play Bob and Fay, while
+    /// Gil, Hal, Ivy and Jon only ever play each other.
+    const FOUR_GAMES: &str = r#"
+        INSERT INTO clubs (id, name) VALUES (1, 'Shuttlers');
+        INSERT INTO players (id, first_name, last_name, email, gender, club_id, skill_level) VALUES
+            (1, 'Ann', 'Ash', 'ann@example.com', 'Female', 1, 10),
+            (2, 'Bob', 'Birch', 'bob@example.com', 'Male', 1, 10),
+            (3, 'Cat', 'Cedar', 'cat@example.com', 'Female', 1, 10),
+            (4, 'Dan', 'Dogwood', 'dan@example.com', 'Male', 1, 10),
+            (5, 'Eve', 'Elm', 'eve@example.com', 'Female', 1, 10),
+            (6, 'Fay', 'Fir', 'fay@example.com', 'Female', 1, 10),
+            (7, 'Gil', 'Gum', 'gil@example.com', 'Male', 1, 10),
+            (8, 'Hal', 'Hazel', 'hal@example.com', 'Male', 1, 10),
+            (9, 'Ivy', 'Ilex', 'ivy@example.com', 'Female', 1, 10),
+            (10, 'Jon', 'Juniper', 'jon@example.com', 'Male', 1, 10);
+        INSERT INTO sessions (id, club_id, court_count) VALUES (1, 1, 4);
+        INSERT INTO rounds (id, session_id, round_number) VALUES (1, 1, 1);
+        INSERT INTO round_games (id, round_id, court) VALUES (1, 1, 1), (2, 1, 2), (3, 1, 3), (4, 1, 4);
+        INSERT INTO round_game_players (game_id, player_id, position, team) VALUES
+            (1, 1, 0, 'A'), (1, 2, 1, 'A'), (1, 3, 2, 'B'), (1, 4, 3, 'B'),
+            (2, 1, 0, 'A'), (2, 2, 1, 'A'), (2, 3, 2, 'B'), (2, 4, 3, 'B'),
+            (3, 1, 0, 'A'), (3, 5, 1, 'A'), (3, 2, 2, 'B'), (3, 6, 3, 'B'),
+            (4, 7, 0, 'A'), (4, 8, 1, 'A'), (4, 9, 2, 'B'), (4, 10, 3, 'B');
+    "#;
+
+    async fn record(db: &SqlitePool, game_id: i64, winner: Team) {
+        sqlx::query("INSERT INTO game_results (game_id, winner) VALUES (?, ?)")
+            .bind(game_id)
+            .bind(winner.as_str())
+            .execute(db)
+            .await
+            .unwrap();
+        let mut conn = db.acquire().await.unwrap();
+        apply_result(&mut conn, game_id).await.unwrap();
+    }
+
+    async fn ratings(db: &SqlitePool) -> Vec<(i64, Option<f64>, Option<f64>, i32)> {
+        sqlx::query_as("SELECT id, rating, rating_deviation, skill_level FROM players ORDER BY id")
+            .fetch_all(db)
+            .await
+            .unwrap()
+    }
+
+    fn rating(rating: f64, deviation: f64) -> Rating {
+        Rating { rating, deviation }
+    }
+
+    #[test]
+    fn test_skill_levels_map_onto_ratings_and_back() {
+        for skill_level in 1..=50 {
+            assert_eq!(skill_level_for_rating(rating_for_skill_level(skill_level)), skill_level);
+        }
+        assert_eq!(skill_level_for_rating(0.0), 1);
+        assert_eq!(skill_level_for_rating(5000.0), 50);
+    }
+
+    #[test]
+    fn test_winners_gain_and_losers_drop() {
+        let team_a = [rating(1500.0, 200.0), rating(1500.0, 200.0)];
+        let team_b = [rating(1500.0, 200.0), rating(1500.0, 200.0)];
+
+        let (after_a, after_b) = rate_game(&team_a, &team_b, Team::A);
+
+        assert!(after_a.iter().all(|r| r.rating > 1500.0));
+        assert!(after_b.iter().all(|r| r.rating < 1500.0));
+        assert!(after_a.iter().chain(&after_b).all(|r| r.deviation < 200.0));
+    }
+
+    #[test]
+    fn test_upsets_move_ratings_further_than_expected_wins() {
+        let strong = [rating(1800.0, 100.0), rating(1800.0, 100.0)];
+        let weak = [rating(1400.0, 100.0), rating(1400.0, 100.0)];
+
+        let (expected_win, _) = rate_game(&strong, &weak, Team::A);
+        let (_, upset_win) = rate_game(&strong, &weak, Team::B);
+
+        let expected_gain = expected_win[0].rating - 1800.0;
+        let upset_gain = upset_win[0].rating - 1400.0;
+        assert!(upset_gain > expected_gain * 4.0);
+    }
+
+    #[test]
+    fn test_uncertain_players_move_more_than_settled_partners() {
+        let team_a = [rating(1500.0, 350.0), rating(1500.0, 60.0)];
+        let team_b = [rating(1500.0, 100.0), rating(1500.0, 100.0)];
+
+        let (after_a, _) = rate_game(&team_a, &team_b, Team::A);
+
+        assert!(after_a[0].rating - 1500.0 > after_a[1].rating - 1500.0);
+        assert!(after_a[1].deviation >= MIN_DEVIATION);
+    }
+
+    #[tokio::test]
+    async fn test_applying_a_result_rates_everyone_in_the_game() {
+        let db = seeded_database(&[FOUR_GAMES]).await;
+        let start = rating_for_skill_level(10);
+
+        record(&db, 1, Team::A).await;
+
+        let players = ratings(&db).await;
+        assert!(players[..2].iter().all(|(_, rating, _, _)| rating.unwrap() > start));
+        assert!(players[2..4].iter().all(|(_, rating, _, _)| rating.unwrap() < start));
+        assert!(players[4..].iter().all(|(_, rating, _, _)| rating.is_none()));
+
+        // Everyone started from their skill level, which stays put while the club sets it by hand
+        let history: Vec<(f64, f64)> = sqlx::query_as("SELECT rating_before, deviation_before FROM rating_history WHERE game_id = 1")
+            .fetch_all(&db)
+            .await
+            .unwrap();
+        assert_eq!(history, vec![(start, INITIAL_DEVIATION); 4]);
+        assert!(players.iter().all(|(_, _, _, skill_level)| *skill_level == 10));
+
+        sqlx::query("UPDATE clubs SET auto_skill_level = 1 WHERE id = 1").execute(&db).await.unwrap();
+        record(&db, 2, Team::A).await;
+
+        let (_, rating, _, skill_level) = ratings(&db).await[0];
+        assert!(skill_level > 10);
+        assert_eq!(skill_level, skill_level_for_rating(rating.unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_replaying_a_correction_matches_having_recorded_it_right() {
+        let db = seeded_database(&[FOUR_GAMES]).await;
+        for (game_id, winner) in [(1, Team::A), (2, Team::A), (3, Team::A), (4, Team::B)] {
+            record(&db, game_id, winner).await;
+        }
+        let untouched: Vec<(i64,)> = sqlx::query_as("SELECT id FROM rating_history WHERE game_id IN (1, 4) ORDER BY id")
+            .fetch_all(&db)
+            .await
+            .unwrap();
+
+        sqlx::query("UPDATE game_results SET winner = 'B' WHERE game_id = 2").execute(&db).await.unwrap();
+        let mut conn = db.acquire().await.unwrap();
+        replay_ratings(&mut conn, 2).await.unwrap();
+        drop(conn);
+
+        let expected = seeded_database(&[FOUR_GAMES]).await;
+        for (game_id, winner) in [(1, Team::A), (2, Team::B), (3, Team::A), (4, Team::B)] {
+            record(&expected, game_id, winner).await;
+        }
+        assert_eq!(ratings(&db).await, ratings(&expected).await);
+
+        // The game before the correction and the game nobody involved played in were left alone
+        let kept: Vec<(i64,)> = sqlx::query_as("SELECT id FROM rating_history WHERE game_id IN (1, 4) ORDER BY id")
+            .fetch_all(&db)
+            .await
+            .unwrap();
+        assert_eq!(kept, untouched);
+
+        let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM rating_history").fetch_one(&db).await.unwrap();
+        assert_eq!(rows, 16);
+    }
+}
@@ -0,0 +1,96 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::*;
+    use lib::commands::reports::*;
+
+    /// Player-minutes come from julianday arithmetic, so allow for rounding.
+    fn assert_minutes(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 0.001, "{} minutes, expected {}", actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_club_report_over_every_session() {
+        let db = seeded_database(&[TWO_NIGHTS]).await;
+        let report = build_club_report(&db, 1, None, None).await.unwrap();
+
+        // The other club's session on the same night is left out
+        assert_eq!(report.sessions.iter().map(|s| s.session_id).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!((report.sessions_held, report.unique_attendees), (2, 6));
+        assert_eq!(report.average_attendance, 5.5);
+
+        // Two hours on one court, 20 and 30 minute games of four players
+        let first = &report.sessions[0];
+        assert_eq!((first.attendees, first.games, first.court_count), (5, 2, 1));
+        assert_minutes(first.player_minutes_played, 200.0);
+        assert_minutes(first.player_minutes_available, 480.0);
+
+        // One hour, with 30 and 15 minute games
+        assert_minutes(report.sessions[1].player_minutes_played, 180.0);
+        assert_minutes(report.sessions[1].player_minutes_available, 240.0);
+
+        assert_minutes(report.player_minutes_played, 380.0);
+        assert_minutes(report.player_minutes_available, 720.0);
+        assert_minutes(report.court_utilisation.unwrap(), 380.0 / 720.0);
+
+        // Everyone's first session is inside the report
+        assert_eq!((report.new_members, report.returning_members), (6, 0));
+    }
+
+    #[tokio::test]
+    async fn test_club_report_within_dates() {
+        let db = seeded_database(&[TWO_NIGHTS]).await;
+
+        // Only Fay came for the first time in February
+        let february = build_club_report(&db, 1, Some("2024-02-01".to_string()), None).await.unwrap();
+        assert_eq!((february.sessions_held, february.unique_attendees), (1, 6));
+        assert_eq!((february.new_members, february.returning_members), (1, 5));
+        assert_eq!(february.average_attendance, 6.0);
+        assert_minutes(february.court_utilisation.unwrap(), 0.75);
+
+        let january = build_club_report(&db, 1, None, Some("2024-01-09".to_string())).await.unwrap();
+        assert_eq!((january.sessions_held, january.new_members, january.returning_members), (1, 5, 0));
+
+        let empty = build_club_report(&db, 1, Some("2024-03-01".to_string()), None).await.unwrap();
+        assert_eq!((empty.sessions_held, empty.unique_attendees, empty.average_attendance), (0, 0, 0.0));
+        assert_eq!(empty.court_utilisation, None);
+
+        assert_eq!(build_club_report(&db, 99, None, None).await.unwrap_err().kind(), "NotFound");
+        assert_eq!(
+            build_club_report(&db, 1, Some("February".to_string()), None).await.unwrap_err().to_string(),
+            "Invalid date 'February', expected YYYY-MM-DD"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_club_report_csv() {
+        let db = seeded_database(&[TWO_NIGHTS]).await;
+
+        let february = build_club_report(&db, 1, Some("2024-02-01".to_string()), None).await.unwrap();
+        let csv = club_report_to_csv(&february).unwrap();
+        assert_eq!(
+            csv,
+            "Club,1\n\
+             From,2024-02-01\n\
+             To,\n\
+             Sessions held,1\n\
+             Unique attendees,6\n\
+             Average attendance,6.0\n\
+             New members,1\n\
+             Returning members,5\n\
+             Player minutes played,180\n\
+             Player minutes available,240\n\
+             Court utilisation,75.0%\n\
+             \n\
+             Session,Started,Ended,Courts,Attendees,Games,Player minutes played,Player minutes available,Court utilisation\n\
+             2,2024-02-06 19:00:00,2024-02-06 20:00:00,1,6,2,180,240,75.0%\n"
+        );
+
+        // With no sessions the utilisation is left blank rather than 0%
+        let empty = build_club_report(&db, 2, None, Some("2024-01-31".to_string())).await.unwrap();
+        let csv = club_report_to_csv(&empty).unwrap();
+        assert!(csv.contains("\nCourt utilisation,\n\n"));
+        assert!(csv.ends_with("Court utilisation\n"));
+    }
+}
@@ -0,0 +1,108 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::*;
+    use lib::commands::results::*;
+    use lib::models::*;
+
+    /// A round of club 1 with one doubles game, Ann and Bob against Cat and Dan.
+    const ONE_GAME: &str = r#"
+        INSERT INTO clubs (id, name) VALUES (1, 'Shuttlers');
+        INSERT INTO players (id, first_name, last_name, email, gender, club_id, skill_level) VALUES
+            (1, 'Ann', 'Ash', 'ann@example.com', 'Female', 1, 10),
+            (2, 'Bob', 'Birch', 'bob@example.com', 'Male', 1, 10),
+            (3, 'Cat', 'Cedar', 'cat@example.com', 'Female', 1, 10),
+            (4, 'Dan', 'Dogwood', 'dan@example.com', 'Male', 1, 10);
+        INSERT INTO sessions (id, club_id, court_count) VALUES (1, 1, 1);
+        INSERT INTO rounds (id, session_id, round_number) VALUES (1, 1, 1);
+        INSERT INTO round_games (id, round_id, court) VALUES (1, 1, 1);
+        INSERT INTO round_game_players (game_id, player_id, position, team) VALUES
+            (1, 1, 0, 'A'), (1, 2, 1, 'A'), (1, 3, 2, 'B'), (1, 4, 3, 'B');
+    "#;
+
+    fn set(team_a: i32, team_b: i32) -> SetScore {
+        SetScore { team_a, team_b }
+    }
+
+    #[test]
+    fn test_set_winner_accepts_valid_scores() {
+        assert_eq!(set(21, 15).winner(), Ok(Team::A));
+        assert_eq!(set(19, 21).winner(), Ok(Team::B));
+        assert_eq!(set(24, 22).winner(), Ok(Team::A));
+        assert_eq!(set(28, 30).winner(), Ok(Team::B));
+        assert_eq!(set(30, 29).winner(), Ok(Team::A));
+    }
+
+    #[test]
+    fn test_set_winner_rejects_invalid_scores() {
+        for (a, b) in [(20, 18), (21, 20), (23, 20), (22, 22), (31, 29), (30, 27), (-1, 21)] {
+            assert!(set(a, b).winner().is_err(), "{}-{} should be rejected", a, b);
+        }
+    }
+
+    #[test]
+    fn test_match_winner_single_set() {
+        assert_eq!(match_winner(&[set(15, 21)]), Ok(Team::B));
+        assert!(match_winner(&[]).is_err());
+    }
+
+    #[test]
+    fn test_match_winner_best_of_three() {
+        assert_eq!(match_winner(&[set(21, 10), set(21, 19)]), Ok(Team::A));
+        assert_eq!(match_winner(&[set(21, 10), set(18, 21), set(27, 29)]), Ok(Team::B));
+
+        // One set each is not a finished match, and nothing is played once it is decided
+        assert!(match_winner(&[set(21, 10), set(10, 21)]).is_err());
+        assert!(match_winner(&[set(21, 10), set(21, 10), set(10, 21)]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_result_is_stored_and_finishes_the_game() {
+        let db = seeded_database(&[ONE_GAME]).await;
+
+        let result = add_result(&db, 1, vec![set(21, 10), set(18, 21), set(21, 19)]).await.unwrap();
+        assert_eq!(result.winner, Team::A);
+
+        let mut conn = db.acquire().await.unwrap();
+        let stored = fetch_session_results(&mut conn, 1).await.unwrap();
+        assert_eq!(stored[&1].sets, vec![set(21, 10), set(18, 21), set(21, 19)]);
+        assert_eq!(stored[&1].winner, Team::A);
+        drop(conn);
+
+        let finished: Option<String> = sqlx::query_scalar("SELECT finished_at FROM round_games WHERE id = 1")
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert!(finished.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_second_result_is_rejected_until_corrected() {
+        let db = seeded_database(&[ONE_GAME]).await;
+        add_result(&db, 1, vec![set(21, 15)]).await.unwrap();
+
+        let error = add_result(&db, 1, vec![set(15, 21)]).await.unwrap_err();
+        assert_eq!((error.kind(), error.to_string().as_str()), ("Conflict", "Game already has a result"));
+
+        let corrected = replace_result(&db, 1, vec![set(15, 21)]).await.unwrap();
+        assert_eq!(corrected.winner, Team::B);
+
+        let mut conn = db.acquire().await.unwrap();
+        let stored = fetch_session_results(&mut conn, 1).await.unwrap();
+        assert_eq!(stored[&1].sets, vec![set(15, 21)]);
+    }
+
+    #[tokio::test]
+    async fn test_games_not_drawn_in_a_round_are_refused() {
+        let db = seeded_database(&[ONE_GAME]).await;
+
+        assert_eq!(add_result(&db, 2, vec![set(21, 15)]).await.unwrap_err().to_string(), "Game not found");
+        assert_eq!(replace_result(&db, 1, vec![set(21, 15)]).await.unwrap_err().to_string(), "Game has no result to correct");
+
+        // Invalid scores are turned down before anything is stored
+        assert!(add_result(&db, 1, vec![set(20, 18)]).await.is_err());
+        let results: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM game_results").fetch_one(&db).await.unwrap();
+        assert_eq!(results, 0);
+    }
+}
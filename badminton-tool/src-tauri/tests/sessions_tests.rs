@@ -0,0 +1,336 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::*;
+    use lib::commands::games_maker::PairingHistory;
+    use lib::commands::sessions::*;
+    use lib::models::*;
+    use sqlx::sqlite::SqliteConnectOptions;
+    use sqlx::SqlitePool;
+    use std::path::{Path, PathBuf};
+
+    /// Six members of club 1 with the same skill, enough for one doubles court
+    /// and two sitting out.
+    const CLUB_OF_SIX: &str = r#"
+        INSERT INTO clubs (id, name) VALUES (1, 'Shuttlers');
+        INSERT INTO players (id, first_name, last_name, email, gender, club_id, skill_level) VALUES
+            (1, 'Ann', 'Ash', 'ann@example.com', 'Female', 1, 10),
+            (2, 'Bob', 'Birch', 'bob@example.com', 'Male', 1, 10),
+            (3, 'Cat', 'Cedar', 'cat@example.com', 'Female', 1, 10),
+            (4, 'Dan', 'Dogwood', 'dan@example.com', 'Male', 1, 10),
+            (5, 'Eve', 'Elm', 'eve@example.com', 'Female', 1, 10),
+            (6, 'Fin', 'Fir', 'fin@example.com', 'Male', 1, 10);
+        INSERT INTO club_memberships (player_id, club_id) VALUES (1, 1), (2, 1), (3, 1), (4, 1), (5, 1), (6, 1);
+    "#;
+
+    /// Four more members of club 1, so two courts leave two sitting out.
+    const FOUR_MORE: &str = r#"
+        INSERT INTO players (id, first_name, last_name, email, gender, club_id, skill_level) VALUES
+            (10, 'Jo', 'Juniper', 'jo@example.com', 'Female', 1, 10),
+            (11, 'Kit', 'Kauri', 'kit@example.com', 'Male', 1, 10),
+            (12, 'Lea', 'Larch', 'lea@example.com', 'Female', 1, 10),
+            (13, 'Max', 'Maple', 'max@example.com', 'Male', 1, 10);
+        INSERT INTO club_memberships (player_id, club_id) VALUES (10, 1), (11, 1), (12, 1), (13, 1);
+    "#;
+
+    /// Gus arrives late to club 1, Hal and Ivy don't play there.
+    const LATECOMERS: &str = r#"
+        INSERT INTO clubs (id, name) VALUES (2, 'Other club');
+        INSERT INTO players (id, first_name, last_name, email, gender, club_id, skill_level) VALUES
+            (7, 'Gus', 'Gum', 'gus@example.com', 'Male', 1, 10),
+            (8, 'Hal', 'Hazel', 'hal@example.com', 'Male', 2, 10),
+            (9, 'Ivy', 'Ivy', 'ivy@example.com', 'Female', 1, 10);
+        INSERT INTO club_memberships (player_id, club_id, status) VALUES (7, 1, 'Active'), (8, 2, 'Active'), (9, 1, 'Inactive');
+    "#;
+
+    fn temp_database(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("sessions_tests_{}_{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join("badminton.db")
+    }
+
+    async fn open(path: &Path) -> SqlitePool {
+        let options = SqliteConnectOptions::new().filename(path).create_if_missing(true);
+        let db = SqlitePool::connect_with(options).await.unwrap();
+        lib::database::run_migrations(&db).await.unwrap();
+        db
+    }
+
+    /// Sit-outs, games played and last round sat out for each player, by id.
+    async fn counters(db: &SqlitePool, session_id: i64) -> Vec<(i64, i32, i32, Option<i32>)> {
+        let mut players = session_players(db, session_id).await.unwrap();
+        players.sort_by_key(|p| p.player_id);
+        players.iter().map(|p| (p.player_id, p.sit_out_count, p.games_played, p.last_sat_out_round)).collect()
+    }
+
+    /// Each game's id and the ids on either side, then the ids sitting out.
+    type Lineup = (Vec<(Option<i64>, Vec<i64>, Vec<i64>)>, Vec<i64>);
+
+    fn lineup(round: &SessionRound) -> Lineup {
+        let ids = |players: &[InGamePlayer]| players.iter().map(|p| p.id).collect::<Vec<_>>();
+        let games = round.games.iter().map(|g| (g.id, ids(&g.team_a), ids(&g.team_b))).collect();
+        let mut sitting_out = ids(&round.sitting_out);
+        sitting_out.sort();
+        (games, sitting_out)
+    }
+
+    #[tokio::test]
+    async fn test_rounds_survive_a_restart() {
+        let path = temp_database("restart");
+        let db = open(&path).await;
+        sqlx::raw_sql(CLUB_OF_SIX).execute(&db).await.unwrap();
+
+        let session = create_session(&db, 1, 1, &[1, 2, 3, 4, 5, 6]).await.unwrap();
+        let first = next_round(&db, session.id, None, Some(1)).await.unwrap();
+        let second = next_round(&db, session.id, None, Some(2)).await.unwrap();
+        db.close().await;
+
+        let db = open(&path).await;
+        let history = session_history(&db, session.id).await.unwrap();
+        assert_eq!(history.session.ended_at, None);
+        assert_eq!(history.rounds.iter().map(|r| (r.round_number, r.seed)).collect::<Vec<_>>(), vec![(1, Some(1)), (2, Some(2))]);
+        assert_eq!(history.rounds.iter().map(lineup).collect::<Vec<_>>(), vec![lineup(&first), lineup(&second)]);
+
+        // Who partnered and opposed whom is rebuilt from the stored games
+        let played = PairingHistory::from_games(first.games.iter().chain(&second.games));
+        let reloaded = PairingHistory::from_games(history.rounds.iter().flat_map(|r| &r.games));
+        for a in 1..=6 {
+            for b in a + 1..=6 {
+                assert_eq!(reloaded.partner_count(a, b), played.partner_count(a, b));
+                assert_eq!(reloaded.opponent_count(a, b), played.opponent_count(a, b));
+            }
+        }
+
+        // The players who sat out before the restart go on court straight after it
+        let third = next_round(&db, session.id, None, Some(3)).await.unwrap();
+        let on_court: Vec<i64> = third.games.iter().flat_map(|g| g.players()).map(|p| p.id).collect();
+        assert_eq!(third.round_number, 3);
+        assert!(lineup(&second).1.iter().all(|id| on_court.contains(id)));
+    }
+
+    #[tokio::test]
+    async fn test_counters_survive_a_restart_until_the_next_session() {
+        let path = temp_database("counters");
+        let db = open(&path).await;
+        sqlx::raw_sql(CLUB_OF_SIX).execute(&db).await.unwrap();
+
+        let session = create_session(&db, 1, 1, &[1, 2, 3, 4, 5, 6]).await.unwrap();
+        let first = next_round(&db, session.id, None, Some(1)).await.unwrap();
+        let second = next_round(&db, session.id, None, Some(2)).await.unwrap();
+        let before = counters(&db, session.id).await;
+        db.close().await;
+
+        // Reopening runs the migrations again, which must leave the counters alone
+        let db = open(&path).await;
+        let after = counters(&db, session.id).await;
+        assert_eq!(after, before);
+
+        for (id, sit_out_count, games_played, last_sat_out_round) in after {
+            let sat_out = |round: &SessionRound| round.sitting_out.iter().any(|p| p.id == id);
+            let rounds_sat_out = [&first, &second].into_iter().filter(|r| sat_out(r)).count() as i32;
+            assert_eq!((sit_out_count, games_played), (rounds_sat_out, 2 - rounds_sat_out));
+            assert_eq!(last_sat_out_round, if sat_out(&second) { Some(2) } else if sat_out(&first) { Some(1) } else { None });
+        }
+
+        let player_counts: Vec<i32> = sqlx::query_scalar("SELECT sit_out_count FROM players ORDER BY id").fetch_all(&db).await.unwrap();
+        assert_eq!(player_counts, before.iter().map(|c| c.1).collect::<Vec<_>>());
+
+        // A new session ends the old one and starts everyone from nothing
+        let next = create_session(&db, 1, 1, &[1, 2, 3, 4, 5, 6]).await.unwrap();
+        assert!(session_history(&db, session.id).await.unwrap().session.ended_at.is_some());
+        assert_eq!(counters(&db, next.id).await, (1..=6).map(|id| (id, 0, 0, None)).collect::<Vec<_>>());
+
+        let player_counts: Vec<i32> = sqlx::query_scalar("SELECT sit_out_count FROM players ORDER BY id").fetch_all(&db).await.unwrap();
+        assert_eq!(player_counts, vec![0; 6]);
+    }
+
+    #[tokio::test]
+    async fn test_late_arrivals_are_credited_with_a_fair_share_of_sit_outs() {
+        let db = seeded_database(&[CLUB_OF_SIX, LATECOMERS]).await;
+        let session = create_session(&db, 1, 1, &[1, 2, 3, 4, 5, 6]).await.unwrap();
+
+        // Before any round there is nothing to make up for
+        check_in(&db, session.id, 7).await.unwrap();
+        check_out(&db, session.id, 7).await.unwrap();
+        let before_play = counters(&db, session.id).await;
+        assert_eq!(before_play[6], (7, 0, 0, None));
+
+        // Two rounds sit four of the six out, so Gus is owed one sit-out and
+        // counts as having waited through round 2
+        let first = next_round(&db, session.id, None, Some(1)).await.unwrap();
+        let second = next_round(&db, session.id, None, Some(2)).await.unwrap();
+        assert!([&first, &second].iter().all(|r| r.games.iter().flat_map(|g| g.players()).all(|p| p.id != 7)));
+
+        let gus = check_in(&db, session.id, 7).await.unwrap();
+        assert_eq!((gus.sit_out_count, gus.games_played, gus.last_sat_out_round), (1, 0, Some(2)));
+        assert_eq!(check_in(&db, session.id, 7).await.unwrap_err().to_string(), "Player is already checked in");
+
+        let third = next_round(&db, session.id, None, Some(3)).await.unwrap();
+        assert!(third.games.iter().flat_map(|g| g.players()).any(|p| p.id == 7));
+
+        // Coming back after checking out never lowers what a player is owed
+        check_out(&db, session.id, 1).await.unwrap();
+        sqlx::query("UPDATE session_players SET sit_out_count = 5 WHERE player_id = 1").execute(&db).await.unwrap();
+        assert_eq!(check_in(&db, session.id, 1).await.unwrap().sit_out_count, 5);
+    }
+
+    #[tokio::test]
+    async fn test_only_active_members_check_in_to_running_sessions() {
+        let db = seeded_database(&[CLUB_OF_SIX, LATECOMERS]).await;
+        let session = create_session(&db, 1, 1, &[1, 2, 3, 4]).await.unwrap();
+
+        for player_id in [8, 9] {
+            let error = check_in(&db, session.id, player_id).await.unwrap_err();
+            assert_eq!((error.field(), error.to_string().as_str()), (Some("player_id"), "Player is not an active member of this club"));
+        }
+        assert_eq!(check_in(&db, session.id, 99).await.unwrap_err().kind(), "NotFound");
+        assert_eq!(check_out(&db, session.id, 5).await.unwrap_err().to_string(), "Player is not checked in to this session");
+
+        // Players who checked out are left out of the next round
+        check_out(&db, session.id, 4).await.unwrap();
+        let error = next_round(&db, session.id, None, Some(1)).await.err().map(|e| e.to_string());
+        assert_eq!(error.as_deref(), Some("Not enough players to fill a court"));
+
+        // Nobody comes or goes once the session is over
+        finish_session(&db, session.id).await.unwrap();
+        assert_eq!(check_in(&db, session.id, 4).await.unwrap_err().to_string(), "Session has already ended");
+        assert_eq!(check_out(&db, session.id, 1).await.unwrap_err().kind(), "Conflict");
+    }
+
+    #[tokio::test]
+    async fn test_only_active_members_start_a_session() {
+        let db = seeded_database(&[CLUB_OF_SIX, LATECOMERS]).await;
+
+        for player_id in [8, 9] {
+            let error = create_session(&db, 1, 1, &[1, 2, 3, 4, player_id]).await.unwrap_err();
+            assert_eq!(error.field(), Some("player_ids"));
+            assert_eq!(error.to_string(), format!("Player {} is not an active member of this club", player_id));
+        }
+
+        // Nothing was started by the sessions that were turned down
+        let sessions: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sessions").fetch_one(&db).await.unwrap();
+        assert_eq!(sessions, 0);
+
+        let session = create_session(&db, 1, 1, &[1, 2, 3, 4, 7]).await.unwrap();
+        assert_eq!(session_players(&db, session.id).await.unwrap().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_refilling_a_court_leaves_everyone_else_alone() {
+        let db = seeded_database(&[CLUB_OF_SIX, FOUR_MORE]).await;
+        let session = create_session(&db, 1, 2, &[1, 2, 3, 4, 5, 6, 10, 11, 12, 13]).await.unwrap();
+        let first = next_round(&db, session.id, None, Some(1)).await.unwrap();
+        let before = counters(&db, session.id).await;
+
+        let court_1: Vec<i64> = first.games[0].players().map(|p| p.id).collect();
+        let court_2: Vec<i64> = first.games[1].players().map(|p| p.id).collect();
+        let sat_out = lineup(&first).1;
+
+        let refill = refill_court(&db, session.id, 1, None, Some(2)).await.unwrap();
+        assert_eq!((refill.id, refill.round_number), (first.id, 1));
+        assert_eq!(refill.games.len(), 1);
+        assert_eq!(refill.games[0].court, 1);
+
+        // The two who sat out have waited longest, and nobody on court 2 is taken
+        let on_court: Vec<i64> = refill.games[0].players().map(|p| p.id).collect();
+        assert!(sat_out.iter().all(|id| on_court.contains(id)));
+        assert!(court_2.iter().all(|id| !on_court.contains(id)));
+
+        // Only the new game's players gain a game, nobody gains a sit-out
+        let waiting = lineup(&refill).1;
+        assert_eq!(waiting.len(), 2);
+        assert!(waiting.iter().all(|id| court_1.contains(id)));
+        for (after, before) in counters(&db, session.id).await.iter().zip(&before) {
+            let games_played = before.2 + on_court.contains(&before.0) as i32;
+            assert_eq!(*after, (before.0, before.1, games_played, before.3));
+        }
+        for player in &refill.sitting_out {
+            assert_eq!(player.sit_out_count, 0);
+        }
+
+        // The game joins round 1 next to the one it replaced
+        let history = session_history(&db, session.id).await.unwrap();
+        assert_eq!(history.rounds.len(), 1);
+        assert_eq!(lineup(&history.rounds[0]).1, sat_out);
+        let courts: Vec<(usize, Option<i64>)> = history.rounds[0].games.iter().map(|g| (g.court, g.id)).collect();
+        assert_eq!(courts, vec![(1, first.games[0].id), (1, refill.games[0].id), (2, first.games[1].id)]);
+
+        let unfinished: Vec<i64> = sqlx::query_scalar("SELECT id FROM round_games WHERE finished_at IS NULL ORDER BY id")
+            .fetch_all(&db)
+            .await
+            .unwrap();
+        assert_eq!(unfinished, vec![first.games[1].id.unwrap(), refill.games[0].id.unwrap()]);
+
+        // Court 2 frees up next: the two left waiting from court 1 go on first
+        let second_refill = refill_court(&db, session.id, 2, None, Some(3)).await.unwrap();
+        assert_eq!(second_refill.games[0].court, 2);
+        let on_court: Vec<i64> = second_refill.games[0].players().map(|p| p.id).collect();
+        assert!(waiting.iter().all(|id| on_court.contains(id)));
+
+        // It's stored on court 2 as well, in place of the game that finished there
+        let history = session_history(&db, session.id).await.unwrap();
+        let courts: Vec<(usize, Option<i64>)> = history.rounds[0].games.iter().map(|g| (g.court, g.id)).collect();
+        assert_eq!(
+            courts,
+            vec![
+                (1, first.games[0].id),
+                (1, refill.games[0].id),
+                (2, first.games[1].id),
+                (2, second_refill.games[0].id),
+            ]
+        );
+        assert_eq!(history.rounds[0].games[3].players().map(|p| p.id).collect::<Vec<_>>(), on_court);
+    }
+
+    #[tokio::test]
+    async fn test_courts_freeing_up_at_once_draw_different_players() {
+        let path = temp_database("courts_at_once");
+        let db = open(&path).await;
+        for seed in [CLUB_OF_SIX, FOUR_MORE, "INSERT INTO players (id, first_name, last_name, email, gender, club_id, skill_level) VALUES
+                (14, 'Ned', 'Nikau', 'ned@example.com', 'Male', 1, 10),
+                (15, 'Ola', 'Oak', 'ola@example.com', 'Female', 1, 10);
+            INSERT INTO club_memberships (player_id, club_id) VALUES (14, 1), (15, 1);"]
+        {
+            sqlx::raw_sql(seed).execute(&db).await.unwrap();
+        }
+
+        // Two courts playing and four waiting, enough for both courts to be refilled
+        let session = create_session(&db, 1, 2, &[1, 2, 3, 4, 5, 6, 10, 11, 12, 13, 14, 15]).await.unwrap();
+        next_round(&db, session.id, None, Some(1)).await.unwrap();
+
+        let (court_1, court_2) = tokio::join!(
+            refill_court(&db, session.id, 1, None, Some(2)),
+            refill_court(&db, session.id, 2, None, Some(3)),
+        );
+        let (court_1, court_2) = (court_1.unwrap(), court_2.unwrap());
+
+        let on_court_1: Vec<i64> = court_1.games[0].players().map(|p| p.id).collect();
+        assert!(court_2.games[0].players().all(|p| !on_court_1.contains(&p.id)));
+
+        let unfinished: Vec<(i64, i64)> = sqlx::query_as("SELECT court, id FROM round_games WHERE finished_at IS NULL ORDER BY court")
+            .fetch_all(&db)
+            .await
+            .unwrap();
+        assert_eq!(unfinished, vec![(1, court_1.games[0].id.unwrap()), (2, court_2.games[0].id.unwrap())]);
+    }
+
+    #[tokio::test]
+    async fn test_history_carries_live_ratings() {
+        let db = seeded_database(&[CLUB_OF_SIX, "UPDATE players SET rating = 1000 + 50 * id WHERE id <= 4"]).await;
+        let session = create_session(&db, 1, 1, &[1, 2, 3, 4, 5, 6]).await.unwrap();
+        let round = next_round(&db, session.id, None, Some(1)).await.unwrap();
+
+        let history = session_history(&db, session.id).await.unwrap();
+        let stored = &history.rounds[0];
+        let players = stored.games.iter().flat_map(|g| g.players()).chain(&stored.sitting_out);
+        for player in players {
+            let rating = if player.id <= 4 { Some(1000.0 + 50.0 * player.id as f64) } else { None };
+            assert_eq!(player.rating, rating, "player {}", player.id);
+        }
+
+        // So the gap between the sides matches the one the round was balanced on
+        assert_eq!(stored.games[0].skill_gap, round.games[0].skill_gap);
+    }
+}
@@ -0,0 +1,103 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::*;
+    use lib::commands::stats::*;
+    use lib::models::*;
+
+    fn date(day: &str) -> Option<String> {
+        Some(day.to_string())
+    }
+
+    /// Names with games and wins, most frequent first.
+    fn tallies(people: &[PlayerTally]) -> Vec<(&str, i32, i32)> {
+        people.iter().map(|p| (p.first_name.as_str(), p.games, p.wins)).collect()
+    }
+
+    #[tokio::test]
+    async fn test_player_stats_over_every_session() {
+        let db = seeded_database(&[TWO_NIGHTS]).await;
+        let stats = player_stats(&db, 1, None, None).await.unwrap();
+
+        // Game 4 has no result, so it counts as played but neither won nor lost
+        assert_eq!((stats.games_played, stats.sit_outs), (4, 0));
+        assert_eq!((stats.wins, stats.losses), (2, 1));
+        assert_eq!(stats.win_rate, Some(2.0 / 3.0));
+
+        // 21-17, then 15-21 19-21, then 21-10 18-21 21-19
+        assert_eq!((stats.points_for, stats.points_against, stats.points_difference), (115, 109, 6));
+
+        assert_eq!(tallies(&stats.partners), vec![("Bob", 2, 2), ("Cat", 1, 0), ("Dan", 1, 0)]);
+        assert_eq!(tallies(&stats.opponents), vec![("Dan", 2, 2), ("Cat", 2, 1), ("Eve", 2, 1), ("Bob", 2, 0)]);
+
+        let trend: Vec<(i64, f64)> = stats.rating_trend.iter().map(|point| (point.game_id, point.rating)).collect();
+        assert_eq!(trend, vec![(1, 1010.0), (2, 1000.0), (3, 1012.0)]);
+
+        let eve = player_stats(&db, 5, None, None).await.unwrap();
+        assert_eq!((eve.games_played, eve.sit_outs, eve.wins, eve.losses), (2, 2, 1, 1));
+    }
+
+    #[tokio::test]
+    async fn test_player_stats_within_dates() {
+        let db = seeded_database(&[TWO_NIGHTS]).await;
+
+        let january = player_stats(&db, 1, None, date("2024-01-31")).await.unwrap();
+        assert_eq!((january.games_played, january.wins, january.losses), (2, 1, 1));
+        assert_eq!(january.win_rate, Some(0.5));
+        assert_eq!((january.points_for, january.points_against), (55, 59));
+        assert_eq!(january.rating_trend.len(), 2);
+
+        // Both ends are inclusive
+        let february = player_stats(&db, 1, date("2024-02-06"), date("2024-02-06")).await.unwrap();
+        assert_eq!((february.games_played, february.wins, february.losses), (2, 1, 0));
+        assert_eq!(february.win_rate, Some(1.0));
+        assert_eq!(february.points_difference, 10);
+        assert_eq!(tallies(&february.partners), vec![("Bob", 1, 1), ("Dan", 1, 0)]);
+        assert_eq!((february.from.as_deref(), february.to.as_deref()), (Some("2024-02-06"), Some("2024-02-06")));
+
+        let fay = player_stats(&db, 6, date("2024-02-01"), None).await.unwrap();
+        assert_eq!((fay.games_played, fay.sit_outs), (0, 2));
+        assert_eq!(fay.win_rate, None);
+
+        let later = player_stats(&db, 1, date("2024-03-01"), None).await.unwrap();
+        assert_eq!((later.games_played, later.points_difference), (0, 0));
+        assert!(later.partners.is_empty() && later.rating_trend.is_empty());
+
+        let bad_date = player_stats(&db, 1, date("2024-2-6"), None).await.unwrap_err();
+        assert_eq!((bad_date.field(), bad_date.to_string().as_str()), (Some("from"), "Invalid date '2024-2-6', expected YYYY-MM-DD"));
+        assert_eq!(player_stats(&db, 99, None, None).await.unwrap_err().kind(), "NotFound");
+    }
+
+    #[tokio::test]
+    async fn test_player_history() {
+        let db = seeded_database(&[TWO_NIGHTS]).await;
+        let history = player_history(&db, 2).await.unwrap();
+
+        let games: Vec<(i64, i64, i32, Team, Option<bool>)> =
+            history.iter().map(|g| (g.game_id, g.session_id, g.round_number, g.team, g.won)).collect();
+        assert_eq!(
+            games,
+            vec![
+                (1, 1, 1, Team::A, Some(true)),
+                (2, 1, 2, Team::B, Some(true)),
+                (3, 2, 1, Team::A, Some(true)),
+                (4, 2, 2, Team::B, None),
+            ]
+        );
+
+        let names = |people: &[PlayerSummary]| people.iter().map(|p| p.first_name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&history[1].partners), vec!["Eve"]);
+        assert_eq!(names(&history[1].opponents), vec!["Ann", "Cat"]);
+        assert_eq!(history[2].result.as_ref().unwrap().sets.len(), 3);
+        assert_eq!(history[0].played_at.as_deref(), Some("2024-01-09 19:00:00"));
+
+        // Only Ann has rating history in the fixture
+        assert_eq!((history[0].rating_before, history[0].rating_after), (None, None));
+        let ann = player_history(&db, 1).await.unwrap();
+        assert_eq!((ann[2].rating_before, ann[2].rating_after), (Some(1000.0), Some(1012.0)));
+
+        assert!(player_history(&db, 6).await.unwrap().is_empty());
+        assert_eq!(player_history(&db, 99).await.unwrap_err().kind(), "NotFound");
+    }
+}
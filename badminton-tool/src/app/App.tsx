@@ -3,12 +3,14 @@ import { BrowserRouter as Router, Route, Routes } from "react-router-dom";
 import ClubsListPage from "./pages/ClubsList";
 import Members from "./pages/Members";
 import Lobby from "./pages/Lobby";
+import Login from "./pages/Login";
 
 const App = () => {
   return (
     <Router>
       <Routes>
         <Route path="/" element={<ClubsListPage />} />
+        <Route path="/login" element={<Login />} />
         <Route path="/clubs/:clubId/members" element={<Members />} />
         <Route path="/clubs/:clubId/lobby" element={<Lobby />} />
       </Routes>
@@ -0,0 +1,45 @@
+import { invoke } from "@tauri-apps/api/core";
+
+// Shapes returned by the auth commands on the Rust side
+export interface User {
+  id: number;
+  username: string;
+  role: "Admin" | "User";
+}
+
+interface LoginResponse {
+  token: string;
+  user: User;
+}
+
+// Logins only last until the app restarts, so the token lives as long as the window
+const TOKEN_KEY = "loginToken";
+const USER_KEY = "loginUser";
+
+// Passed as `token` to commands that check the account's club role
+export const loginToken = (): string | null => sessionStorage.getItem(TOKEN_KEY);
+
+export const currentUser = (): User | null => {
+  const user = sessionStorage.getItem(USER_KEY);
+  return user ? (JSON.parse(user) as User) : null;
+};
+
+export const login = async (username: string, password: string): Promise<User> => {
+  const response = await invoke<LoginResponse>("login", { username, password });
+  sessionStorage.setItem(TOKEN_KEY, response.token);
+  sessionStorage.setItem(USER_KEY, JSON.stringify(response.user));
+  return response.user;
+};
+
+// The first account registered becomes the admin
+export const register = async (username: string, password: string): Promise<User> => {
+  await invoke<User>("register_user", { username, password });
+  return login(username, password);
+};
+
+export const logout = async (): Promise<void> => {
+  const token = loginToken();
+  sessionStorage.removeItem(TOKEN_KEY);
+  sessionStorage.removeItem(USER_KEY);
+  if (token) await invoke("logout", { token });
+};
@@ -0,0 +1,84 @@
+import React, { useEffect, useState } from "react";
+import { Box, Button, TextField, Typography } from "@mui/material";
+import { invoke } from "@tauri-apps/api/core";
+import { errorMessage } from "../errors";
+
+// Listening on every interface lets phones on the hall network reach the board
+const DEFAULT_BIND_ADDRESS = "0.0.0.0:8080";
+
+const BoardServerControl: React.FC = () => {
+  const [bindAddress, setBindAddress] = useState<string>(DEFAULT_BIND_ADDRESS);
+  const [address, setAddress] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const [busy, setBusy] = useState(false);
+
+  useEffect(() => {
+    invoke<string | null>("get_board_server_address")
+      .then(setAddress)
+      .catch((err) => console.error("Error loading the court board server:", err));
+  }, []);
+
+  const handleStart = async () => {
+    setBusy(true);
+    setError(null);
+    try {
+      setAddress(await invoke<string>("start_board_server", { bindAddress }));
+    } catch (err) {
+      setError(errorMessage(err));
+      console.error("Error starting the court board server:", err);
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const handleStop = async () => {
+    setBusy(true);
+    setError(null);
+    try {
+      await invoke("stop_board_server");
+      setAddress(null);
+    } catch (err) {
+      setError(errorMessage(err));
+      console.error("Error stopping the court board server:", err);
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  if (address) {
+    return (
+      <Box sx={{ display: "flex", alignItems: "center", justifyContent: "flex-end", gap: 1 }}>
+        <Typography variant="body2" color="text.secondary">
+          Board on http://{address}
+        </Typography>
+        <Button variant="outlined" size="small" onClick={handleStop} disabled={busy}>
+          Stop board
+        </Button>
+      </Box>
+    );
+  }
+
+  return (
+    <Box sx={{ display: "flex", alignItems: "flex-start", justifyContent: "flex-end", gap: 1 }}>
+      <TextField
+        label="Board address"
+        value={bindAddress}
+        onChange={(e) => setBindAddress(e.target.value)}
+        size="small"
+        error={error !== null}
+        helperText={error}
+        sx={{ width: 200 }}
+      />
+      <Button
+        variant="outlined"
+        onClick={handleStart}
+        disabled={busy || bindAddress.trim() === ""}
+        sx={{ height: 40 }}
+      >
+        Start board
+      </Button>
+    </Box>
+  );
+};
+
+export default BoardServerControl;
@@ -11,8 +11,11 @@ interface Player {
 }
 
 interface Game {
+  id: number | null;
   court: number;
-  players: Player[];
+  team_a: Player[];
+  team_b: Player[];
+  skill_gap: number;
 }
 
 interface CourtGridProps {
@@ -29,7 +32,15 @@ const CourtGrid: React.FC<CourtGridProps> = ({ games }) => {
               Court {game.court}
             </Typography>
             <Stack spacing={1}>
-              {game.players.map((player) => (
+              {game.team_a.map((player) => (
+                <Typography key={player.id}>
+                  {player.first_name} {player.last_name}
+                </Typography>
+              ))}
+              <Typography variant="caption" color="text.secondary">
+                vs
+              </Typography>
+              {game.team_b.map((player) => (
                 <Typography key={player.id}>
                   {player.first_name} {player.last_name}
                 </Typography>
@@ -13,8 +13,11 @@ interface Player {
 }
 
 interface Game {
+  id: number | null;
   court: number;
-  players: Player[];
+  team_a: Player[];
+  team_b: Player[];
+  skill_gap: number;
 }
 
 interface CourtsPanelProps {
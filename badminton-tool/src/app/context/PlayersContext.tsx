@@ -8,6 +8,7 @@ interface Player {
   gender?: "Male" | "Female";
   club_id: number;
   sit_out_count: number;
+  rating?: number | null;
 }
 
 interface PlayersContextType {
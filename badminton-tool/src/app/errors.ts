@@ -0,0 +1,17 @@
+// Shape of the errors returned by commands, AppError on the Rust side
+export interface AppError {
+  kind: "NotFound" | "Conflict" | "Validation" | "Unauthorized" | "Forbidden" | "Database" | "Io";
+  message: string;
+  field: string | null;
+}
+
+export const isAppError = (err: unknown): err is AppError =>
+  typeof err === "object" && err !== null && "kind" in err && "message" in err;
+
+// Anything else, such as a failure to reach the backend at all, is shown as it is
+export const errorMessage = (err: unknown): string =>
+  isAppError(err) ? err.message : String(err);
+
+// The command wants a login, either because there is none or it has expired
+export const needsLogin = (err: unknown): boolean =>
+  isAppError(err) && err.kind === "Unauthorized";
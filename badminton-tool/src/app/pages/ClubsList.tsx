@@ -1,5 +1,6 @@
 import EditIcon from "@mui/icons-material/Edit";
 import {
+  Alert,
   Box,
   Button,
   Dialog,
@@ -17,6 +18,8 @@ import {
 import { invoke } from "@tauri-apps/api/core";
 import React, { useEffect, useState } from "react";
 import { useNavigate } from "react-router-dom";
+import { currentUser, loginToken, logout } from "../auth";
+import { errorMessage, needsLogin } from "../errors";
 
 type Club = {
   id: number;
@@ -30,6 +33,8 @@ const ClubsListPage: React.FC = () => {
   const [renameDialogOpen, setRenameDialogOpen] = useState(false);
   const [renameClubId, setRenameClubId] = useState<string | null>(null);
   const [renameClubName, setRenameClubName] = useState("");
+  const [user, setUser] = useState(currentUser());
+  const [error, setError] = useState<string | null>(null);
   const navigate = useNavigate();
 
   const fetchClubs = () => {
@@ -48,9 +53,23 @@ const ClubsListPage: React.FC = () => {
 
   const handleCreateClub = async () => {
     if (!newClubName.trim()) return;
-    await invoke<Club>("create_club", { request: { name: newClubName } });
-    setNewClubName("");
-    fetchClubs();
+    setError(null);
+    try {
+      await invoke<Club>("create_club", { request: { name: newClubName }, token: loginToken() });
+      setNewClubName("");
+      fetchClubs();
+    } catch (err) {
+      if (needsLogin(err)) {
+        navigate("/login");
+        return;
+      }
+      setError(errorMessage(err));
+    }
+  };
+
+  const handleLogout = async () => {
+    await logout();
+    setUser(null);
   };
 
   // const handleOpenRenameDialog = (club: Club) => {
@@ -81,9 +100,24 @@ const ClubsListPage: React.FC = () => {
         overflow: "auto",
       }}
     >
-      <Typography variant="h4" gutterBottom>
-        My Clubs
-      </Typography>
+      <Box display="flex" alignItems="center" justifyContent="space-between" width="100%">
+        <Typography variant="h4" gutterBottom>
+          My Clubs
+        </Typography>
+        {user ? (
+          <Box display="flex" alignItems="center" gap={1}>
+            <Typography>Logged in as {user.username}</Typography>
+            <Button onClick={handleLogout}>Log out</Button>
+          </Box>
+        ) : (
+          <Button onClick={() => navigate("/login")}>Log in</Button>
+        )}
+      </Box>
+      {error && (
+        <Alert severity="error" sx={{ mb: 2 }}>
+          {error}
+        </Alert>
+      )}
       <Box display="flex" gap={2} mb={2}>
         <TextField
           label="New Club Name"
@@ -7,6 +7,7 @@ import SelectedPlayersPanel from "../components/SelectedPlayersPanel";
 import CourtsPanel from "../components/CourtsPanel";
 import SittingOutPanel from "../components/SittingOutPanel";
 import Footer from "../components/Footer";
+import BoardServerControl from "../components/BoardServerControl";
 import PlayArrowIcon from "@mui/icons-material/PlayArrow";
 import PauseIcon from "@mui/icons-material/Pause";
 import AddIcon from "@mui/icons-material/Add";
@@ -21,13 +22,18 @@ interface Player {
   first_name: string;
   last_name: string;
   skill_level: number;
+  gender?: "Male" | "Female";
   club_id: number;
   sit_out_count: number;
+  rating?: number | null;
 }
 
 interface Game {
+  id: number | null;
   court: number;
-  players: Player[];
+  team_a: Player[];
+  team_b: Player[];
+  skill_gap: number;
 }
 
 interface GamesRound {
@@ -71,8 +77,25 @@ const Lobby: React.FC<LobbyProps> = ({ initialAvailableCourts = 4 }) => {
     return () => window.clearInterval(timerId);
   }, [isRunning]);
 
-  const addMinute = () => setRemainingSeconds((prev) => prev + 60);
-  const removeMinute = () => setRemainingSeconds((prev) => Math.max(0, prev - 60));
+  // Phones on the court board count down by themselves, so only changes made here are sent
+  const syncBoardTimer = (seconds: number, running: boolean) => {
+    invoke("set_board_timer", { remainingSeconds: seconds, running }).catch((e) =>
+      console.error("Failed to update the court board timer:", e)
+    );
+  };
+
+  const addMinute = () => {
+    setRemainingSeconds(remainingSeconds + 60);
+    syncBoardTimer(remainingSeconds + 60, isRunning);
+  };
+  const removeMinute = () => {
+    setRemainingSeconds(Math.max(0, remainingSeconds - 60));
+    syncBoardTimer(Math.max(0, remainingSeconds - 60), isRunning);
+  };
+  const toggleTimer = () => {
+    setIsRunning(!isRunning);
+    syncBoardTimer(remainingSeconds, !isRunning);
+  };
 
   const minutes = String(Math.floor(remainingSeconds / 60)).padStart(2, "0");
   const seconds = String(remainingSeconds % 60).padStart(2, "0");
@@ -91,7 +114,9 @@ const Lobby: React.FC<LobbyProps> = ({ initialAvailableCourts = 4 }) => {
           first_name: p.first_name,
           last_name: p.last_name,
           skill_level: p.skill_level,
+          gender: p.gender ?? "Male",
           sit_out_count: p.sit_out_count ?? 0,
+          rating: p.rating ?? null,
         })),
         numCourts: numCourts,
         previous_sit_out:
@@ -101,7 +126,9 @@ const Lobby: React.FC<LobbyProps> = ({ initialAvailableCourts = 4 }) => {
                 first_name: p.first_name,
                 last_name: p.last_name,
                 skill_level: p.skill_level,
+                gender: p.gender ?? "Male",
                 sit_out_count: p.sit_out_count ?? 0,
+                rating: p.rating ?? null,
               }))
             : null,
       });
@@ -165,7 +192,7 @@ const Lobby: React.FC<LobbyProps> = ({ initialAvailableCourts = 4 }) => {
           </IconButton>
         </Box>
 
-        <Box />
+        <BoardServerControl />
       </Box>
 
       <Grid container spacing={2} sx={{ flex: 1 }}>
@@ -198,7 +225,7 @@ const Lobby: React.FC<LobbyProps> = ({ initialAvailableCourts = 4 }) => {
 
       <Fab
         color="primary"
-        onClick={() => setIsRunning((prev) => !prev)}
+        onClick={toggleTimer}
         sx={{ position: "fixed", right: 24, bottom: 24 }}
         aria-label={isRunning ? "pause timer" : "play timer"}
         >
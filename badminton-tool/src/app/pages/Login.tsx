@@ -0,0 +1,90 @@
+import { Alert, Box, Button, Paper, TextField, Typography } from "@mui/material";
+import React, { useState } from "react";
+import { useNavigate } from "react-router-dom";
+import { login, register } from "../auth";
+import { errorMessage, isAppError } from "../errors";
+
+const Login: React.FC = () => {
+  const [username, setUsername] = useState("");
+  const [password, setPassword] = useState("");
+  const [error, setError] = useState<string | null>(null);
+  const [errorField, setErrorField] = useState<string | null>(null);
+  const [submitting, setSubmitting] = useState(false);
+  const navigate = useNavigate();
+
+  const submit = async (action: typeof login) => {
+    setError(null);
+    setErrorField(null);
+    setSubmitting(true);
+    try {
+      await action(username, password);
+      navigate("/");
+    } catch (err) {
+      setError(errorMessage(err));
+      setErrorField(isAppError(err) ? err.field : null);
+    } finally {
+      setSubmitting(false);
+    }
+  };
+
+  const handleSubmit = (e: React.FormEvent) => {
+    e.preventDefault();
+    submit(login);
+  };
+
+  return (
+    <Box
+      sx={{
+        width: "100vw",
+        height: "100vh",
+        display: "flex",
+        alignItems: "center",
+        justifyContent: "center",
+        p: 3,
+        boxSizing: "border-box",
+      }}
+    >
+      <Paper component="form" onSubmit={handleSubmit} sx={{ p: 3, width: 360 }}>
+        <Typography variant="h5" gutterBottom>
+          Log in
+        </Typography>
+        {error && (
+          <Alert severity="error" sx={{ mb: 2 }}>
+            {error}
+          </Alert>
+        )}
+        <TextField
+          label="Username"
+          value={username}
+          onChange={(e) => setUsername(e.target.value)}
+          error={errorField === "username"}
+          fullWidth
+          autoFocus
+          margin="normal"
+        />
+        <TextField
+          label="Password"
+          type="password"
+          value={password}
+          onChange={(e) => setPassword(e.target.value)}
+          error={errorField === "password"}
+          fullWidth
+          margin="normal"
+        />
+        <Box display="flex" justifyContent="space-between" mt={2}>
+          <Button onClick={() => navigate("/")}>Back</Button>
+          <Box display="flex" gap={1}>
+            <Button disabled={submitting} onClick={() => submit(register)}>
+              Create account
+            </Button>
+            <Button type="submit" variant="contained" disabled={submitting}>
+              Log in
+            </Button>
+          </Box>
+        </Box>
+      </Paper>
+    </Box>
+  );
+};
+
+export default Login;
@@ -9,6 +9,8 @@ import Footer from "../components/Footer";
 import MembersList from "../components/MembersList";
 import CreatePlayerDialog from "../components/CreatePlayerDialog";
 import { usePlayersContext, Player } from "../context/PlayersContext";
+import { errorMessage, needsLogin } from "../errors";
+import { loginToken } from "../auth";
 
 
 const Members: React.FC = () => {
@@ -68,7 +70,7 @@ const Members: React.FC = () => {
       });
       setPlayers(result);
     } catch (err) {
-      setError(err as string);
+      setError(errorMessage(err));
       console.error("Error loading players:", err);
     } finally {
       setLoading(false);
@@ -99,7 +101,7 @@ const Members: React.FC = () => {
         club_id: selectedClubId,
       };
 
-      await invoke("create_player", { request: playerRequest });
+      await invoke("create_player", { request: playerRequest, token: loginToken() });
 
       setNewPlayer({
         first_name: "",
@@ -111,7 +113,11 @@ const Members: React.FC = () => {
       setCreateDialogOpen(false);
       await loadPlayers();
     } catch (err) {
-      setError(err as string);
+      if (needsLogin(err)) {
+        navigate("/login");
+        return;
+      }
+      setError(errorMessage(err));
       console.error("Error creating player:", err);
     }
   };
@@ -121,11 +127,15 @@ const Members: React.FC = () => {
 
     setError(null);
     try {
-      await invoke("delete_player", { playerId });
+      await invoke("delete_player", { playerId, token: loginToken() });
       deselectPlayer(playerId);
       await loadPlayers();
     } catch (err) {
-      setError(err as string);
+      if (needsLogin(err)) {
+        navigate("/login");
+        return;
+      }
+      setError(errorMessage(err));
       console.error("Error deleting player:", err);
     }
   };